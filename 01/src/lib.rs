@@ -0,0 +1,75 @@
+//! Day 1: Calorie Counting, finding the elves that carry the most calories
+
+use common::{
+    image::{Drawing, Rgb},
+    parse::{parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+};
+
+/// Calories carried by each elve, in input order; elves are separated by empty lines
+pub fn get_calories_by_elve(input: &str) -> Result<Vec<u32>, ParseError> {
+    input.lines().try_fold(vec![0u32], |mut acc, line| {
+        if line.is_empty() {
+            acc.push(0);
+        } else {
+            let item: u32 = parse_token(input, line, "calories")?;
+            *acc.last_mut().unwrap() += item;
+        }
+        Ok(acc)
+    })
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+
+    type Params = ();
+    /// Calories carried by each elve, most first
+    type Input = Vec<u32>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        let mut calories = get_calories_by_elve(input)?;
+        calories.sort();
+        calories.reverse();
+        Ok(calories)
+    }
+
+    fn part1(calories: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(calories.first().cloned().unwrap_or(0))
+    }
+
+    fn part2(calories: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(calories.iter().take(3).sum())
+    }
+
+    /// Bars of the calories carried by each elve, the top three in red
+    fn draw(calories: &Self::Input) -> anyhow::Result<Drawing> {
+        let max = calories.first().copied().unwrap_or(0).max(1) as f64;
+        let height = (calories.len() as f64 / 2.0).max(1.0);
+        let mut drawing = Drawing::new(calories.len() as f64, height);
+        for (i, &c) in calories.iter().enumerate() {
+            let bar = c as f64 / max * height;
+            let color = if i < 3 { Rgb::RED } else { Rgb::BLUE };
+            drawing.rect(i as f64, height - bar, 1.0, bar, color);
+        }
+        Ok(drawing)
+    }
+
+    /// `size` elves carrying 1 to 15 items each
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let elves: Vec<String> = (0..size)
+            .map(|_| {
+                let items = rng.range(1..=15);
+                (0..items)
+                    .map(|_| format!("{}\n", rng.range(1000..=60000)))
+                    .collect()
+            })
+            .collect();
+        Ok(elves.join("\n"))
+    }
+}
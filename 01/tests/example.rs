@@ -0,0 +1,27 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day01::{get_calories_by_elve, Day01};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn calories_by_elve() {
+    assert_eq!(
+        get_calories_by_elve(&example()).unwrap(),
+        vec![6000, 4000, 11000, 24000, 10000]
+    );
+    assert!(get_calories_by_elve("100\nlots\n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let calories = Day01::parse(&example()).unwrap();
+    assert_eq!(Day01::part1(&calories).unwrap(), 24000);
+    assert_eq!(Day01::part2(&calories).unwrap(), 45000);
+}
@@ -0,0 +1,183 @@
+//! Day 2: Rock Paper Scissors, scoring the rounds of an encrypted strategy guide
+
+use common::{
+    image::{Drawing, Image, Rgb},
+    parse::{next_token, ParseError},
+    random::Rng,
+    solution::Solution,
+};
+use phf::phf_map;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Hand {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub their_hand: Hand,
+    pub my_hand: Hand,
+}
+
+static HAND_BY_CODE: phf::Map<&'static str, Hand> = phf_map! {
+    "A" => Hand::Rock, "B" => Hand::Paper, "C" => Hand::Scissors,
+    "X" => Hand::Rock, "Y" => Hand::Paper, "Z" => Hand::Scissors,
+};
+
+/// How a round ends for me
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+static OUTCOME_BY_CODE: phf::Map<&'static str, Outcome> = phf_map! {
+    "X" => Outcome::Loss, "Y" => Outcome::Draw, "Z" => Outcome::Win,
+};
+
+/// One line of the strategy guide: the opponent's hand and the still ambiguous second
+/// column (X, Y or Z)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GuideLine {
+    their_hand: Hand,
+    code: &'static str,
+}
+
+/// Parses a line like `A Y`
+pub fn parse_guide_line(ln: &str) -> Result<GuideLine, ParseError> {
+    let mut sp = ln.split(' ');
+    let their_hand_code = next_token(ln, &mut sp, "opponent's hand")?;
+    let code = next_token(ln, &mut sp, "second column")?;
+    if let Some(extra) = sp.next() {
+        return Err(ParseError::at(ln, extra, "expected two columns"));
+    }
+    let their_hand = match their_hand_code {
+        "A" | "B" | "C" => HAND_BY_CODE[their_hand_code],
+        _ => {
+            return Err(ParseError::at(
+                ln,
+                their_hand_code,
+                format!("invalid hand '{their_hand_code}', expected A, B or C"),
+            ))
+        }
+    };
+    let (&code, _) = OUTCOME_BY_CODE.get_entry(code).ok_or_else(|| {
+        ParseError::at(
+            ln,
+            code,
+            format!("invalid code '{code}', expected X, Y or Z"),
+        )
+    })?;
+    Ok(GuideLine { their_hand, code })
+}
+
+fn shape_score(my_hand: Hand) -> u32 {
+    match my_hand {
+        Hand::Rock => 1,
+        Hand::Paper => 2,
+        Hand::Scissors => 3,
+    }
+}
+
+fn winning_hand_against(hand: Hand) -> Hand {
+    use Hand::*;
+    match hand {
+        Rock => Paper,
+        Paper => Scissors,
+        Scissors => Rock,
+    }
+}
+
+fn losing_hand_against(hand: Hand) -> Hand {
+    use Hand::*;
+    match hand {
+        Rock => Scissors,
+        Paper => Rock,
+        Scissors => Paper,
+    }
+}
+
+fn outcome_score(round: Round) -> u32 {
+    if round.my_hand == winning_hand_against(round.their_hand) {
+        return 6;
+    };
+    if round.my_hand == round.their_hand {
+        return 3;
+    };
+    0
+}
+
+/// The hand I need to play against `their_hand` for the given outcome
+pub fn hand_for_outcome(their_hand: Hand, outcome: Outcome) -> Hand {
+    match outcome {
+        Outcome::Loss => losing_hand_against(their_hand),
+        Outcome::Draw => their_hand,
+        Outcome::Win => winning_hand_against(their_hand),
+    }
+}
+
+/// My score over all rounds: the score of my hand plus that of the outcome, per round
+pub fn total_score(rounds: impl Iterator<Item = Round>) -> u32 {
+    rounds
+        .map(|round| shape_score(round.my_hand) + outcome_score(round))
+        .sum()
+}
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+
+    type Params = ();
+    type Input = Vec<GuideLine>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|ln| parse_guide_line(ln).map_err(|e| e.within(input, ln)))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(guide: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(total_score(guide.iter().map(|ln| Round {
+            their_hand: ln.their_hand,
+            my_hand: HAND_BY_CODE[ln.code],
+        })))
+    }
+
+    fn part2(guide: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(total_score(guide.iter().map(|ln| Round {
+            their_hand: ln.their_hand,
+            my_hand: hand_for_outcome(ln.their_hand, OUTCOME_BY_CODE[ln.code]),
+        })))
+    }
+
+    /// How often each opponent's hand (rows) meets each code (columns)
+    fn draw(guide: &Self::Input) -> anyhow::Result<Drawing> {
+        let mut counts = [[0usize; 3]; 3];
+        for ln in guide {
+            let code = (ln.code.as_bytes()[0] - b'X') as usize;
+            counts[ln.their_hand as usize][code] += 1;
+        }
+        let max = counts.iter().flatten().copied().max().unwrap_or(0).max(1);
+        let image =
+            Image::from_fn(3, 3, |x, y| Rgb::heat(counts[y][x] as f64 / max as f64));
+        Ok(image.into())
+    }
+
+    /// `size` rounds
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok((0..size)
+            .map(|_| {
+                let their_hand = rng.pick(&["A", "B", "C"]);
+                format!("{their_hand} {}\n", rng.pick(&["X", "Y", "Z"]))
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,44 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day02::{
+    hand_for_outcome, parse_guide_line, total_score, Day02, Hand, Outcome, Round,
+};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn scores_rounds() {
+    let round = |their_hand, my_hand| Round {
+        their_hand,
+        my_hand,
+    };
+    assert_eq!(total_score([round(Hand::Rock, Hand::Paper)].into_iter()), 8);
+    assert_eq!(total_score([round(Hand::Paper, Hand::Rock)].into_iter()), 1);
+    assert_eq!(
+        total_score([round(Hand::Scissors, Hand::Scissors)].into_iter()),
+        6
+    );
+    assert_eq!(hand_for_outcome(Hand::Rock, Outcome::Win), Hand::Paper);
+    assert_eq!(hand_for_outcome(Hand::Paper, Outcome::Loss), Hand::Rock);
+}
+
+#[test]
+fn rejects_invalid_lines() {
+    assert!(parse_guide_line("A Y").is_ok());
+    assert!(parse_guide_line("D Y").is_err());
+    assert!(parse_guide_line("A W").is_err());
+    assert!(parse_guide_line("A Y Z").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let guide = Day02::parse(&example()).unwrap();
+    assert_eq!(Day02::part1(&guide).unwrap(), 15);
+    assert_eq!(Day02::part2(&guide).unwrap(), 12);
+}
@@ -0,0 +1,211 @@
+//! Day 3: Rucksack Reorganization, finding the item types elves packed twice
+
+use anyhow::anyhow;
+use common::{
+    bitset::BitSet,
+    image::{Drawing, Image, Rgb},
+    parse::ParseError,
+    random::Rng,
+    solution::Solution,
+};
+
+/// Item types of `items`, which must be ASCII, by their character code
+fn item_types(items: &str) -> BitSet<2> {
+    items.bytes().map(usize::from).collect()
+}
+
+/// The item type in both compartments (halves) of the rucksack, the first one if there
+/// are several
+pub fn find_duplicate_item_type(rucksack: &str) -> Option<char> {
+    let (compartment1, compartment2) = rucksack.split_at(rucksack.len() / 2);
+    let common = item_types(compartment1) & &item_types(compartment2);
+    common
+        .iter()
+        .next()
+        .map(|item_type| item_type as u8 as char)
+}
+
+/// 1 to 26 for `a` to `z`, 27 to 52 for `A` to `Z`
+pub fn get_item_type_priority(item_type: char) -> Result<u32, String> {
+    if item_type.is_ascii_lowercase() {
+        return Ok(item_type as u32 - 'a' as u32 + 1);
+    }
+    if item_type.is_ascii_uppercase() {
+        return Ok(item_type as u32 - 'A' as u32 + 27);
+    }
+    Err(format!(
+        "Could not determine priority for item type {item_type}"
+    ))
+}
+
+/// The item type all three rucksacks of a group have in common
+pub fn get_badge_item(group: &[&str; 3]) -> Option<char> {
+    let common = group
+        .iter()
+        .map(|rucksack| item_types(rucksack))
+        .reduce(|acc, types| acc & &types)?;
+    common
+        .iter()
+        .next()
+        .map(|item_type| item_type as u8 as char)
+}
+
+/// The rucksacks, one per line, checking that they hold an even number of letters
+pub fn parse_rucksacks(input: &str) -> Result<Vec<String>, ParseError> {
+    input
+        .lines()
+        .map(|ln| {
+            if let Some((pos, item_type)) =
+                ln.char_indices().find(|(_, c)| !c.is_ascii_alphabetic())
+            {
+                let span = &ln[pos..pos + item_type.len_utf8()];
+                return Err(ParseError::at(
+                    input,
+                    span,
+                    format!("invalid item type '{item_type}'"),
+                ));
+            }
+            if ln.len() % 2 != 0 {
+                return Err(ParseError::at(
+                    input,
+                    ln,
+                    "rucksack has an odd number of items",
+                ));
+            }
+            Ok(ln.to_owned())
+        })
+        .collect()
+}
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+
+    type Params = ();
+    type Input = Vec<String>;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_rucksacks(input)?)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        rucksacks
+            .iter()
+            .map(|ln| {
+                let item_type = find_duplicate_item_type(ln).ok_or_else(|| {
+                    anyhow!("No item type is in both compartments of {ln}")
+                })?;
+                get_item_type_priority(item_type).map_err(|e| anyhow!(e))
+            })
+            .sum()
+    }
+
+    fn part2(rucksacks: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        rucksacks
+            .chunks(3)
+            .map(|group| {
+                let [a, b, c] = group else {
+                    return Err(anyhow!("The last group has fewer than three elves"));
+                };
+                let badge = get_badge_item(&[a, b, c])
+                    .ok_or_else(|| anyhow!("The group of {a} has no badge"))?;
+                get_item_type_priority(badge).map_err(|e| anyhow!(e))
+            })
+            .sum()
+    }
+
+    /// The item types of each rucksack by priority; blue in the first compartment, green
+    /// in the second and red in both
+    fn draw(rucksacks: &Self::Input) -> anyhow::Result<Drawing> {
+        let mut image = Image::new(52, rucksacks.len(), Rgb::WHITE);
+        for (y, rucksack) in rucksacks.iter().enumerate() {
+            let (compartment1, compartment2) = rucksack.split_at(rucksack.len() / 2);
+            let (types1, types2) = (item_types(compartment1), item_types(compartment2));
+            for ch in rucksack.chars() {
+                let color =
+                    match (types1.contains(ch as usize), types2.contains(ch as usize)) {
+                        (true, true) => Rgb::RED,
+                        (true, false) => Rgb::BLUE,
+                        _ => Rgb::GREEN,
+                    };
+                let x = get_item_type_priority(ch).map_err(|e| anyhow!(e))? as i64 - 1;
+                image.set(x, y as i64, color);
+            }
+        }
+        Ok(image.into())
+    }
+
+    /// `size` groups of three rucksacks with 3 to 24 items per compartment
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let mut item_types: Vec<u8> = (b'a'..=b'z').chain(b'A'..=b'Z').collect();
+        let mut result = String::new();
+        for _ in 0..size {
+            rng.shuffle(&mut item_types);
+            let (&badge, rest) = item_types.split_first().unwrap();
+            // every compartment of the group draws from its own 8 item types, so that
+            // only the badge and the misplaced items are shared
+            for pools in rest.chunks(16).take(3) {
+                let len = rng.range(3..=24) as usize;
+                let mut compartments = [&pools[..8], &pools[8..]]
+                    .map(|pool| (0..len).map(|_| *rng.pick(pool)).collect::<Vec<_>>());
+                let misplaced = compartments[0][0];
+                compartments[1][0] = misplaced;
+                compartments[rng.index(2)][1] = badge;
+                for compartment in &mut compartments {
+                    rng.shuffle(compartment);
+                    result.extend(compartment.iter().map(|&b| b as char));
+                }
+                result.push('\n');
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_duplicate_item_types() {
+        let rucksacks = vec![
+            ("vJrwpWtwJgWrhcsFMMfFFhFp", 'p'),
+            ("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL", 'L'),
+            ("PmmdzqPrVvPwwTWBwg", 'P'),
+            ("wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn", 'v'),
+            ("ttgJtRGJQctTZtZT", 't'),
+            ("CrZsJsPPZsGzwwsLwLmpwMDw", 's'),
+        ];
+        for (rucksack, dup_item) in rucksacks {
+            assert_eq!(find_duplicate_item_type(rucksack), Some(dup_item));
+        }
+    }
+
+    #[test]
+    fn parse_rucksacks_reports_invalid_item_types() {
+        let err = parse_rucksacks("abcd\nab1d\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 3));
+        assert_eq!(err.offending_text(), "1");
+        let err = parse_rucksacks("abc\n").unwrap_err();
+        assert_eq!(err.message(), "rucksack has an odd number of items");
+    }
+
+    #[test]
+    fn get_item_type_priorities() {
+        let types = vec![
+            ('a', 1),
+            ('e', 5),
+            ('z', 26),
+            ('A', 27),
+            ('X', 50),
+            ('Z', 52),
+        ];
+        for (item_type, priority) in types {
+            assert_eq!(get_item_type_priority(item_type).unwrap(), priority);
+        }
+    }
+}
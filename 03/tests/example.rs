@@ -0,0 +1,38 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day03::{find_duplicate_item_type, get_badge_item, Day03};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn finds_shared_item_types() {
+    assert_eq!(find_duplicate_item_type("abcB"), None);
+    assert_eq!(find_duplicate_item_type("abca"), Some('a'));
+    let group = [
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+        "PmmdzqPrVvPwwTWBwg",
+    ];
+    assert_eq!(get_badge_item(&group), Some('r'));
+    assert_eq!(get_badge_item(&["ab", "cd", "ef"]), None);
+}
+
+#[test]
+fn reports_missing_duplicates_and_badges() {
+    let rucksacks = Day03::parse("abcd\n").unwrap();
+    assert!(Day03::part1(&rucksacks).is_err());
+    assert!(Day03::part2(&rucksacks).is_err());
+}
+
+#[test]
+fn example_answers() {
+    let rucksacks = Day03::parse(&example()).unwrap();
+    assert_eq!(Day03::part1(&rucksacks).unwrap(), 157);
+    assert_eq!(Day03::part2(&rucksacks).unwrap(), 70);
+}
@@ -0,0 +1,219 @@
+//! Day 4: Camp Cleanup, finding elves whose section assignments overlap
+
+use std::str::FromStr;
+
+use common::{
+    image::{Drawing, Image, Rgb},
+    interval::Interval,
+    parse::{parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+};
+
+/// The sections an elve has to clean up, e.g., `2-4`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SectionRange(Interval<u32>);
+
+/// The section ranges of two elves, e.g., `2-4,6-8`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ElvePair(SectionRange, SectionRange);
+
+impl SectionRange {
+    /// Sections `start` to `end`, both included
+    pub fn new(start: u32, end: u32) -> Self {
+        Self(Interval::closed(start, end))
+    }
+
+    #[must_use]
+    pub fn fully_contains(&self, other: &Self) -> bool {
+        self.0.contains_interval(&other.0)
+    }
+
+    #[must_use]
+    pub fn overlaps_with(&self, other: &Self) -> bool {
+        self.0.overlaps(&other.0)
+    }
+}
+
+impl FromStr for SectionRange {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once('-').ok_or_else(|| {
+            ParseError::at(s, s, "expected range in format start-end, e.g., 7-15")
+        })?;
+        let range = SectionRange::new(
+            parse_token(s, start, "start index")?,
+            parse_token(s, end, "end index")?,
+        );
+        match range.0.is_empty() {
+            true => Err(ParseError::at(s, s, "range ends before it starts")),
+            false => Ok(range),
+        }
+    }
+}
+
+impl ElvePair {
+    #[must_use]
+    pub fn one_section_range_contains_the_other(&self) -> bool {
+        self.0.fully_contains(&self.1) || self.1.fully_contains(&self.0)
+    }
+
+    #[must_use]
+    pub fn section_ranges_overlap(&self) -> bool {
+        self.0.overlaps_with(&self.1)
+    }
+}
+
+impl FromStr for ElvePair {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r0, r1) = s.split_once(',').ok_or_else(|| {
+            ParseError::at(s, s, "expected exactly two ranges separated by comma")
+        })?;
+        Ok(ElvePair(
+            SectionRange::from_str(r0).map_err(|e| e.within(s, r0))?,
+            SectionRange::from_str(r1).map_err(|e| e.within(s, r1))?,
+        ))
+    }
+}
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const TITLE: &'static str = "Camp Cleanup";
+
+    type Params = ();
+    type Input = Vec<ElvePair>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|ln| ElvePair::from_str(ln).map_err(|e| e.within(input, ln)))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(pairs: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(pairs
+            .iter()
+            .filter(|pair| pair.one_section_range_contains_the_other())
+            .count())
+    }
+
+    fn part2(pairs: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(pairs
+            .iter()
+            .filter(|pair| pair.section_ranges_overlap())
+            .count())
+    }
+
+    /// One row per pair with its sections; blue for the first elve, green for the second
+    /// and red for both
+    fn draw(pairs: &Self::Input) -> anyhow::Result<Drawing> {
+        let width = pairs
+            .iter()
+            .map(|pair| std::cmp::max(pair.0 .0.end(), pair.1 .0.end()))
+            .max()
+            .unwrap_or(0);
+        let image = Image::from_fn(width as usize, pairs.len(), |x, y| {
+            let (first, second) = (pairs[y].0 .0, pairs[y].1 .0);
+            match (first.contains(x as u32), second.contains(x as u32)) {
+                (true, true) => Rgb::RED,
+                (true, false) => Rgb::BLUE,
+                (false, true) => Rgb::GREEN,
+                (false, false) => Rgb::WHITE,
+            }
+        });
+        Ok(image.into())
+    }
+
+    /// `size` pairs of section ranges within sections 1 to 99
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let mut range = || {
+            let start = rng.range(1..=99);
+            format!("{start}-{}", rng.range(start..=99))
+        };
+        Ok((0..size)
+            .map(|_| format!("{},{}\n", range(), range()))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn section_range_shoud_correctly_report_whether_it_fully_contains_another() {
+        let tests = vec![
+            (SectionRange::new(1, 2), SectionRange::new(2, 3), false),
+            (SectionRange::new(1, 4), SectionRange::new(2, 5), false),
+            (SectionRange::new(1, 4), SectionRange::new(2, 3), true),
+            (SectionRange::new(2, 3), SectionRange::new(1, 3), false),
+            (SectionRange::new(1, 2), SectionRange::new(1, 2), true),
+        ];
+        for (range1, range2, fully_contained) in tests {
+            assert_eq!(range1.fully_contains(&range2), fully_contained);
+        }
+    }
+
+    #[test]
+    fn elve_pairs_should_correctly_determine_whether_one_section_range_is_fully_contained_in_the_other(
+    ) {
+        let test_data = vec![
+            ("2-4,6-8", false),
+            ("2-3,4-5", false),
+            ("5-7,7-9", false),
+            ("2-8,3-7", true),
+            ("6-6,4-6", true),
+            ("2-6,4-8", false),
+        ];
+        for (pair_str, fully_contained) in test_data {
+            let pair = ElvePair::from_str(pair_str).unwrap();
+            assert_eq!(
+                pair.one_section_range_contains_the_other(),
+                fully_contained,
+                "{}, parsed as {:?}",
+                pair_str,
+                pair
+            );
+        }
+    }
+
+    #[test]
+    fn elve_pairs_should_correctly_determine_overlap() {
+        let test_data = vec![
+            ("2-4,6-8", false),
+            ("2-3,4-5", false),
+            ("5-7,7-9", true),
+            ("2-8,3-7", true),
+            ("6-6,4-6", true),
+            ("2-6,4-8", true),
+        ];
+        for (pair_str, overlap) in test_data {
+            let pair = ElvePair::from_str(pair_str).unwrap();
+            assert_eq!(
+                pair.section_ranges_overlap(),
+                overlap,
+                "{}, parsed as {:?}",
+                pair_str,
+                pair
+            );
+        }
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_offending_index() {
+        let err = Day04::parse("2-4,6-8\n2-3,4-x\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line(), err.column()), (2, 7));
+        assert_eq!(err.offending_text(), "x");
+        assert!(ElvePair::from_str("2-4").is_err());
+        assert!(ElvePair::from_str("2-4,5").is_err());
+        assert!(ElvePair::from_str("2-4,5-3").is_err());
+    }
+}
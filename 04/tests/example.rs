@@ -0,0 +1,35 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day04::{Day04, ElvePair, SectionRange};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn compares_section_ranges() {
+    assert!(SectionRange::new(2, 8).fully_contains(&SectionRange::new(3, 7)));
+    assert!(!SectionRange::new(3, 7).fully_contains(&SectionRange::new(2, 8)));
+    assert!(SectionRange::new(5, 7).overlaps_with(&SectionRange::new(7, 9)));
+    assert!(!SectionRange::new(2, 4).overlaps_with(&SectionRange::new(6, 8)));
+}
+
+#[test]
+fn parses_pairs() {
+    let pair: ElvePair = "6-6,4-6".parse().unwrap();
+    assert!(pair.one_section_range_contains_the_other());
+    assert!(pair.section_ranges_overlap());
+    assert!("6-4,1-2".parse::<ElvePair>().is_err());
+    assert!("1-2".parse::<ElvePair>().is_err());
+}
+
+#[test]
+fn example_answers() {
+    let pairs = Day04::parse(&example()).unwrap();
+    assert_eq!(Day04::part1(&pairs).unwrap(), 2);
+    assert_eq!(Day04::part2(&pairs).unwrap(), 4);
+}
@@ -0,0 +1,216 @@
+//! Day 5: Supply Stacks, following a crane's rearrangement procedure
+
+use anyhow::anyhow;
+use common::{
+    image::{Drawing, Image, Rgb},
+    parse::{expect_token, next_token, parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Move {
+    n: usize,
+    from: usize,
+    to: usize,
+}
+
+/// The stacks of crates, bottom first, and the moves to rearrange them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crates {
+    stacks: Vec<Vec<char>>,
+    procedure: Vec<Move>,
+}
+
+fn parse_move(ln: &str) -> Result<Move, ParseError> {
+    let mut words = ln.split_whitespace();
+    let mut field = |keyword: &str, what: &str| -> Result<usize, ParseError> {
+        expect_token(
+            ln,
+            next_token(ln, &mut words, &format!("'{keyword}'"))?,
+            keyword,
+        )?;
+        parse_token(ln, next_token(ln, &mut words, what)?, what)
+    };
+    let mov = Move {
+        n: field("move", "amount of crates")?,
+        from: field("from", "source stack index")?,
+        to: field("to", "target stack index")?,
+    };
+    match words.next() {
+        Some(extra) => Err(ParseError::at(ln, extra, "unexpected text after move")),
+        None => Ok(mov),
+    }
+}
+
+/// Parses the drawing of the stacks and, after an empty line, the moves
+pub fn parse_crates(input: &str) -> Result<Crates, ParseError> {
+    let (stacks, procedure) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::at_end(
+            input,
+            "expecting two paragraphs in input: intial stacks and rearrangement procedure",
+        )
+    })?;
+    let stacks = {
+        let mut result: Vec<Vec<char>> = vec![];
+        for line in stacks.lines().rev().skip(1) {
+            for (idx, crate_id) in line.chars().skip(1).step_by(4).enumerate() {
+                if result.len() <= idx {
+                    result.push(vec![]);
+                }
+                if crate_id == ' ' {
+                    continue;
+                }
+                result[idx].push(crate_id);
+            }
+        }
+        result
+    };
+    let procedure = procedure
+        .lines()
+        .map(|ln| parse_move(ln).map_err(|e| e.within(input, ln)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Crates { stacks, procedure })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CraneModel {
+    /// Moves one crate at a time
+    CrateMover9000,
+    /// Moves several crates at once, keeping their order
+    CrateMover9001,
+}
+
+/// Rearranges the crates with the given crane, returning the crates on top of the stacks
+pub fn rearrange(crates: &Crates, crane: CraneModel) -> anyhow::Result<String> {
+    let mut stacks = crates.stacks.clone();
+    for mov in &crates.procedure {
+        if mov.to == mov.from
+            || [mov.from, mov.to]
+                .iter()
+                .any(|&i| i == 0 || i > stacks.len())
+        {
+            return Err(anyhow!("Invalid move {mov:?}"));
+        }
+        let from = &stacks[mov.from - 1];
+        if from.len() < mov.n {
+            return Err(anyhow!("Not enough crates for move {mov:?}"));
+        }
+        let moved_crates = from[from.len() - mov.n..].to_owned();
+        match crane {
+            CraneModel::CrateMover9000 => moved_crates
+                .iter()
+                .rev()
+                .for_each(|&c| stacks[mov.to - 1].push(c)),
+            CraneModel::CrateMover9001 => moved_crates
+                .iter()
+                .for_each(|&c| stacks[mov.to - 1].push(c)),
+        }
+        let new_source_stack_len = stacks[mov.from - 1].len() - mov.n;
+        stacks[mov.from - 1].resize(new_source_stack_len, 'X');
+    }
+    Ok(stacks
+        .iter()
+        .filter_map(|stack| stack.last())
+        .collect::<String>())
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const TITLE: &'static str = "Supply Stacks";
+
+    type Params = ();
+    type Input = Crates;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_crates(input)?)
+    }
+
+    fn part1(crates: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        rearrange(crates, CraneModel::CrateMover9000)
+    }
+
+    fn part2(crates: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        rearrange(crates, CraneModel::CrateMover9001)
+    }
+
+    /// The initial stacks, with a colour per crate label
+    fn draw(crates: &Self::Input) -> anyhow::Result<Drawing> {
+        let height = crates.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let width = (2 * crates.stacks.len()).saturating_sub(1);
+        let mut image = Image::new(width, height, Rgb::WHITE);
+        for (i, stack) in crates.stacks.iter().enumerate() {
+            for (j, &label) in stack.iter().enumerate() {
+                let color = Rgb::categorical(label as usize);
+                image.set(2 * i as i64, (height - 1 - j) as i64, color);
+            }
+        }
+        Ok(image.into())
+    }
+
+    /// Up to nine stacks and `size` moves
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let mut stacks: Vec<Vec<char>> = (0..rng.range(2..=9))
+            .map(|_| {
+                (0..rng.range(1..=8))
+                    .map(|_| rng.range('A' as i64..='Z' as i64) as u8 as char)
+                    .collect()
+            })
+            .collect();
+        let height = stacks.iter().map(Vec::len).max().unwrap();
+        let mut result = String::new();
+        for level in (0..height).rev() {
+            let row: Vec<String> = stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => format!("[{label}]"),
+                    None => "   ".to_owned(),
+                })
+                .collect();
+            result += &(row.join(" ") + "\n");
+        }
+        let numbers: Vec<String> = (1..=stacks.len()).map(|i| format!(" {i} ")).collect();
+        result += &(numbers.join(" ") + "\n\n");
+        for _ in 0..size {
+            let non_empty: Vec<usize> = (0..stacks.len())
+                .filter(|&i| !stacks[i].is_empty())
+                .collect();
+            let from = *rng.pick(&non_empty);
+            let to = (from + 1 + rng.index(stacks.len() - 1)) % stacks.len();
+            let len = stacks[from].len();
+            let n = rng.range(1..=len as i64) as usize;
+            let moved = stacks[from].split_off(len - n);
+            stacks[to].extend(moved.into_iter().rev());
+            result += &format!("move {n} from {} to {}\n", from + 1, to + 1);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_move_reports_location() {
+        assert_eq!(
+            parse_move("move 3 from 1 to 2").unwrap(),
+            Move {
+                n: 3,
+                from: 1,
+                to: 2
+            }
+        );
+        let err = parse_crates("[A]\n 1 \n\nmove 1 from 1 to 1\nmove x from 1 to 2\n")
+            .unwrap_err();
+        assert_eq!((err.line(), err.column()), (5, 6));
+        assert_eq!(err.offending_text(), "x");
+        let err = parse_move("move 3 from 1").unwrap_err();
+        assert_eq!((err.column(), err.message()), (14, "missing 'to'"));
+        assert!(parse_move("move 3 to 1 from 2").is_err());
+    }
+}
@@ -0,0 +1,35 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day05::{parse_crates, rearrange, CraneModel, Day05};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn cranes_keep_or_reverse_the_order() {
+    let crates =
+        parse_crates("[A]    \n[B]    \n 1   2 \n\nmove 2 from 1 to 2\n").unwrap();
+    assert_eq!(rearrange(&crates, CraneModel::CrateMover9000).unwrap(), "B");
+    assert_eq!(rearrange(&crates, CraneModel::CrateMover9001).unwrap(), "A");
+}
+
+#[test]
+fn rejects_impossible_moves() {
+    let crates = parse_crates("[A]    \n 1   2 \n\nmove 2 from 1 to 2\n").unwrap();
+    assert!(rearrange(&crates, CraneModel::CrateMover9000).is_err());
+    let crates = parse_crates("[A]    \n 1   2 \n\nmove 1 from 1 to 3\n").unwrap();
+    assert!(rearrange(&crates, CraneModel::CrateMover9000).is_err());
+    assert!(parse_crates("[A]\n 1 \n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let crates = Day05::parse(&example()).unwrap();
+    assert_eq!(Day05::part1(&crates).unwrap(), "CMZ");
+    assert_eq!(Day05::part2(&crates).unwrap(), "MCD");
+}
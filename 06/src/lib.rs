@@ -0,0 +1,124 @@
+//! Day 6: Tuning Trouble, finding markers in a datastream
+
+use common::{
+    image::{Drawing, Image, Rgb},
+    random::Rng,
+    solution::Solution,
+};
+
+/// Number of characters read until the last `marker_len` characters were all different,
+/// 0 if that never happens
+pub fn find_marker(signal: &str, marker_len: usize) -> usize {
+    signal
+        .as_bytes()
+        .windows(marker_len)
+        .enumerate()
+        .find_map(|(n, window)| {
+            if window
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+                == marker_len
+            {
+                Some(n + marker_len)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(0)
+}
+
+/// Characters read until the start-of-packet marker, 4 different characters
+pub fn find_sop_marker(signal: &str) -> usize {
+    find_marker(signal, 4)
+}
+
+/// Characters read until the start-of-message marker, 14 different characters
+pub fn find_som_marker(signal: &str) -> usize {
+    find_marker(signal, 14)
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const TITLE: &'static str = "Tuning Trouble";
+
+    type Params = ();
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input.trim_end().to_owned())
+    }
+
+    fn part1(signal: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(find_sop_marker(signal))
+    }
+
+    fn part2(signal: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(find_som_marker(signal))
+    }
+
+    /// The datastream in rows of 64 characters, with a colour per character; the
+    /// start-of-packet marker in white and the start-of-message marker in black
+    fn draw(signal: &Self::Input) -> anyhow::Result<Drawing> {
+        const WIDTH: usize = 64;
+        let bytes = signal.as_bytes();
+        let (sop, som) = (find_sop_marker(signal), find_som_marker(signal));
+        let image = Image::from_fn(WIDTH, bytes.len().div_ceil(WIDTH), |x, y| {
+            let pos = y * WIDTH + x;
+            match pos {
+                _ if pos >= bytes.len() => Rgb::WHITE,
+                _ if sop > 0 && (sop - 4..sop).contains(&pos) => Rgb::WHITE,
+                _ if som > 0 && (som - 14..som).contains(&pos) => Rgb::BLACK,
+                _ => Rgb::categorical(bytes[pos] as usize),
+            }
+        });
+        Ok(image.into())
+    }
+
+    /// A datastream of `size` characters, at least 14; before its start-of-message marker
+    /// it only uses 13 letters
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        const MARKER_LEN: usize = 14;
+        let mut letters: Vec<char> = ('a'..='z').collect();
+        rng.shuffle(&mut letters);
+        let size = size.max(MARKER_LEN);
+        let marker_start = rng.index(size - MARKER_LEN + 1);
+        let mut signal: String = (0..marker_start)
+            .map(|_| *rng.pick(&letters[..MARKER_LEN - 1]))
+            .collect();
+        // the 13 letters and then a new one, so that no earlier window qualifies
+        let mut marker = letters[..MARKER_LEN - 1].to_vec();
+        rng.shuffle(&mut marker);
+        signal.extend(marker);
+        signal.push(letters[MARKER_LEN - 1]);
+        signal.extend((signal.len()..size).map(|_| *rng.pick(&letters)));
+        Ok(signal + "\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_sop_marker_examples() {
+        assert_eq!(find_sop_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), 7);
+        assert_eq!(find_sop_marker("bvwbjplbgvbhsrlpgdmjqwftvncz"), 5);
+        assert_eq!(find_sop_marker("nppdvjthqldpwncqszvftbrmjlhg"), 6);
+        assert_eq!(find_sop_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 10);
+        assert_eq!(find_sop_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 11);
+    }
+
+    #[test]
+    fn find_som_marker_examples() {
+        assert_eq!(find_som_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb"), 19);
+        assert_eq!(find_som_marker("bvwbjplbgvbhsrlpgdmjqwftvncz"), 23);
+        assert_eq!(find_som_marker("nppdvjthqldpwncqszvftbrmjlhg"), 23);
+        assert_eq!(find_som_marker("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg"), 29);
+        assert_eq!(find_som_marker("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw"), 26);
+    }
+}
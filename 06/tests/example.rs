@@ -0,0 +1,26 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day06::{find_marker, find_som_marker, find_sop_marker, Day06};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn finds_markers() {
+    assert_eq!(find_marker("aab", 2), 3);
+    assert_eq!(find_marker("aaaa", 2), 0);
+    assert_eq!(find_sop_marker("abc"), 0);
+    assert_eq!(find_som_marker("abcdefghijklmn"), 14);
+}
+
+#[test]
+fn example_answers() {
+    let signal = Day06::parse(&example()).unwrap();
+    assert_eq!(Day06::part1(&signal).unwrap(), 7);
+    assert_eq!(Day06::part2(&signal).unwrap(), 19);
+}
@@ -0,0 +1,577 @@
+//! Day 7: No Space Left On Device, sizing directories from a terminal session
+
+use std::collections::{btree_map, BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context};
+use common::{
+    image::{Drawing, Rgb},
+    params,
+    parse::{parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+};
+
+//. #[derive(Debug, PartialEq)]
+//. struct File {
+//.     name: String,
+//.     size: u64,
+//. }
+
+enum Command<'a> {
+    Cd(&'a str),
+    Ls,
+}
+
+fn parse_command(line: &str) -> Option<Command<'_>> {
+    const CMD_CD: &str = "$ cd ";
+    const CMD_LS: &str = "$ ls";
+    if let Some(arg) = line.strip_prefix(CMD_CD) {
+        return Some(Command::Cd(arg));
+    }
+    if line.starts_with(CMD_LS) {
+        return Some(Command::Ls);
+    }
+    None
+}
+
+enum LsOutputLine<'a> {
+    Dir(&'a str),
+    File(u64, &'a str),
+}
+
+fn parse_ls_output_line(s: &str) -> Result<LsOutputLine<'_>, ParseError> {
+    let (left, name) = s.split_once(' ').ok_or_else(|| {
+        ParseError::at(s, s, "expected 'dir <name>' or '<size> <name>'")
+    })?;
+    if left == "dir" {
+        return Ok(LsOutputLine::Dir(name));
+    }
+    let size: u64 = parse_token(s, left, "file size")?;
+    Ok(LsOutputLine::File(size, name))
+}
+
+/// A directory and the total size of the files in it and its subdirectories
+#[derive(Clone, Debug, PartialEq)]
+pub struct Directory {
+    path: String,
+    size: u64,
+}
+
+impl Directory {
+    /// Absolute path, e.g., `/a/e`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Returns `None` when trying to go up from outside of any directory
+fn ls(current_dir: &str, arg: &str) -> Option<String> {
+    if arg.starts_with('/') {
+        Some(arg.to_owned()) // assume normalized
+    } else if arg == ".." {
+        let (left, _) = current_dir.rsplit_once('/')?;
+        if left.is_empty() {
+            Some("/".to_owned())
+        } else {
+            Some(left.to_owned())
+        }
+    } else if current_dir.ends_with('/') {
+        Some(format!("{current_dir}{arg}"))
+    } else {
+        Some(format!("{current_dir}/{arg}"))
+    }
+}
+
+/// The directories visited in a terminal session of `cd` and `ls` commands, largest
+/// first
+pub fn read_term_output(oup: &str) -> Result<Vec<Directory>, ParseError> {
+    let mut result = oup
+        .lines()
+        .try_fold(
+            (HashMap::new(), "".to_string(), false),
+            |(mut dirs, mut cwd, visiting_new_dir), ln| {
+                if let Some(cmd) = parse_command(ln) {
+                    match cmd {
+                        Command::Cd(arg) => {
+                            cwd = ls(&cwd, arg).ok_or_else(|| {
+                                ParseError::at(oup, ln, "cannot 'cd ..' before 'cd /'")
+                            })?;
+                            if dirs.contains_key(&cwd) {
+                                return Ok((dirs, cwd, false));
+                            } else {
+                                dirs.insert(
+                                    cwd.clone(),
+                                    Directory {
+                                        path: cwd.clone(),
+                                        size: 0,
+                                    },
+                                );
+                                return Ok((dirs, cwd, true));
+                            }
+                        }
+                        Command::Ls => {}
+                    }
+                    return Ok((dirs, cwd, visiting_new_dir));
+                }
+                if !visiting_new_dir {
+                    return Ok((dirs, cwd, false));
+                }
+                match parse_ls_output_line(ln).map_err(|e| e.within(oup, ln))? {
+                    LsOutputLine::File(size, _name) => {
+                        // meh...
+                        let mut tmp = cwd.clone();
+                        while let Some(dir) = dirs.get_mut(&tmp) {
+                            dir.size += size;
+                            if tmp == "/" {
+                                break;
+                            }
+                            tmp = ls(&tmp, "..").expect("parent of an absolute path");
+                        }
+                    }
+                    LsOutputLine::Dir(_name) => {}
+                }
+                Ok((dirs, cwd, true))
+            },
+        )?
+        .0
+        .values()
+        .cloned()
+        .collect::<Vec<_>>();
+    result.sort_by_key(|d| std::cmp::Reverse(d.size));
+    Ok(result)
+}
+
+/// Sizes given by the puzzle, the same for the example
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// Largest size of the directories summed up in part one
+    pub small_dir_limit: u64,
+    pub total_disk_space: u64,
+    /// Free space needed for the update in part two
+    pub required_disk_space: u64,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            small_dir_limit: 100_000,
+            total_disk_space: 70_000_000,
+            required_disk_space: 30_000_000,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "small_dir_limit" => self.small_dir_limit = params::parse_value(key, value)?,
+            "total_disk_space" => {
+                self.total_disk_space = params::parse_value(key, value)?
+            }
+            "required_disk_space" => {
+                self.required_disk_space = params::parse_value(key, value)?
+            }
+            _ => {
+                return Err(params::unknown(
+                    key,
+                    &["small_dir_limit", "total_disk_space", "required_disk_space"],
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The directories of a terminal session and the sizes to look for
+#[derive(Debug, Clone)]
+pub struct Disk {
+    /// Largest first
+    pub dirs: Vec<Directory>,
+    pub params: Params,
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const TITLE: &'static str = "No Space Left On Device";
+
+    type Params = Params;
+    type Input = Disk;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_with(input, &Params::default())
+    }
+
+    fn parse_with(input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Disk {
+            dirs: read_term_output(input)?,
+            params: params.clone(),
+        })
+    }
+
+    fn part1(disk: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(disk
+            .dirs
+            .iter()
+            .map(|d| d.size)
+            .filter(|&s| s <= disk.params.small_dir_limit)
+            .sum())
+    }
+
+    fn part2(disk: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        let Params {
+            total_disk_space,
+            required_disk_space,
+            ..
+        } = disk.params;
+        let used = disk.dirs.first().context("No directories")?.size;
+        let amount_to_delete =
+            (required_disk_space + used).saturating_sub(total_disk_space);
+        let dir_to_delete = disk
+            .dirs
+            .iter()
+            .rfind(|d| d.size >= amount_to_delete)
+            .ok_or(anyhow!("No directory is large enough"))?;
+        Ok(dir_to_delete.size)
+    }
+
+    /// Directory sizes on a logarithmic scale, largest first; the small directories of
+    /// part one in green and the directory to delete in red
+    fn draw(disk: &Self::Input) -> anyhow::Result<Drawing> {
+        let to_delete = Self::part2(disk)?;
+        let dirs = &disk.dirs;
+        let height = (dirs.len() as f64 / 2.0).max(1.0);
+        let max = dirs.first().map_or(1.0, |d| (d.size as f64).ln_1p());
+        let mut drawing = Drawing::new(dirs.len() as f64, height);
+        for (i, dir) in dirs.iter().enumerate() {
+            let bar = (dir.size as f64).ln_1p() / max * height;
+            let color = match dir.size {
+                s if s == to_delete => Rgb::RED,
+                s if s <= disk.params.small_dir_limit => Rgb::GREEN,
+                _ => Rgb::BLUE,
+            };
+            drawing.rect(i as f64, height - bar, 1.0, bar, color);
+        }
+        Ok(drawing)
+    }
+
+    /// A terminal session exploring all of `size` directories, with up to four files each
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        enum Entry {
+            Dir(usize),
+            File(u64),
+        }
+        fn name(rng: &mut Rng, extension: bool) -> String {
+            fn letters(rng: &mut Rng, n: i64) -> String {
+                (0..n)
+                    .map(|_| (b'a' + rng.below(26) as u8) as char)
+                    .collect()
+            }
+            let len = rng.range(1..=8);
+            let stem = letters(rng, len);
+            match extension {
+                true => format!("{stem}.{}", letters(rng, 3)),
+                false => stem,
+            }
+        }
+        fn add(rng: &mut Rng, dir: &mut BTreeMap<String, Entry>, entry: Entry) {
+            let is_file = matches!(entry, Entry::File(_));
+            let extension = is_file && rng.chance(0.5);
+            loop {
+                if let btree_map::Entry::Vacant(slot) = dir.entry(name(rng, extension)) {
+                    slot.insert(entry);
+                    return;
+                }
+            }
+        }
+        fn session(dirs: &[BTreeMap<String, Entry>], dir: usize, out: &mut String) {
+            *out += "$ ls\n";
+            for (name, entry) in &dirs[dir] {
+                *out += &match entry {
+                    Entry::Dir(_) => format!("dir {name}\n"),
+                    Entry::File(size) => format!("{size} {name}\n"),
+                };
+            }
+            for (name, entry) in &dirs[dir] {
+                if let Entry::Dir(sub) = entry {
+                    *out += &format!("$ cd {name}\n");
+                    session(dirs, *sub, out);
+                    *out += "$ cd ..\n";
+                }
+            }
+        }
+        let mut dirs = vec![BTreeMap::new()];
+        for dir in 1..size.max(1) {
+            let parent = rng.index(dir);
+            dirs.push(BTreeMap::new());
+            add(rng, &mut dirs[parent], Entry::Dir(dir));
+        }
+        // file sizes are scaled so that the disk is fairly full, as in part two
+        let mut files = vec![];
+        for dir in 0..dirs.len() {
+            for _ in 0..rng.range(0..=4) {
+                files.push((dir, rng.range(1000..=330_000)));
+            }
+        }
+        let total: i64 = files.iter().map(|(_, weight)| weight).sum();
+        let used = rng.range(45_000_000..=65_000_000);
+        for (dir, weight) in files {
+            let size = (weight as f64 / total as f64 * used as f64) as u64;
+            add(rng, &mut dirs[dir], Entry::File(size.max(1)));
+        }
+        let mut result = "$ cd /\n".to_owned();
+        session(&dirs, 0, &mut result);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_term_output_empty_root() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls"
+            )
+            .unwrap(),
+            vec![Directory {
+                path: "/".to_string(),
+                size: 0
+            }]
+        );
+    }
+
+    #[test]
+    fn read_term_output_some_files_in_root() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 100 a"
+            )
+            .unwrap(),
+            vec![Directory {
+                path: "/".to_string(),
+                size: 111
+            }]
+        );
+    }
+
+    #[test]
+    fn read_term_output_some_files_and_empty_dir_in_root() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 100 a\n\
+                 dir yy\n\
+                 $ cd yy\n\
+                 $ ls"
+            )
+            .unwrap(),
+            vec![
+                Directory {
+                    path: "/".to_string(),
+                    size: 111
+                },
+                Directory {
+                    path: "/yy".to_string(),
+                    size: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_term_output_cd_dotdot() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 100 a\n\
+                 dir yy\n\
+                 $ cd yy\n\
+                 $ ls\n\
+                 $ cd .."
+            )
+            .unwrap(),
+            vec![
+                Directory {
+                    path: "/".to_string(),
+                    size: 111
+                },
+                Directory {
+                    path: "/yy".to_string(),
+                    size: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_term_output_subdirs() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 dir yy\n\
+                 $ cd yy\n\
+                 $ ls\n\
+                 22 world\n\
+                 $ cd .."
+            )
+            .unwrap(),
+            vec![
+                Directory {
+                    path: "/".to_string(),
+                    size: 33
+                },
+                Directory {
+                    path: "/yy".to_string(),
+                    size: 22
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_term_output_visit_subdir_twice() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 dir yy\n\
+                 $ cd yy\n\
+                 $ ls\n\
+                 22 world\n\
+                 $ cd ..\n\
+                 $ cd yy\n\
+                 $ ls\n\
+                 22 world\n\
+                 "
+            )
+            .unwrap(),
+            vec![
+                Directory {
+                    path: "/".to_string(),
+                    size: 33
+                },
+                Directory {
+                    path: "/yy".to_string(),
+                    size: 22
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_term_output_r1() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 dir l1\n\
+                 $ cd l1\n\
+                 $ ls\n\
+                 dir l2\n\
+                 22 world\n\
+                 $ cd l2\n\
+                 $ ls\n\
+                 dir l3\n\
+                 22 world\n\
+                 $ cd l3\n\
+                 $ ls\n\
+                 33 b\n\
+                 "
+            )
+            .unwrap(),
+            vec![
+                Directory {
+                    path: "/".to_string(),
+                    size: 88
+                },
+                Directory {
+                    path: "/l1".to_string(),
+                    size: 77
+                },
+                Directory {
+                    path: "/l1/l2".to_string(),
+                    size: 55
+                },
+                Directory {
+                    path: "/l1/l2/l3".to_string(),
+                    size: 33
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_term_output_r2() {
+        assert_eq!(
+            read_term_output(
+                "$ cd /\n\
+                 $ ls\n\
+                 11 hello\n\
+                 dir l1\n\
+                 $ cd l1\n\
+                 $ ls\n\
+                 dir l2a\n\
+                 dir l2b\n\
+                 22 world\n\
+                 $ cd l2a\n\
+                 $ ls\n\
+                 22 world\n\
+                 $ cd ..\n\
+                 $ cd l2b\n\
+                 $ ls\n\
+                 33 b\n\
+                 "
+            )
+            .unwrap(),
+            vec![
+                Directory {
+                    path: "/".to_string(),
+                    size: 88
+                },
+                Directory {
+                    path: "/l1".to_string(),
+                    size: 77
+                },
+                Directory {
+                    path: "/l1/l2b".to_string(),
+                    size: 33
+                },
+                Directory {
+                    path: "/l1/l2a".to_string(),
+                    size: 22
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn read_term_output_reports_bad_lines() {
+        let err = read_term_output("$ cd /\n$ ls\n11 a\nbig b\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (4, 1));
+        assert_eq!(err.offending_text(), "big");
+        let err = read_term_output("$ cd ..\n").unwrap_err();
+        assert_eq!(err.line(), 1);
+    }
+}
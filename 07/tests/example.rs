@@ -0,0 +1,47 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day07::{read_term_output, Day07, Params};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn sizes_directories() {
+    let dirs = read_term_output(&example()).unwrap();
+    let sizes: Vec<_> = dirs.iter().map(|d| (d.path(), d.size())).collect();
+    assert_eq!(
+        sizes,
+        [
+            ("/", 48381165),
+            ("/d", 24933642),
+            ("/a", 94853),
+            ("/a/e", 584)
+        ]
+    );
+    assert!(read_term_output("$ cd ..\n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let dirs = Day07::parse(&example()).unwrap();
+    assert_eq!(Day07::part1(&dirs).unwrap(), 95437);
+    assert_eq!(Day07::part2(&dirs).unwrap(), 24933642);
+}
+
+#[test]
+fn answers_for_other_sizes() {
+    let params = Params {
+        small_dir_limit: 1000,
+        required_disk_space: 21_700_000,
+        ..Params::default()
+    };
+    let disk = Day07::parse_with(&example(), &params).unwrap();
+    assert_eq!(Day07::part1(&disk).unwrap(), 584);
+    // only 81165 need to be freed, /a is enough
+    assert_eq!(Day07::part2(&disk).unwrap(), 94853);
+}
@@ -0,0 +1,572 @@
+//! Day 8: Treetop Tree House, finding visible trees and the most scenic spot
+
+use common::{
+    image::{Drawing, Image, Rgb},
+    parse::ParseError,
+    random::Rng,
+    solution::Solution,
+    twod::{Grid, GridCell},
+};
+use itertools::Itertools;
+
+/// Height of a tree, 0 to 9
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TreeHeight(u8);
+
+impl GridCell for TreeHeight {
+    fn from_char(ch: char) -> Option<Self> {
+        ch.to_digit(10).map(|h| TreeHeight(h as u8))
+    }
+
+    fn to_char(&self) -> char {
+        (b'0' + self.0) as char
+    }
+}
+
+struct SideVisibility(Vec<bool>);
+
+impl SideVisibility {
+    fn new() -> Self {
+        Self(Vec::new())
+    }
+    fn update_from_line(&mut self, ln: &[TreeHeight]) {
+        self.0.resize(ln.len(), false);
+        self.0.fill(false);
+        self.mark_visibles_from_left(ln);
+        self.mark_visibles_from_right(ln);
+    }
+    fn mark_visibles_from_left(&mut self, ln: &[TreeHeight]) {
+        let mut tallest = -1;
+        for (height, flag) in ln.iter().map(|t| t.0 as i32).zip(self.0.iter_mut()) {
+            if height > tallest {
+                *flag = true;
+                tallest = height;
+            }
+        }
+    }
+    // FIXME: Deduplicate somehow
+    fn mark_visibles_from_right(&mut self, ln: &[TreeHeight]) {
+        let mut tallest = -1;
+        for (height, flag) in ln
+            .iter()
+            .rev()
+            .map(|t| t.0 as i32)
+            .zip(self.0.iter_mut().rev())
+        {
+            if height > tallest {
+                *flag = true;
+                tallest = height;
+            }
+        }
+    }
+    fn count(&self) -> usize {
+        self.0.iter().filter(|&&x| x).count()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TreeColumnAccumulatorP1 {
+    // left_view_blocked: bool,
+    tallest: i32,
+
+    // number of trees visible from top and/or bottom but not from the sides
+    visible_from_top: usize,
+
+    // flags indicating that there is a tree of the given height visible exclusively
+    // from below
+    visible_from_below_only: [bool; 10],
+}
+
+impl TreeColumnAccumulatorP1 {
+    fn new() -> Self {
+        Self {
+            tallest: -1,
+            visible_from_top: 0,
+            visible_from_below_only: [false; 10],
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.visible_from_top
+            + self.visible_from_below_only.iter().filter(|&&x| x).count()
+    }
+}
+
+/// Number of trees visible from outside the forest, i.e., taller than all trees between
+/// them and an edge
+pub fn count_visible(forest: &Grid<TreeHeight>) -> usize {
+    let mut col_acc = Vec::new();
+    col_acc.resize(forest.width(), TreeColumnAccumulatorP1::new());
+    let mut visible_from_sides_count = 0;
+    let mut sidevis = SideVisibility::new();
+    for ln in forest.rows() {
+        sidevis.update_from_line(ln);
+        visible_from_sides_count += sidevis.count();
+        for ((height, acc), visible_from_side) in ln
+            .iter()
+            .map(|t| t.0 as i32)
+            .zip(col_acc.iter_mut())
+            .zip(sidevis.0.iter())
+        {
+            for h in 0..=height {
+                acc.visible_from_below_only[h as usize] = false;
+            }
+            if !visible_from_side {
+                if acc.tallest < height {
+                    acc.visible_from_top += 1;
+                } else {
+                    acc.visible_from_below_only[height as usize] = true;
+                }
+            }
+            acc.tallest = std::cmp::max(acc.tallest, height);
+        }
+    }
+    visible_from_sides_count
+        + col_acc
+            .iter()
+            .map(TreeColumnAccumulatorP1::count)
+            .sum::<usize>()
+}
+
+/// The highest scenic score: the product of the viewing distances in all four directions
+pub fn find_most_scenic(forest: &Grid<TreeHeight>) -> usize {
+    let width = forest.width();
+    let heights = forest.data.iter().map(|t| t.0);
+    let left_up_scenic_scores = partial_scenic_score(heights.clone(), width);
+    let right_down_scenic_scores_rev = partial_scenic_score(heights.rev(), width);
+    left_up_scenic_scores
+        .iter()
+        .zip(right_down_scenic_scores_rev.iter().rev())
+        .fold(0, |acc, (lu, rd)| std::cmp::max(acc, lu * rd))
+}
+
+fn partial_scenic_score<I>(iter: I, width: usize) -> Vec<usize>
+where
+    I: Iterator<Item = u8>,
+{
+    let mut result = Vec::with_capacity(iter.size_hint().0);
+    let mut last_row_for_height = vec![[0; 10]; width];
+    result.resize(width, 0); // 1st row
+    for (row_idx, row) in iter.chunks(width).into_iter().enumerate().skip(1) {
+        let mut last_col_for_height = [0; 10];
+        result.push(0); // 1st col
+        for (col_idx, (t, last_row_for_height)) in
+            row.zip(last_row_for_height.iter_mut()).enumerate().skip(1)
+        {
+            let mut score = 1;
+            if row_idx > 0 {
+                score *= row_idx - last_row_for_height[t as usize];
+            }
+            if col_idx > 0 {
+                score *= col_idx - last_col_for_height[t as usize];
+            }
+            for h in 0..=t {
+                last_col_for_height[h as usize] = col_idx;
+            }
+            for h in 0..=t {
+                last_row_for_height[h as usize] = row_idx;
+            }
+            result.push(score);
+        }
+    }
+    result
+}
+
+/// A grid of tree heights, one digit per tree
+pub fn parse_forest(input: &str) -> Result<Grid<TreeHeight>, ParseError> {
+    let forest = Grid::parse(input)?;
+    if forest.width() == 0 {
+        return Err(ParseError::at_end(input, "empty forest"));
+    }
+    Ok(forest)
+}
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const TITLE: &'static str = "Treetop Tree House";
+
+    type Params = ();
+    type Input = Grid<TreeHeight>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_forest(input)?)
+    }
+
+    fn part1(forest: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(count_visible(forest))
+    }
+
+    fn part2(forest: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(find_most_scenic(forest))
+    }
+
+    /// The tree heights, from dark blue for 0 to yellow for 9
+    fn draw(forest: &Self::Input) -> anyhow::Result<Drawing> {
+        Ok(Image::from_grid(forest, |t| Rgb::heat(t.0 as f64 / 9.0)).into())
+    }
+
+    /// A square forest `size` trees wide, with taller trees towards the centre
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let centre = (size as f64 - 1.0) / 2.0;
+        Ok((0..size)
+            .flat_map(|y| {
+                let row: Vec<char> = (0..size)
+                    .map(|x| {
+                        let off_centre =
+                            (x as f64 - centre).abs().max((y as f64 - centre).abs());
+                        let base = 6.0 - 5.0 * off_centre / centre.max(1.0);
+                        let height = (base + rng.range(-3..=3) as f64).clamp(0.0, 9.0);
+                        char::from_digit(height as u32, 10).unwrap()
+                    })
+                    .collect();
+                row.into_iter().chain(['\n'])
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests_p1 {
+    use super::*;
+
+    fn forest(s: &str) -> Grid<TreeHeight> {
+        parse_forest(s).unwrap()
+    }
+
+    #[test]
+    fn parse_forest_reports_bad_trees() {
+        let err = parse_forest("303\n2x5\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 2));
+        let err = parse_forest("303\n25\n").unwrap_err();
+        assert_eq!(err.message(), "expected 3 cells in each row, found 2");
+    }
+
+    #[test]
+    fn test_input() {
+        assert_eq!(
+            count_visible(&forest(
+                "30373\n\
+                 25512\n\
+                 65332\n\
+                 33549\n\
+                 35390\n"
+            )),
+            21
+        );
+    }
+
+    #[test]
+    fn test_visible_from_below() {
+        assert_eq!(
+            count_visible(&forest(
+                "30373\n\
+                 25512\n\
+                 65332\n\
+                 33349\n\
+                 35290\n"
+            )),
+            22
+        );
+    }
+
+    #[test]
+    fn test_visible_from_below_and_side() {
+        assert_eq!(
+            count_visible(&forest(
+                "30373\n\
+                 25512\n\
+                 65332\n\
+                 33459\n\
+                 35290\n"
+            )),
+            22
+        );
+    }
+
+    #[test]
+    fn test_visible_variios() {
+        assert_eq!(
+            count_visible(&forest(
+                "00000\n\
+                 00000\n\
+                 00000\n\
+                 00000\n\
+                 00000\n"
+            )),
+            16
+        );
+        assert_eq!(
+            count_visible(&forest(
+                "10000\n\
+                 10000\n\
+                 10000\n\
+                 10000\n\
+                 10000\n"
+            )),
+            16
+        );
+        assert_eq!(
+            count_visible(&forest(
+                "12000\n\
+                 12000\n\
+                 12000\n\
+                 12000\n\
+                 12000\n"
+            )),
+            19
+        );
+        assert_eq!(
+            count_visible(&forest(
+                "12000\n\
+                 13300\n\
+                 13300\n\
+                 13300\n\
+                 12000\n"
+            )),
+            22
+        );
+        assert_eq!(
+            count_visible(&forest(
+                "12000\n\
+                 13300\n\
+                 13300\n\
+                 13322\n\
+                 12022\n"
+            )),
+            23
+        );
+        assert_eq!(
+            count_visible(&forest(
+                "555959\n\
+                 555969\n\
+                 555969\n\
+                 555989\n\
+                 555999\n"
+            )),
+            23
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_p2 {
+    use super::*;
+
+    fn forest(s: &str) -> Grid<TreeHeight> {
+        parse_forest(s).unwrap()
+    }
+
+    #[test]
+    fn test_input() {
+        assert_eq!(
+            find_most_scenic(&forest(
+                "30373\n\
+                 25512\n\
+                 65332\n\
+                 33549\n\
+                 35390\n"
+            )),
+            8
+        );
+    }
+
+    #[test]
+    fn test_various() {
+        assert_eq!(
+            find_most_scenic(&forest(
+                "11111\n\
+                 11111\n\
+                 11111\n\
+                 11111\n\
+                 11111\n"
+            )),
+            1
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "11111\n\
+                 11111\n\
+                 11211\n\
+                 11111\n\
+                 11111\n"
+            )),
+            16
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "11111\n\
+                 11111\n\
+                 13231\n\
+                 11111\n\
+                 11111\n"
+            )),
+            8
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "11111\n\
+                 77777\n\
+                 13231\n\
+                 11111\n\
+                 11111\n"
+            )),
+            4
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "11111\n\
+                 22222\n\
+                 33333\n\
+                 22222\n\
+                 11111\n"
+            )),
+            4
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "98989\n\
+                 88888\n\
+                 98789\n\
+                 88888\n\
+                 98989\n"
+            )),
+            2
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "12345\n\
+                 23456\n\
+                 34567\n\
+                 45678\n\
+                 56789\n"
+            )),
+            9
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "999999999\n\
+                 900000009\n\
+                 999999999\n"
+            )),
+            1
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "999999999\n\
+                 900010009\n\
+                 999999999\n"
+            )),
+            16
+        );
+        assert_eq!(
+            find_most_scenic(&forest(
+                "999999999\n\
+                 900001009\n\
+                 999999999\n"
+            )),
+            15
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests_reference {
+    use common::twod::Point;
+
+    use super::*;
+
+    const DIRECTIONS: [Point; 4] = [
+        Point::new(1, 0),
+        Point::new(-1, 0),
+        Point::new(0, 1),
+        Point::new(0, -1),
+    ];
+
+    /// The heights of the trees from `p` (exclusive) to the edge in direction `dir`
+    fn line_of_sight(forest: &Grid<TreeHeight>, p: Point, dir: Point) -> Vec<u8> {
+        std::iter::successors(Some(p + dir), |&q| Some(q + dir))
+            .map_while(|q| forest.get(q).map(|t| t.0))
+            .collect()
+    }
+
+    fn positions(forest: &Grid<TreeHeight>) -> impl Iterator<Item = Point> {
+        let (width, height) = (forest.width() as i32, forest.height() as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    fn naive_count_visible(forest: &Grid<TreeHeight>) -> usize {
+        positions(forest)
+            .filter(|&p| {
+                DIRECTIONS.iter().any(|&dir| {
+                    line_of_sight(forest, p, dir)
+                        .iter()
+                        .all(|&h| h < forest[p].0)
+                })
+            })
+            .count()
+    }
+
+    fn naive_find_most_scenic(forest: &Grid<TreeHeight>) -> usize {
+        positions(forest)
+            .map(|p| {
+                DIRECTIONS
+                    .iter()
+                    .map(|&dir| {
+                        let trees = line_of_sight(forest, p, dir);
+                        match trees.iter().position(|&h| h >= forest[p].0) {
+                            Some(blocking) => blocking + 1,
+                            None => trees.len(),
+                        }
+                    })
+                    .product()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn optimized_matches_naive_on_random_forests() {
+        let mut rng = Rng::new(8);
+        for _ in 0..500 {
+            let (width, height) = (rng.range(1..=9), rng.range(1..=9));
+            let max_height = rng.range(0..=9);
+            let input: String = (0..height)
+                .map(|_| {
+                    let row: String = (0..width)
+                        .map(|_| rng.range(0..=max_height).to_string())
+                        .collect();
+                    row + "\n"
+                })
+                .collect();
+            let forest = forest(&input);
+            assert_eq!(
+                count_visible(&forest),
+                naive_count_visible(&forest),
+                "{input}"
+            );
+            assert_eq!(
+                find_most_scenic(&forest),
+                naive_find_most_scenic(&forest),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn optimized_matches_naive_on_generated_inputs() {
+        let mut rng = Rng::new(8);
+        for size in 1..=20 {
+            let forest = forest(&Day08::generate(&mut rng, size).unwrap());
+            assert_eq!(count_visible(&forest), naive_count_visible(&forest));
+            assert_eq!(find_most_scenic(&forest), naive_find_most_scenic(&forest));
+        }
+    }
+
+    fn forest(s: &str) -> Grid<TreeHeight> {
+        parse_forest(s).unwrap()
+    }
+}
@@ -0,0 +1,29 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day08::{count_visible, find_most_scenic, parse_forest, Day08};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn small_forests() {
+    let forest = parse_forest("111\n121\n111\n").unwrap();
+    assert_eq!(count_visible(&forest), 9);
+    assert_eq!(find_most_scenic(&forest), 1);
+    let forest = parse_forest("222\n212\n222\n").unwrap();
+    assert_eq!(count_visible(&forest), 8);
+    assert!(parse_forest("12\n3x\n").is_err());
+    assert!(parse_forest("").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let forest = Day08::parse(&example()).unwrap();
+    assert_eq!(Day08::part1(&forest).unwrap(), 21);
+    assert_eq!(Day08::part2(&forest).unwrap(), 8);
+}
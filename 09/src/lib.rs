@@ -0,0 +1,156 @@
+//! Day 9: Rope Bridge, following the knots of a rope as its head moves
+
+pub mod ropebridge;
+
+use common::{
+    image::{Drawing, Image, Rgb},
+    parse::{parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+    twod::{Bounds, Direction, InfiniteGrid, Point},
+    viz::{self, Animator},
+};
+use ropebridge::*;
+use std::io::Write;
+
+fn parse_movement(ln: &str) -> Result<(Direction, usize), ParseError> {
+    let (d, c) = ln
+        .split_once(' ')
+        .ok_or_else(|| ParseError::at(ln, ln, "expected '<direction> <steps>'"))?;
+    let d = match d {
+        "L" => Direction::Left,
+        "R" => Direction::Right,
+        "U" => Direction::Up,
+        "D" => Direction::Down,
+        _ => {
+            return Err(ParseError::at(
+                ln,
+                d,
+                format!("invalid direction '{d}', expected L, R, U or D"),
+            ))
+        }
+    };
+    Ok((d, parse_token(ln, c, "number of steps")?))
+}
+
+/// Motions of the head, one per line like `R 4`
+pub fn parse_movements(input: &str) -> Result<Vec<(Direction, usize)>, ParseError> {
+    input
+        .lines()
+        .map(|ln| parse_movement(ln).map_err(|e| e.within(input, ln)))
+        .collect()
+}
+
+/// Number of positions the tail of a rope with `L` knots visits
+pub fn count_tail_positions<const L: usize>(movements: &[(Direction, usize)]) -> usize {
+    let mut bridge = RopeBridge::<L>::new();
+    for m in movements {
+        bridge.motion(m.0, m.1);
+    }
+    bridge.count_visited_positions()
+}
+
+fn animate_rope<const L: usize>(
+    movements: &[(Direction, usize)],
+    animator: &mut Animator<impl Write>,
+    caption: &str,
+) {
+    let mut bridge = RopeBridge::<L>::new();
+    for &(dir, count) in movements {
+        bridge.motion_with(dir, count, |bridge| animator.frame(bridge, caption));
+    }
+    let caption = format!("{caption}: {} positions", bridge.count_visited_positions());
+    animator.key_frame(&bridge, &caption);
+}
+
+/// Shows the ropes of both parts moving on the terminal
+pub fn animate(
+    movements: &[(Direction, usize)],
+    opts: &viz::Options,
+) -> anyhow::Result<()> {
+    let mut animator = Animator::stdout(opts.clone())?;
+    animate_rope::<2>(movements, &mut animator, "part 1");
+    animate_rope::<10>(movements, &mut animator, "part 2");
+    animator.finish()
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u8 = 9;
+    const TITLE: &'static str = "Rope Bridge";
+
+    type Params = ();
+    type Input = Vec<(Direction, usize)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_movements(input)?)
+    }
+
+    fn part1(movements: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(count_tail_positions::<2>(movements))
+    }
+
+    fn part2(movements: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        Ok(count_tail_positions::<10>(movements))
+    }
+
+    /// Positions visited by the tail of part one's rope in blue, of part two's in red and
+    /// of both in purple; the start in green
+    fn draw(movements: &Self::Input) -> anyhow::Result<Drawing> {
+        let (mut short, mut long) = (RopeBridge::<2>::new(), RopeBridge::<10>::new());
+        for &(dir, count) in movements {
+            short.motion(dir, count);
+            long.motion(dir, count);
+        }
+        let mut visited = InfiniteGrid::new((false, false));
+        short.visited().for_each(|p| visited[p].0 = true);
+        long.visited().for_each(|p| visited[p].1 = true);
+        let bounds = visited.bounds().unwrap_or(Bounds::of_point(Point::ORIGIN));
+        let image = Image::from_fn(bounds.width(), bounds.height(), |x, y| {
+            let p = bounds.min + Point::new(x as i32, y as i32);
+            match *visited.get(p) {
+                _ if p == Point::ORIGIN => Rgb::GREEN,
+                (true, true) => Rgb::RED.mix(Rgb::BLUE, 0.5),
+                (true, false) => Rgb::BLUE,
+                (false, true) => Rgb::RED,
+                (false, false) => Rgb::WHITE,
+            }
+        });
+        Ok(image.into())
+    }
+
+    /// `size` motions of 1 to 19 steps
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        Ok((0..size)
+            .map(|_| {
+                format!(
+                    "{} {}\n",
+                    rng.pick(&["U", "D", "L", "R"]),
+                    rng.range(1..=19)
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_movements_reports_location() {
+        assert_eq!(
+            parse_movements("R 4\nU 2\n").unwrap(),
+            vec![(Direction::Right, 4), (Direction::Up, 2)]
+        );
+        let err = parse_movements("R 4\nX 2\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 1));
+        assert_eq!(err.offending_text(), "X");
+        let err = parse_movements("R 4\nU -2\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 3));
+        assert!(parse_movements("R4\n").is_err());
+    }
+}
@@ -1,70 +1,107 @@
-use std::collections::HashSet;
-
-#[derive(Copy, Clone, Debug)]
-pub enum Direction {
-    L,
-    U,
-    R,
-    D,
-}
+use common::{
+    twod::{Bounds, Direction, InfiniteGrid, Point},
+    viz::{Color, Glyph, Scene},
+};
 
+/// A rope of `L` knots, all starting at the origin, and the positions its tail visited
 pub struct RopeBridge<const L: usize> {
-    rope: [(i32, i32); L],
-    visited: HashSet<(i32, i32)>,
+    rope: [Point; L],
+    visited: InfiniteGrid<bool>,
 }
 
 impl<const L: usize> RopeBridge<L> {
     pub fn new() -> Self {
+        let mut visited = InfiniteGrid::new(false);
+        visited.set(Point::ORIGIN, true);
         Self {
-            rope: [(0, 0); L],
-            visited: HashSet::from([(0, 0)]),
+            rope: [Point::ORIGIN; L],
+            visited,
         }
     }
 
-    pub fn head(&self) -> (i32, i32) {
-        self.rope.first().cloned().unwrap_or((0, 0))
+    pub fn head(&self) -> Point {
+        self.rope.first().cloned().unwrap_or(Point::ORIGIN)
     }
 
-    pub fn tail(&self) -> (i32, i32) {
-        self.rope.last().cloned().unwrap_or((0, 0))
+    pub fn tail(&self) -> Point {
+        self.rope.last().cloned().unwrap_or(Point::ORIGIN)
     }
 
     pub fn motion(&mut self, dir: Direction, count: usize) {
-        let step = match dir {
-            Direction::L => (-1, 0),
-            Direction::U => (0, 1),
-            Direction::R => (1, 0),
-            Direction::D => (0, -1),
-        };
+        self.motion_with(dir, count, |_| ());
+    }
+
+    /// Like `motion`, calling `observe` after each step
+    pub fn motion_with(
+        &mut self,
+        dir: Direction,
+        count: usize,
+        mut observe: impl FnMut(&Self),
+    ) {
         for _ in 0..count {
             if let Some(head) = self.rope.first_mut() {
-                head.0 += step.0;
-                head.1 += step.1;
+                *head += dir.offset();
             }
             self.relax_rope();
+            observe(self);
         }
     }
 
+    /// Positions the tail has visited
+    pub fn visited(&self) -> impl Iterator<Item = Point> + '_ {
+        self.visited.iter().map(|(p, _)| p)
+    }
+
     pub fn count_visited_positions(&self) -> usize {
-        self.visited.len()
+        self.visited.iter().count()
     }
 
     fn relax_rope(&mut self) {
-        if L < 2 {
+        let Some(mut precursor) = self.rope.first().copied() else {
             return;
-        }
-        let mut precursor = self.rope.first().unwrap().clone();
+        };
         for knot in self.rope.iter_mut().skip(1) {
-            let dx = precursor.0 - knot.0;
-            let dy = precursor.1 - knot.1;
-            if dx.abs() > 1 || dy.abs() > 1 {
-                knot.0 += dx.signum();
-                knot.1 += dy.signum();
+            if precursor.chebyshev(*knot) > 1 {
+                let d = precursor - *knot;
+                *knot += Point::new(d.x.signum(), d.y.signum());
             }
-            precursor = knot.clone();
+            precursor = *knot;
         }
-        if let Some(tail) = self.rope.last() {
-            self.visited.insert(*tail);
+        if let Some(&tail) = self.rope.last() {
+            self.visited.set(tail, true);
+        }
+    }
+}
+
+impl<const L: usize> Default for RopeBridge<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const L: usize> Scene for RopeBridge<L> {
+    fn bounds(&self) -> Bounds {
+        let visited = self
+            .visited
+            .bounds()
+            .unwrap_or(Bounds::of_point(Point::ORIGIN));
+        self.rope.iter().fold(visited, |b, &knot| b.including(knot))
+    }
+
+    fn focus(&self) -> Point {
+        self.head()
+    }
+
+    fn glyph(&self, p: Point) -> Glyph {
+        match self.rope.iter().position(|&knot| knot == p) {
+            Some(0) => Glyph::new('H', Color::Red),
+            Some(n) if n == L - 1 => Glyph::new('T', Color::Green),
+            Some(n) => {
+                Glyph::new(char::from_digit(n as u32 % 10, 10).unwrap(), Color::Yellow)
+            }
+            None if p == Point::ORIGIN => Glyph::new('s', Color::Cyan),
+            None if *self.visited.get(p) => Glyph::new('#', Color::Gray),
+            None => Glyph::new('.', Color::Gray),
         }
     }
 }
@@ -76,64 +113,63 @@ mod ropebridge_tests {
     #[test]
     fn new() {
         let b = RopeBridge::<2>::new();
-        assert_eq!(b.head(), (0, 0));
-        assert_eq!(b.tail(), (0, 0));
+        assert_eq!(b.head(), Point::ORIGIN);
+        assert_eq!(b.tail(), Point::ORIGIN);
         assert_eq!(b.count_visited_positions(), 1);
     }
 
     #[test]
     fn vertical_motion() {
         let mut b = RopeBridge::<2>::new();
-        b.motion(Direction::R, 1);
-        assert_eq!(b.head(), (1, 0));
-        assert_eq!(b.tail(), (0, 0));
+        b.motion(Direction::Right, 1);
+        assert_eq!(b.head(), Point::new(1, 0));
+        assert_eq!(b.tail(), Point::ORIGIN);
         assert_eq!(b.count_visited_positions(), 1);
-        b.motion(Direction::L, 2);
-        assert_eq!(b.head(), (-1, 0));
-        assert_eq!(b.tail(), (0, 0));
+        b.motion(Direction::Left, 2);
+        assert_eq!(b.head(), Point::new(-1, 0));
+        assert_eq!(b.tail(), Point::ORIGIN);
         assert_eq!(b.count_visited_positions(), 1);
     }
 
     #[test]
     fn horizontal_motion() {
         let mut b = RopeBridge::<2>::new();
-        b.motion(Direction::U, 1);
-        assert_eq!(b.head(), (0, 1));
-        assert_eq!(b.tail(), (0, 0));
+        b.motion(Direction::Up, 1);
+        assert_eq!(b.head(), Point::new(0, -1));
+        assert_eq!(b.tail(), Point::ORIGIN);
         assert_eq!(b.count_visited_positions(), 1);
-        b.motion(Direction::D, 2);
-        assert_eq!(b.head(), (0, -1));
-        assert_eq!(b.tail(), (0, 0));
+        b.motion(Direction::Down, 2);
+        assert_eq!(b.head(), Point::new(0, 1));
+        assert_eq!(b.tail(), Point::ORIGIN);
         assert_eq!(b.count_visited_positions(), 1);
     }
 
     #[test]
     fn tail_should_follow_when_two_steps_away() {
         let mut b = RopeBridge::<2>::new();
-        b.motion(Direction::R, 2);
-        assert_eq!(b.head(), (2, 0));
-        assert_eq!(b.tail(), (1, 0));
+        b.motion(Direction::Right, 2);
+        assert_eq!(b.head(), Point::new(2, 0));
+        assert_eq!(b.tail(), Point::new(1, 0));
         assert_eq!(b.count_visited_positions(), 2);
     }
 
     #[test]
     fn tail_should_not_follow_when_touching_diagonally() {
         let mut b = RopeBridge::<2>::new();
-        b.motion(Direction::R, 1);
-        b.motion(Direction::U, 1);
-        assert_eq!(b.tail(), (0, 0));
+        b.motion(Direction::Right, 1);
+        b.motion(Direction::Up, 1);
+        assert_eq!(b.tail(), Point::ORIGIN);
         assert_eq!(b.count_visited_positions(), 1);
     }
 
     #[test]
     fn rope_should_follow_when_two_steps_away() {
         let mut b = RopeBridge::<3>::new();
-        b.motion(Direction::R, 2);
+        b.motion(Direction::Right, 2);
         // middle moves between head and tail, relaxing the rope without moving tail
-        assert_eq!(b.tail(), (0, 0));
-        b.motion(Direction::R, 1);
-        assert_eq!(b.tail(), (1, 0));
+        assert_eq!(b.tail(), Point::ORIGIN);
+        b.motion(Direction::Right, 1);
+        assert_eq!(b.tail(), Point::new(1, 0));
         assert_eq!(b.count_visited_positions(), 2);
     }
-
 }
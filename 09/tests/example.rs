@@ -0,0 +1,38 @@
+use common::{
+    input::DayInputs,
+    solution::Solution,
+    twod::{Direction, Point},
+};
+use day09::{count_tail_positions, parse_movements, ropebridge::RopeBridge, Day09};
+
+fn read(spec: &str) -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&spec.parse().unwrap())
+        .unwrap()
+}
+
+#[test]
+fn tail_follows_head() {
+    let mut bridge = RopeBridge::<3>::new();
+    bridge.motion(Direction::Right, 4);
+    assert_eq!(bridge.head(), Point::new(4, 0));
+    assert_eq!(bridge.tail(), Point::new(2, 0));
+    assert_eq!(bridge.count_visited_positions(), 3);
+}
+
+#[test]
+fn counts_tail_positions() {
+    let movements = parse_movements("R 3\nL 3\n").unwrap();
+    assert_eq!(count_tail_positions::<2>(&movements), 3);
+    assert_eq!(count_tail_positions::<1>(&movements), 4);
+    assert!(parse_movements("X 3\n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let movements = Day09::parse(&read("example")).unwrap();
+    assert_eq!(Day09::part1(&movements).unwrap(), 13);
+    assert_eq!(Day09::part2(&movements).unwrap(), 1);
+    let movements = Day09::parse(&read("test_data_2.txt")).unwrap();
+    assert_eq!(Day09::part2(&movements).unwrap(), 36);
+}
@@ -0,0 +1,216 @@
+//! Day 10: Cathode-Ray Tube, running a CPU program that draws letters on a CRT
+
+use anyhow::anyhow;
+use common::{
+    image::{Drawing, Image, Rgb},
+    ocr,
+    parse::{next_token, parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+    twod::{Grid, Point},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Instruction {
+    /// Adds to X, taking two cycles
+    Addx(i64),
+    /// Does nothing for one cycle
+    Noop,
+}
+
+fn parse_instruction(ln: &str) -> Result<Instruction, ParseError> {
+    if ln == "noop" {
+        return Ok(Instruction::Noop);
+    }
+    let mut words = ln.split(' ');
+    let op = next_token(ln, &mut words, "instruction")?;
+    if op != "addx" {
+        let msg = format!("unknown instruction '{op}', expected addx or noop");
+        return Err(ParseError::at(ln, op, msg));
+    }
+    let v = parse_token(
+        ln,
+        next_token(ln, &mut words, "addx operand")?,
+        "addx operand",
+    )?;
+    match words.next() {
+        Some(extra) => Err(ParseError::at(ln, extra, "unexpected operand")),
+        None => Ok(Instruction::Addx(v)),
+    }
+}
+
+/// The instructions, one per line like `addx 3` or `noop`
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    input
+        .lines()
+        .map(|ln| parse_instruction(ln).map_err(|e| e.within(input, ln)))
+        .collect()
+}
+
+/// Iterates over (last cycle, X value) pairs, the X value being valid from the previous
+/// item's last cycle to the given last cycle
+fn x_values(prog: &[Instruction]) -> impl Iterator<Item = (i64, i64)> + Clone + '_ {
+    prog.iter().scan((0, 1i64), |(start_cycle, x), &inst| {
+        let current_x = *x;
+        (*start_cycle, *x) = match inst {
+            Instruction::Addx(v) => (*start_cycle + 2, *x + v),
+            Instruction::Noop => (*start_cycle + 1, *x),
+        };
+        Some((*start_cycle, current_x))
+    })
+}
+
+/// Sum of the cycle numbers times X during cycles 20, 60, 100, 140, 180 and 220
+pub fn signal_strength(prog: &[Instruction]) -> anyhow::Result<i64> {
+    let mut x_value_iter = x_values(prog);
+    (20..=220)
+        .step_by(40)
+        .map(|cycle| {
+            let (_, x) = x_value_iter
+                .find(|(x_value_cycle, _)| *x_value_cycle >= cycle)
+                .ok_or(anyhow!("Missing some X values"))?;
+            Ok(cycle * x)
+        })
+        .sum()
+}
+
+const CRT_WIDTH: usize = 40;
+const CRT_HEIGHT: usize = 6;
+
+/// The lit pixels of the 40x6 CRT
+pub fn render_crt(prog: &[Instruction]) -> anyhow::Result<Grid<bool>> {
+    let mut x_value_iter = x_values(prog);
+    let mut next_x_value = || x_value_iter.next().ok_or(anyhow!("Missing some X values"));
+    let mut crt = Grid::from_fn(CRT_WIDTH, CRT_HEIGHT, |_| false);
+    let (mut x_valid_until, mut x) = next_x_value()?;
+    for cycle in 1..=(CRT_WIDTH * CRT_HEIGHT) as i64 {
+        if cycle > x_valid_until {
+            (x_valid_until, x) = next_x_value()?;
+        }
+        let col = (cycle - 1) % CRT_WIDTH as i64;
+        let row = (cycle - 1) / CRT_WIDTH as i64;
+        crt[Point::new(col as i32, row as i32)] = (x - col).abs() < 2;
+    }
+    Ok(crt)
+}
+
+/// A random program that makes the CRT show `screen`, if there is one
+fn program_showing(screen: &Grid<bool>, rng: &mut Rng) -> Option<Vec<Instruction>> {
+    // X values beyond the screen's edges light nothing
+    const X_MIN: i64 = -2;
+    const X_MAX: i64 = CRT_WIDTH as i64 + 1;
+    let cycles = CRT_WIDTH * CRT_HEIGHT;
+    let shows = |cycle: usize, x: i64| {
+        let col = (cycle % CRT_WIDTH) as i64;
+        let pixel = Point::new(col as i32, (cycle / CRT_WIDTH) as i32);
+        cycle >= cycles || screen[pixel] == ((x - col).abs() < 2)
+    };
+    // instructions starting at a cycle (counted from 0) with the given X, after which the
+    // rest of the screen can still be drawn
+    let options = |feasible: &[Vec<bool>], cycle: usize, x: i64| {
+        let mut result = vec![];
+        if !shows(cycle, x) {
+            return result;
+        }
+        if feasible[cycle + 1][(x - X_MIN) as usize] {
+            result.push(Instruction::Noop);
+        }
+        if shows(cycle + 1, x) {
+            result.extend(
+                (X_MIN..=X_MAX)
+                    .filter(|&to| to != x && feasible[cycle + 2][(to - X_MIN) as usize])
+                    .map(|to| Instruction::Addx(to - x)),
+            );
+        }
+        result
+    };
+    let mut feasible = vec![vec![true; (X_MAX - X_MIN + 1) as usize]; cycles + 2];
+    for cycle in (0..cycles).rev() {
+        for x in X_MIN..=X_MAX {
+            feasible[cycle][(x - X_MIN) as usize] =
+                !options(&feasible, cycle, x).is_empty();
+        }
+    }
+    let (mut cycle, mut x) = (0, 1);
+    let mut prog = vec![];
+    while cycle < cycles {
+        let options = options(&feasible, cycle, x);
+        if options.is_empty() {
+            return None;
+        }
+        let inst = match options[0] {
+            Instruction::Noop if options.len() == 1 || rng.chance(0.3) => {
+                Instruction::Noop
+            }
+            Instruction::Noop => options[1 + rng.index(options.len() - 1)],
+            _ => *rng.pick(&options),
+        };
+        (cycle, x) = match inst {
+            Instruction::Addx(v) => (cycle + 2, x + v),
+            Instruction::Noop => (cycle + 1, x),
+        };
+        prog.push(inst);
+    }
+    Some(prog)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const TITLE: &'static str = "Cathode-Ray Tube";
+
+    type Params = ();
+    type Input = Vec<Instruction>;
+    type Answer1 = i64;
+    /// The capital letters shown on the CRT
+    type Answer2 = String;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_program(input)?)
+    }
+
+    fn part1(prog: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        signal_strength(prog)
+    }
+
+    fn part2(prog: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        ocr::read_letters(&render_crt(prog)?)
+    }
+
+    /// The CRT screen
+    fn draw(prog: &Self::Input) -> anyhow::Result<Drawing> {
+        let crt = render_crt(prog)?;
+        Ok(
+            Image::from_grid(&crt, |&lit| if lit { Rgb::YELLOW } else { Rgb::BLACK })
+                .into(),
+        )
+    }
+
+    /// A program that shows random letters on the CRT, padded with random instructions
+    /// to `size` instructions
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let alphabet: Vec<char> = ocr::alphabet().collect();
+        let mut prog = loop {
+            let text: String = (0..CRT_WIDTH / ocr::LETTER_PITCH)
+                .map(|_| *rng.pick(&alphabet))
+                .collect();
+            if let Some(prog) = program_showing(&ocr::render(&text)?, rng) {
+                break prog;
+            }
+        };
+        while prog.len() < size {
+            prog.push(match rng.chance(0.3) {
+                true => Instruction::Noop,
+                false => Instruction::Addx(rng.range(1..=5) * rng.pick(&[-1, 1])),
+            });
+        }
+        Ok(prog
+            .iter()
+            .map(|inst| match inst {
+                Instruction::Addx(v) => format!("addx {v}\n"),
+                Instruction::Noop => "noop\n".to_owned(),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,44 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+    twod::Point,
+};
+use day10::{parse_program, render_crt, signal_strength, Day10, Instruction};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn runs_programs() {
+    let program = parse_program("noop\naddx 3\naddx -5\n").unwrap();
+    assert_eq!(
+        program,
+        [
+            Instruction::Noop,
+            Instruction::Addx(3),
+            Instruction::Addx(-5)
+        ]
+    );
+    // too short to reach cycle 20
+    assert!(signal_strength(&program).is_err());
+    assert!(render_crt(&program).is_err());
+    assert!(parse_program("addx\n").is_err());
+    assert!(parse_program("jmp 1\n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let program = Day10::parse(&example()).unwrap();
+    assert_eq!(Day10::part1(&program).unwrap(), 13140);
+    let crt = render_crt(&program).unwrap();
+    // the example draws stripes of two lit and two dark pixels in the first row
+    let first_row: Vec<_> = (0..8).map(|x| crt[Point::new(x, 0)]).collect();
+    assert_eq!(
+        first_row,
+        [true, true, false, false, true, true, false, false]
+    );
+    assert!(Day10::part2(&program).is_err());
+}
@@ -0,0 +1,274 @@
+//! Day 11: Monkey in the Middle, tracking items thrown between monkeys
+
+use anyhow::{anyhow, bail};
+use common::{
+    image::{Drawing, Rgb},
+    params,
+    random::Rng,
+    solution::Solution,
+};
+use log::{debug, trace};
+use std::collections::VecDeque;
+
+pub mod notes;
+
+struct Monkey {
+    items: VecDeque<usize>,
+    inspection_count: usize,
+}
+
+/// After each inspection the worry level is divided by three
+pub struct Part1;
+
+/// Worry levels are kept modulo the product of all test divisors
+pub struct Part2 {
+    gcd: usize,
+}
+
+impl Part2 {
+    /// Fails if the product of the divisors does not fit into a `usize`
+    pub fn new(notes: &[notes::Monkey]) -> anyhow::Result<Self> {
+        let gcd = notes
+            .iter()
+            .try_fold(1usize, |product, m| product.checked_mul(m.test.divisor))
+            .ok_or(anyhow!("Product of the test divisors overflows"))?;
+        Ok(Self { gcd })
+    }
+}
+
+/// How worry levels are kept manageable
+pub trait PuzzlePart {
+    fn managy_worries(&self, item: usize) -> usize;
+}
+
+impl PuzzlePart for Part1 {
+    fn managy_worries(&self, item: usize) -> usize {
+        item / 3
+    }
+}
+impl PuzzlePart for Part2 {
+    fn managy_worries(&self, item: usize) -> usize {
+        item % self.gcd
+    }
+}
+
+/// Product of the number of inspections of the two most active monkeys after `rounds`
+pub fn monkey_business_level(
+    notes: &[notes::Monkey],
+    part: &dyn PuzzlePart,
+    rounds: usize,
+) -> anyhow::Result<usize> {
+    let mut monkeys: Vec<_> = notes
+        .iter()
+        .map(|monkey_note| Monkey {
+            items: monkey_note.starting_items.clone().into(),
+            inspection_count: 0,
+        })
+        .collect();
+    let mut items_to_catch: Vec<VecDeque<usize>> = vec![VecDeque::new(); monkeys.len()];
+    for round in 0..rounds {
+        for note in notes.iter() {
+            trace!("Monkey {}", note.idx);
+            let monkey = &mut monkeys[note.idx];
+            while let Some(item) = monkey.items.pop_front() {
+                trace!("  Monkey inspects an item with a worry level of {item}.");
+                monkey.inspection_count += 1;
+                use notes::Operand::*;
+                let get_operand = |o: notes::Operand| match o {
+                    Old => item,
+                    Constant(x) => x,
+                };
+                let item = match &note.operation {
+                    notes::Operation::Add(a, b) => {
+                        let (a, b) = (get_operand(a.clone()), get_operand(b.clone()));
+                        let new =
+                            a.checked_add(b).ok_or(anyhow!("Worry level overflow"))?;
+                        trace!("    Worry level increases by {a} to {new}.");
+                        new
+                    }
+                    notes::Operation::Multiply(a, b) => {
+                        let (a, b) = (get_operand(a.clone()), get_operand(b.clone()));
+                        let new =
+                            a.checked_mul(b).ok_or(anyhow!("Worry level overflow"))?;
+                        trace!("    Worry level is multiplied by {a} to {new}.");
+                        new
+                    }
+                };
+                let item = part.managy_worries(item);
+                trace!("    Monkey gets bored with item. Worry level is divided by 3 to {item}.");
+                let target = if (item % note.test.divisor) == 0 {
+                    trace!(
+                        "    Current worry level is divisible by {}.",
+                        note.test.divisor
+                    );
+                    note.test.true_target
+                } else {
+                    trace!(
+                        "    Current worry level is not divisible by {}.",
+                        note.test.divisor
+                    );
+                    note.test.false_target
+                };
+                trace!("    Item with worry level {item} is thrown to monkey {target}.");
+                items_to_catch[target].push_back(item);
+            }
+            for (monkey, new_items) in monkeys.iter_mut().zip(items_to_catch.iter_mut()) {
+                while let Some(item) = new_items.pop_front() {
+                    monkey.items.push_back(item);
+                }
+            }
+        }
+        if round == 0 || round == 19 || ((round + 1) % 1000) == 0 {
+            debug!("== After round {} ==", round + 1);
+            for (idx, monkey) in monkeys.iter().enumerate() {
+                debug!(
+                    "Monkey {idx} inspected items {} times",
+                    monkey.inspection_count
+                );
+            }
+        }
+    }
+    let most_active: [usize; 2] = {
+        let mut res: Vec<usize> = monkeys.iter().map(|m| m.inspection_count).collect();
+        res.sort();
+        [res[res.len() - 2], res[res.len() - 1]]
+    };
+    Ok(most_active.iter().product())
+}
+
+/// How long the monkeys play, the same for the example
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub part1_rounds: usize,
+    pub part2_rounds: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            part1_rounds: 20,
+            part2_rounds: 10000,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "part1_rounds" => self.part1_rounds = params::parse_value(key, value)?,
+            "part2_rounds" => self.part2_rounds = params::parse_value(key, value)?,
+            _ => return Err(params::unknown(key, &["part1_rounds", "part2_rounds"])),
+        }
+        Ok(())
+    }
+}
+
+/// The notes on the monkeys and how long they play
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub notes: Vec<notes::Monkey>,
+    pub params: Params,
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+    const TITLE: &'static str = "Monkey in the Middle";
+
+    type Params = Params;
+    type Input = Game;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_with(input, &Params::default())
+    }
+
+    fn parse_with(input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        let notes = notes::parse_notes(input)?;
+        Ok(Game {
+            notes,
+            params: params.clone(),
+        })
+    }
+
+    fn part1(game: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        monkey_business_level(&game.notes, &Part1 {}, game.params.part1_rounds)
+    }
+
+    fn part2(game: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        let part = Part2::new(&game.notes)?;
+        monkey_business_level(&game.notes, &part, game.params.part2_rounds)
+    }
+
+    /// The monkeys on a circle, with a bent line to each monkey they throw to: green if
+    /// the test is true, red if it is false
+    fn draw(game: &Self::Input) -> anyhow::Result<Drawing> {
+        const RADIUS: f64 = 40.0;
+        let notes = &game.notes;
+        let position = |idx: usize| {
+            let angle = idx as f64 / notes.len() as f64 * std::f64::consts::TAU;
+            (50.0 + RADIUS * angle.sin(), 50.0 - RADIUS * angle.cos())
+        };
+        let mut drawing = Drawing::new(100.0, 100.0);
+        for note in notes {
+            let from = position(note.idx);
+            for (target, color, bend) in [
+                (note.test.true_target, Rgb::GREEN, 0.15),
+                (note.test.false_target, Rgb::RED, -0.15),
+            ] {
+                // bent towards one side so that throws back and forth do not overlap
+                let to = position(target);
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                let mid = (from.0 + dx / 2.0 - dy * bend, from.1 + dy / 2.0 + dx * bend);
+                drawing.polyline([from, mid, to], color);
+            }
+        }
+        for note in notes {
+            let (x, y) = position(note.idx);
+            let diamond = [(x, y - 3.0), (x + 3.0, y), (x, y + 3.0), (x - 3.0, y)];
+            drawing.polygon(diamond, Some(Rgb::BLACK), Some(Rgb::YELLOW));
+        }
+        Ok(drawing)
+    }
+
+    /// `size` monkeys, at least 3 and at most 9 so that part two's worry levels fit
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        // worry levels may grow too large for part one, so there are a few attempts
+        const ATTEMPTS: usize = 100;
+        let mut divisors = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+        // the monkeys besides the squaring one need another one to throw to
+        let count = size.clamp(3, divisors.len());
+        for _ in 0..ATTEMPTS {
+            rng.shuffle(&mut divisors);
+            let squaring_monkey = rng.index(count);
+            let mut notes = String::new();
+            for (idx, divisor) in divisors.iter().take(count).enumerate() {
+                let items: Vec<String> = (0..rng.range(1..=8))
+                    .map(|_| rng.range(50..=99).to_string())
+                    .collect();
+                let operation = match rng.chance(0.5) {
+                    _ if idx == squaring_monkey => "* old".to_owned(),
+                    true => format!("+ {}", rng.range(1..=8)),
+                    false => format!("* {}", rng.range(2..=19)),
+                };
+                // squared worry levels would only grow further each time they return
+                let mut targets = (0..count)
+                    .filter(|&t| t != idx && t != squaring_monkey)
+                    .collect::<Vec<_>>();
+                rng.shuffle(&mut targets);
+                let false_target = targets[targets.len() - 1];
+                notes += &format!(
+                    "Monkey {idx}:\n  Starting items: {}\n  Operation: new = old {operation}\n  Test: divisible by {divisor}\n    If true: throw to monkey {}\n    If false: throw to monkey {false_target}\n\n",
+                    items.join(", "),
+                    targets[0],
+                );
+            }
+            if Self::part1(&Self::parse(&notes)?).is_ok() {
+                return Ok(notes);
+            }
+        }
+        bail!("No notes of {count} monkeys without overflows in {ATTEMPTS} attempts")
+    }
+}
@@ -1,11 +1,13 @@
+use common::parse::ParseError;
 use nom::{
     bytes::complete::tag,
     character::complete::{digit1, multispace0, newline, one_of, space0},
-    combinator::{map_res, opt},
+    combinator::{consumed, map_res, opt},
     sequence::tuple,
     IResult,
 };
 
+/// What the notes say about one monkey
 #[derive(Debug, Clone)]
 pub struct Monkey {
     pub idx: usize,
@@ -26,6 +28,8 @@ pub enum Operation {
     Multiply(Operand, Operand),
 }
 
+/// Where a monkey throws an item: to `true_target` if the worry level is divisible by
+/// `divisor`, to `false_target` otherwise
 #[derive(Debug, Clone, PartialEq)]
 pub struct Test {
     pub divisor: usize,
@@ -33,10 +37,23 @@ pub struct Test {
     pub false_target: usize,
 }
 
-fn parse_monkey_head(input: &str) -> IResult<&str, usize> {
+/// Where the numbers of a monkey's notes are, to point errors found after parsing at them
+#[derive(Debug)]
+struct Spans<'a> {
+    idx: &'a str,
+    divisor: &'a str,
+    targets: [&'a str; 2],
+}
+
+/// A number, along with the text it was parsed from
+fn number(input: &str) -> IResult<&str, (&str, usize)> {
+    consumed(map_res(digit1, str::parse))(input)
+}
+
+fn parse_monkey_head(input: &str) -> IResult<&str, (&str, usize)> {
     let monkey_marker = tag("Monkey");
-    let idx = map_res(digit1, str::parse);
-    let (input, (_, _, idx, _)) = tuple((monkey_marker, space0, idx, tag(":")))(input)?;
+    let (input, (_, _, idx, _)) =
+        tuple((monkey_marker, space0, number, tag(":")))(input)?;
     let (input, _) = newline(input)?;
     Ok((input, idx))
 }
@@ -46,7 +63,7 @@ fn test_parse_monkey_head() {
     let input = "Monkey 11:\n";
     let (rest, result) = parse_monkey_head(input).unwrap();
     assert_eq!(rest, "");
-    assert_eq!(result, 11);
+    assert_eq!(result, ("11", 11));
 }
 
 fn parse_monkey_starting_items(input: &str) -> IResult<&str, Vec<usize>> {
@@ -81,7 +98,7 @@ fn parse_operand(input: &str) -> IResult<&str, Operand> {
         return Ok((input, Operand::Old));
     }
     let (input, constant) = map_res(digit1, str::parse)(input)?;
-    return Ok((input, Operand::Constant(constant)));
+    Ok((input, Operand::Constant(constant)))
 }
 
 fn parse_monkey_operation(input: &str) -> IResult<&str, Operation> {
@@ -117,52 +134,56 @@ fn test_parse_monkey_operation() {
     assert_eq!(rest, "");
 }
 
-fn parse_monkey_test(input: &str) -> IResult<&str, Test> {
+/// The test, along with the text of its divisor and of its two targets
+fn parse_monkey_test(input: &str) -> IResult<&str, (Test, [&str; 3])> {
     let (input, _) = tuple((tag("  Test: divisible by"), space0))(input)?;
-    let (input, divisor) = map_res(digit1, str::parse)(input)?;
+    let (input, divisor) = number(input)?;
     let (input, (_, _, _, true_target, _)) = tuple((
         newline,
         tag("    If true: throw to monkey"),
         space0,
-        map_res(digit1, str::parse),
+        number,
         space0,
     ))(input)?;
     let (input, (_, _, _, false_target, _)) = tuple((
         newline,
         tag("    If false: throw to monkey"),
         space0,
-        map_res(digit1, str::parse),
+        number,
         space0,
     ))(input)?;
-    Ok((
-        input,
-        Test {
-            divisor,
-            true_target,
-            false_target,
-        },
-    ))
-}
-
-fn parse_monkey(input: &str) -> IResult<&str, Monkey> {
-    let (input, (idx, starting_items, operation, test)) = tuple((
-        parse_monkey_head,
-        parse_monkey_starting_items,
-        parse_monkey_operation,
-        parse_monkey_test,
-    ))(input)?;
-    Ok((
-        input,
-        Monkey {
-            idx,
-            starting_items,
-            operation,
-            test,
-        },
-    ))
-}
-
-fn parse_notes_internal(input: &str) -> IResult<&str, Vec<Monkey>> {
+    let test = Test {
+        divisor: divisor.1,
+        true_target: true_target.1,
+        false_target: false_target.1,
+    };
+    Ok((input, (test, [divisor.0, true_target.0, false_target.0])))
+}
+
+fn parse_monkey(input: &str) -> IResult<&str, (Monkey, Spans<'_>)> {
+    let (input, ((idx_span, idx), starting_items, operation, (test, test_spans))) =
+        tuple((
+            parse_monkey_head,
+            parse_monkey_starting_items,
+            parse_monkey_operation,
+            parse_monkey_test,
+        ))(input)?;
+    let [divisor, true_target, false_target] = test_spans;
+    let monkey = Monkey {
+        idx,
+        starting_items,
+        operation,
+        test,
+    };
+    let spans = Spans {
+        idx: idx_span,
+        divisor,
+        targets: [true_target, false_target],
+    };
+    Ok((input, (monkey, spans)))
+}
+
+fn parse_notes_internal(input: &str) -> IResult<&str, Vec<(Monkey, Spans<'_>)>> {
     let mut result = vec![];
     let mut input = input;
     loop {
@@ -178,8 +199,81 @@ fn parse_notes_internal(input: &str) -> IResult<&str, Vec<Monkey>> {
     Ok((input, result))
 }
 
-pub fn parse_notes(input: &str) -> Result<Vec<Monkey>, String> {
-    let res = parse_notes_internal(input);
-    res.map_err(|err| err.to_string())
-        .map(|(_, monkeys)| monkeys)
+/// Parses the notes on all monkeys, separated by empty lines
+///
+/// Fails unless there are at least two monkeys, numbered in order, that only throw to
+/// each other and test for divisibility by nonzero divisors.
+pub fn parse_notes(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let (rest, monkeys) =
+        parse_notes_internal(input).map_err(|e| ParseError::from_nom(input, e))?;
+    if !rest.trim().is_empty() {
+        // Parsing stopped at a monkey it could not read, parse it again for the reason
+        let err = parse_monkey(rest).expect_err("parsing the monkey failed before");
+        return Err(ParseError::from_nom(input, err));
+    }
+    if monkeys.len() < 2 {
+        return Err(ParseError::at_end(
+            input,
+            "expected notes on at least two monkeys",
+        ));
+    }
+    for (idx, (monkey, spans)) in monkeys.iter().enumerate() {
+        if monkey.idx != idx {
+            let msg = format!("expected notes on monkey {idx}");
+            return Err(ParseError::at(input, spans.idx, msg));
+        }
+        if monkey.test.divisor == 0 {
+            return Err(ParseError::at(
+                input,
+                spans.divisor,
+                "divisor must not be 0",
+            ));
+        }
+        let targets = [monkey.test.true_target, monkey.test.false_target];
+        for (target, span) in targets.into_iter().zip(spans.targets) {
+            if target >= monkeys.len() {
+                let msg = format!("throws to unknown monkey {target}");
+                return Err(ParseError::at(input, span, msg));
+            }
+        }
+    }
+    Ok(monkeys.into_iter().map(|(monkey, _)| monkey).collect())
+}
+
+#[test]
+fn test_parse_notes_reports_location() {
+    let input = "Monkey 0:\n  Starting items: 79, 98\n  Operation: new = old ^ 19\n";
+    let err = parse_notes(input).unwrap_err();
+    assert_eq!((err.line(), err.column()), (3, 24));
+    assert_eq!(err.offending_text(), "^ 19");
+}
+
+#[test]
+fn test_parse_notes_rejects_invalid_monkeys() {
+    let monkey = |idx: usize, divisor: usize, target: usize| {
+        format!(
+            "Monkey {idx}:\n  Starting items: 1\n  Operation: new = old + 1\n  \
+            Test: divisible by {divisor}\n    If true: throw to monkey {target}\n    \
+            If false: throw to monkey 0\n\n"
+        )
+    };
+    let input = monkey(0, 2, 1) + &monkey(1, 3, 0);
+    assert_eq!(parse_notes(&input).unwrap().len(), 2);
+    let err = parse_notes(&monkey(0, 2, 0)).unwrap_err();
+    assert_eq!(err.message(), "expected notes on at least two monkeys");
+    let err = parse_notes(&(monkey(0, 2, 1) + &monkey(2, 3, 0))).unwrap_err();
+    assert_eq!(
+        (err.line(), err.column(), err.offending_text()),
+        (8, 8, "2")
+    );
+    let err = parse_notes(&(monkey(0, 2, 1) + &monkey(1, 0, 0))).unwrap_err();
+    assert_eq!(
+        (err.line(), err.column(), err.offending_text()),
+        (11, 22, "0")
+    );
+    let err = parse_notes(&(monkey(0, 2, 1) + &monkey(1, 3, 12))).unwrap_err();
+    assert_eq!(
+        (err.line(), err.column(), err.offending_text()),
+        (12, 30, "12")
+    );
 }
@@ -0,0 +1,70 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day11::{
+    monkey_business_level,
+    notes::{parse_notes, Operand, Operation},
+    Day11, Params, Part1, Part2,
+};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn parses_notes() {
+    let notes = parse_notes(&example()).unwrap();
+    assert_eq!(notes.len(), 4);
+    assert_eq!(notes[0].starting_items, [79, 98]);
+    assert_eq!(
+        notes[0].operation,
+        Operation::Multiply(Operand::Old, Operand::Constant(19))
+    );
+    assert_eq!(notes[0].test.divisor, 23);
+    assert_eq!(
+        (notes[0].test.true_target, notes[0].test.false_target),
+        (2, 3)
+    );
+}
+
+#[test]
+fn monkey_business_per_part() {
+    let notes = parse_notes(&example()).unwrap();
+    assert_eq!(monkey_business_level(&notes, &Part1, 20).unwrap(), 10605);
+    assert_eq!(
+        monkey_business_level(&notes, &Part2::new(&notes).unwrap(), 10000).unwrap(),
+        2713310158
+    );
+}
+
+#[test]
+fn example_answers() {
+    let game = Day11::parse(&example()).unwrap();
+    assert_eq!(Day11::part1(&game).unwrap(), 10605);
+    assert_eq!(Day11::part2(&game).unwrap(), 2713310158);
+    assert!(Day11::parse("Monkey 0:\n  Starting items: 1\n").is_err());
+}
+
+#[test]
+fn rejects_divisors_whose_product_overflows() {
+    let big = usize::MAX / 2;
+    let input = example().replace("divisible by 23", &format!("divisible by {big}"));
+    let game = Day11::parse(&input).unwrap();
+    assert!(Day11::part1(&game).is_ok());
+    assert!(Day11::part2(&game).is_err());
+}
+
+#[test]
+fn answers_after_other_numbers_of_rounds() {
+    let params = Params {
+        part1_rounds: 1,
+        part2_rounds: 20,
+    };
+    let game = Day11::parse_with(&example(), &params).unwrap();
+    // the inspection counts listed in the puzzle text
+    assert_eq!(Day11::part1(&game).unwrap(), 4 * 5);
+    assert_eq!(Day11::part2(&game).unwrap(), 99 * 103);
+}
@@ -0,0 +1,171 @@
+//! Day 12: Hill Climbing Algorithm, finding the shortest climb to the best signal
+
+use std::str::FromStr;
+
+use anyhow::Context;
+use common::{
+    image::{Drawing, Image, Rgb},
+    parse::ParseError,
+    random::Rng,
+    search::{bfs, GridGraph},
+    solution::Solution,
+    twod::{Grid, GridCell, Point},
+};
+
+/// The heightmap, with elevations `a` to `z`, and the start S and end E on it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    heightmap: Grid<u8>,
+    start: Point,
+    end: Point,
+}
+
+impl Field {
+    /// Fewest steps from S to E, climbing at most one elevation per step
+    pub fn find_shortest_path(&self) -> anyhow::Result<u64> {
+        let uphill = GridGraph::new(&self.heightmap, |&from, &to| to <= from + 1);
+        bfs(&uphill, self.start)
+            .get(self.end)
+            .context("E is not reachable from S")
+    }
+
+    /// Fewest steps to E from any square of elevation `a`
+    pub fn find_shortest_path_from_any_a(&self) -> anyhow::Result<u64> {
+        // search backwards, from E
+        let downhill = GridGraph::new(&self.heightmap, |&from, &to| from <= to + 1);
+        bfs(&downhill, self.end)
+            .iter()
+            .filter(|&(p, _)| self.heightmap[p] == b'a')
+            .map(|(_, dist)| dist)
+            .min()
+            .context("E is not reachable from any square of elevation a")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Square {
+    Start,
+    End,
+    Elevation(u8),
+}
+
+impl Square {
+    fn elevation(&self) -> u8 {
+        match self {
+            Square::Start => b'a',
+            Square::End => b'z',
+            Square::Elevation(e) => *e,
+        }
+    }
+}
+
+impl GridCell for Square {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            'S' => Some(Square::Start),
+            'E' => Some(Square::End),
+            'a'..='z' => Some(Square::Elevation(ch as u8)),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            Square::Start => 'S',
+            Square::End => 'E',
+            Square::Elevation(e) => *e as char,
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let squares = Grid::<Square>::parse(s)?;
+        let start = squares
+            .position(|&sq| sq == Square::Start)
+            .ok_or_else(|| ParseError::at_end(s, "could not find starting point"))?;
+        let end = squares
+            .position(|&sq| sq == Square::End)
+            .ok_or_else(|| ParseError::at_end(s, "could not find target point"))?;
+        Ok(Self {
+            heightmap: squares.map(Square::elevation),
+            start,
+            end,
+        })
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    const TITLE: &'static str = "Hill Climbing Algorithm";
+
+    type Params = ();
+    type Input = Field;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(field: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        field.find_shortest_path()
+    }
+
+    fn part2(field: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        field.find_shortest_path_from_any_a()
+    }
+
+    /// The heightmap with the shortest path from S to E
+    fn draw(field: &Self::Input) -> anyhow::Result<Drawing> {
+        let heights = Image::from_grid(&field.heightmap, |&h| {
+            Rgb::heat((h - b'a') as f64 / (b'z' - b'a') as f64)
+        });
+        let mut drawing = Drawing::from(heights);
+        let uphill = GridGraph::new(&field.heightmap, |&from, &to| to <= from + 1);
+        if let Some(path) = bfs(&uphill, field.start).path_to(field.end) {
+            let centre = |p: &Point| (p.x as f64 + 0.5, p.y as f64 + 0.5);
+            drawing.polyline(path.iter().map(centre), Rgb::RED);
+        }
+        Ok(drawing)
+    }
+
+    /// A heightmap `size` squares wide, at least 26, rising from west to east, with a
+    /// climbable path from S in the west to E in the east
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let width = size.max(26);
+        let height = (width / 4).max(5);
+        let mut rows: Vec<Vec<u8>> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|x| {
+                        let base = (25 * x / (width - 1)) as i64;
+                        b'a' + (base + rng.range(-4..=4)).clamp(0, 25) as u8
+                    })
+                    .collect()
+            })
+            .collect();
+        // a path of width - 1 steps east, and some north or south, rising evenly
+        let (start_y, end_y) = (rng.index(height), rng.index(height));
+        let vertical = if end_y > start_y { 1 } else { -1 };
+        let mut steps = vec![(1, 0); width - 1];
+        steps.extend(vec![(0, vertical); start_y.abs_diff(end_y)]);
+        rng.shuffle(&mut steps);
+        let (mut x, mut y) = (0, start_y as i64);
+        for (i, (dx, dy)) in steps.iter().enumerate() {
+            (x, y) = (x + dx, y + dy);
+            let elevation = (25 * (i + 1) / steps.len()) as u8;
+            rows[y as usize][x as usize] = b'a' + elevation;
+        }
+        rows[start_y][0] = b'S';
+        rows[end_y][width - 1] = b'E';
+        Ok(rows
+            .into_iter()
+            .map(|row| String::from_utf8(row).unwrap() + "\n")
+            .collect())
+    }
+}
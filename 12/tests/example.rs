@@ -0,0 +1,29 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day12::{Day12, Field};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn climbs_at_most_one_elevation_per_step() {
+    let field: Field = "SbcdefghijklmnopqrstuvwxyE\n".parse().unwrap();
+    assert_eq!(field.find_shortest_path().unwrap(), 25);
+    let field: Field = "SbE\n".parse().unwrap();
+    assert!(field.find_shortest_path().is_err());
+    let field: Field = "SaaE\nxyzz\n".parse().unwrap();
+    assert!(field.find_shortest_path().is_err());
+    assert!("abc\n".parse::<Field>().is_err());
+}
+
+#[test]
+fn example_answers() {
+    let field = Day12::parse(&example()).unwrap();
+    assert_eq!(Day12::part1(&field).unwrap(), 31);
+    assert_eq!(Day12::part2(&field).unwrap(), 29);
+}
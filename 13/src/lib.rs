@@ -0,0 +1,232 @@
+//! Day 13: Distress Signal, ordering packets of nested lists
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use common::{
+    image::{Drawing, Image, Rgb},
+    parse::{parse_token, ParseError},
+    random::Rng,
+    solution::Solution,
+};
+
+/// A packet or part of one, e.g., `[1,[2,3]]`, ordered as the puzzle describes
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Value {
+    List(Vec<Value>),
+    Int(i32),
+}
+
+fn int_to_list(i: i32) -> Value {
+    Value::List(vec![Value::Int(i)])
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (Value::Int(lhs), Value::Int(rhs)) => lhs.cmp(rhs),
+            (Value::List(lhs), Value::List(rhs)) => lhs.cmp(rhs), // Vec's lexicographical ordering should work
+            (lhs_list, Value::Int(rhs)) => lhs_list.cmp(&int_to_list(*rhs)),
+            (Value::Int(lhs), rhs_list) => int_to_list(*lhs).cmp(rhs_list),
+        }
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn read_list_item(s: &str) -> Result<(Value, &str), ParseError> {
+            match s.chars().next() {
+                Some('[') => {
+                    let end = s
+                        .chars()
+                        .skip(1)
+                        .scan(1, |depth, ch| {
+                            match ch {
+                                '[' => *depth += 1,
+                                ']' => *depth -= 1,
+                                _ => (),
+                            };
+                            Some(*depth)
+                        })
+                        .position(|depth| depth == 0)
+                        .ok_or_else(|| ParseError::at(s, &s[..1], "unclosed bracket"))?
+                        + 1;
+                    let rest_start = if let Some(',') = s.chars().nth(end + 1) {
+                        end + 2
+                    } else {
+                        end + 1
+                    };
+                    let item = &s[0..=end];
+                    let value = Value::from_str(item).map_err(|e| e.within(s, item))?;
+                    Ok((value, &s[rest_start..]))
+                }
+                Some(c) => {
+                    if c == ']' {
+                        return Err(ParseError::at(s, &s[..1], "unexpected ']'"));
+                    }
+                    let (end, rest) =
+                        match s.chars().position(|ch| ch == ',' || ch == ']') {
+                            Some(pos) => (pos, &s[pos + 1..]),
+                            None => (s.len(), ""),
+                        };
+                    let item = &s[0..end];
+                    let value = Value::from_str(item).map_err(|e| e.within(s, item))?;
+                    Ok((value, rest))
+                }
+                None => Err(ParseError::at_end(s, "expected a list item")),
+            }
+        }
+
+        match s.chars().next() {
+            Some('[') => {
+                if s.len() < 2 || !s.ends_with(']') {
+                    return Err(ParseError::at(s, &s[..1], "unclosed bracket"));
+                }
+                let mut res = vec![];
+                let mut items = &s[1..s.len() - 1];
+                while !items.is_empty() {
+                    let (item, rest) =
+                        read_list_item(items).map_err(|e| e.within(s, items))?;
+                    res.push(item);
+                    items = rest;
+                }
+                Ok(Value::List(res))
+            }
+            Some(_) => Ok(Value::Int(parse_token(s, s, "integer")?)),
+            None => Err(ParseError::at_end(s, "expected a value")),
+        }
+    }
+}
+
+/// Pairs of packets, each pair separated by an empty line
+pub fn parse_pairs(input: &str) -> Result<Vec<(Value, Value)>, ParseError> {
+    input
+        .split("\n\n")
+        .map(|pair| {
+            let pair = pair.trim();
+            let (left, right) = pair.split_once('\n').ok_or_else(|| {
+                ParseError::at(input, pair, "expected a pair of packets")
+            })?;
+            let parse = |packet: &str| {
+                Value::from_str(packet).map_err(|e| e.within(input, packet))
+            };
+            Ok((parse(left)?, parse(right)?))
+        })
+        .collect()
+}
+
+/// Product of the positions of the divider packets `[[2]]` and `[[6]]` among the sorted
+/// packets, counting from 1
+pub fn decoder_key(pairs: &[(Value, Value)]) -> anyhow::Result<usize> {
+    let mut items: Vec<_> = pairs.iter().cloned().fold(vec![], |mut acc, pair| {
+        acc.push(pair.0);
+        acc.push(pair.1);
+        acc
+    });
+    let sep1: Value = "[[2]]".parse()?;
+    let sep2: Value = "[[6]]".parse()?;
+    items.push(sep1.clone());
+    items.push(sep2.clone());
+    items.sort();
+    let code = (items
+        .iter()
+        .position(|v| *v == sep1)
+        .ok_or(anyhow!("Lost separator ([[2]])"))?
+        + 1)
+        * (items
+            .iter()
+            .position(|v| *v == sep2)
+            .ok_or(anyhow!("Lost separator ([[6]])"))?
+            + 1);
+    Ok(code)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    const TITLE: &'static str = "Distress Signal";
+
+    type Params = ();
+    type Input = Vec<(Value, Value)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(parse_pairs(input)?)
+    }
+
+    fn part1(pairs: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(pairs
+            .iter()
+            .enumerate()
+            .map(|(idx, (a, b))| if a < b { idx + 1 } else { 0 })
+            .sum())
+    }
+
+    fn part2(pairs: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        decoder_key(pairs)
+    }
+
+    /// One square per pair, green if it is in the right order
+    fn draw(pairs: &Self::Input) -> anyhow::Result<Drawing> {
+        const WIDTH: usize = 10;
+        let image =
+            Image::from_fn(WIDTH, pairs.len().div_ceil(WIDTH), |x, y| {
+                match pairs.get(y * WIDTH + x) {
+                    Some((a, b)) if a < b => Rgb::GREEN,
+                    Some(_) => Rgb::RED,
+                    None => Rgb::WHITE,
+                }
+            });
+        Ok(image.into())
+    }
+
+    /// `size` pairs of packets, nested up to four levels deep
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        fn list(rng: &mut Rng, depth: u32) -> String {
+            let items: Vec<String> = (0..rng.range(0..=5))
+                .map(|_| match depth < 4 && rng.chance(0.3) {
+                    true => list(rng, depth + 1),
+                    false => rng.range(0..=10).to_string(),
+                })
+                .collect();
+            format!("[{}]", items.join(","))
+        }
+        let pairs: Vec<String> = (0..size)
+            .map(|_| format!("{}\n{}\n", list(rng, 0), list(rng, 0)))
+            .collect();
+        Ok(pairs.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_nested_values() {
+        assert_eq!(
+            "[[1],4]".parse::<Value>().unwrap(),
+            Value::List(vec![Value::List(vec![Value::Int(1)]), Value::Int(4)])
+        );
+        assert_eq!("[]".parse::<Value>().unwrap(), Value::List(vec![]));
+    }
+
+    #[test]
+    fn parse_errors_point_into_nested_lists() {
+        let err = parse_pairs("[1]\n[2]\n\n[[1],[2,x]]\n[3]\n").unwrap_err();
+        assert_eq!((err.line(), err.column()), (4, 9));
+        assert_eq!(err.offending_text(), "x");
+        assert!("[1,[2]".parse::<Value>().is_err());
+        assert!("[1".parse::<Value>().is_err());
+    }
+}
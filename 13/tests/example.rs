@@ -0,0 +1,39 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day13::{decoder_key, parse_pairs, Day13, Value};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+fn value(s: &str) -> Value {
+    s.parse().unwrap()
+}
+
+#[test]
+fn orders_packets() {
+    assert!(value("[1,1,3,1,1]") < value("[1,1,5,1,1]"));
+    assert!(value("[[1],[2,3,4]]") < value("[[1],4]"));
+    assert!(value("[9]") > value("[[8,7,6]]"));
+    assert!(value("[]") < value("[3]"));
+    assert_eq!(value("[[4]]").cmp(&value("4")), std::cmp::Ordering::Equal);
+    assert!("[1,".parse::<Value>().is_err());
+}
+
+#[test]
+fn finds_decoder_key() {
+    let pairs = parse_pairs("[1]\n[3]\n\n[[5]]\n[7]\n").unwrap();
+    assert_eq!(decoder_key(&pairs).unwrap(), 2 * 5);
+    assert!(parse_pairs("[1]\n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let pairs = Day13::parse(&example()).unwrap();
+    assert_eq!(Day13::part1(&pairs).unwrap(), 13);
+    assert_eq!(Day13::part2(&pairs).unwrap(), 140);
+}
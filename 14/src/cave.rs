@@ -1,9 +1,13 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-use anyhow::{anyhow, Context};
-use common::twod::{Grid, Point};
+use common::{
+    image::{Image, Rgb},
+    parse::{parse_token, ParseError},
+    twod::{Bounds, GridCell, InfiniteGrid, Point},
+    viz::{Color, Glyph, Scene},
+};
 
-const SAND_SOURCE_X: i32 = 500;
+const SAND_SOURCE: Point = Point::new(500, 0);
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Tile {
@@ -13,9 +17,19 @@ pub enum Tile {
     SandSource,
 }
 
-impl From<Tile> for char {
-    fn from(value: Tile) -> Self {
-        match value {
+impl GridCell for Tile {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '.' => Some(Tile::Air),
+            '#' => Some(Tile::Rock),
+            'o' => Some(Tile::Sand),
+            '+' => Some(Tile::SandSource),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
             Tile::Air => '.',
             Tile::Rock => '#',
             Tile::Sand => 'o',
@@ -24,20 +38,40 @@ impl From<Tile> for char {
     }
 }
 
+/// A slice of the cave, with sand pouring in at 500,0
 pub struct Cave {
-    grid: Grid<Tile>,
-    x_offset: i32,
-    y_max: i32, // below y_max, sand falls into the void
+    grid: InfiniteGrid<Tile>,
+    kind: CaveType,
+    y_max: i32, // below y_max, sand falls into the void; at y_max lies the bottom, if any
 }
 
 impl FromStr for Cave {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::from_str_int(s, CaveType::Bottomless)
+        Ok(Self::new(&parse_paths(s)?))
     }
 }
 
+/// Parses the scanned rock structures, one path per line
+pub fn parse_paths(s: &str) -> Result<Vec<Vec<Point>>, ParseError> {
+    s.lines()
+        .map(|ln| {
+            ln.split(" -> ")
+                .map(|point_str| {
+                    let (x, y) = point_str.split_once(',').ok_or_else(|| {
+                        ParseError::at(s, point_str, "expected a point in format x,y")
+                    })?;
+                    Ok(Point {
+                        x: parse_token(s, x, "x coordinate")?,
+                        y: parse_token(s, y, "y coordinate")?,
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum CaveType {
     Bottomless,
@@ -45,95 +79,104 @@ enum CaveType {
 }
 
 impl Cave {
-    pub fn from_str_with_bottom(s: &str) -> anyhow::Result<Self> {
-        Self::from_str_int(s, CaveType::WithBottom)
+    /// A cave whose sand falls into the void below the lowest rock
+    pub fn new(paths: &[Vec<Point>]) -> Self {
+        Self::from_paths(paths, CaveType::Bottomless)
     }
 
-    fn from_str_int(s: &str, kind: CaveType) -> anyhow::Result<Self> {
-        let paths = s
-            .lines()
-            .map(|ln| {
-                ln.split(" -> ")
-                    .map(|point_str| -> anyhow::Result<Point> {
-                        let (x, y) = point_str
-                            .split_once(',')
-                            .ok_or(anyhow!("Could not parse point '{point_str}'"))?;
-                        Ok(Point {
-                            x: x.parse().context(
-                                "Failed to parse x coordinate in '{point_str}'",
-                            )?,
-                            y: y.parse().context(
-                                "Failed to parse y coordinate in '{point_str}'",
-                            )?,
-                        })
-                    })
-                    .collect::<Result<Vec<_>, _>>()
-                    .context("Failed to parse path {ln}")
-            })
-            .collect::<Result<Vec<Vec<_>>, _>>()?;
-        let paths_x_min = paths.iter().flatten().map(|p| p.x).min().unwrap_or(0);
-        let paths_x_max = paths.iter().flatten().map(|p| p.x).max().unwrap_or(0);
+    /// A cave with an infinitely wide floor two below the lowest rock
+    pub fn with_bottom(paths: &[Vec<Point>]) -> Self {
+        Self::from_paths(paths, CaveType::WithBottom)
+    }
+
+    fn from_paths(paths: &[Vec<Point>], kind: CaveType) -> Self {
         let paths_y_max = paths.iter().flatten().map(|p| p.y).max().unwrap_or(0);
         let y_max = match kind {
             CaveType::Bottomless => paths_y_max,
             CaveType::WithBottom => 2 + paths_y_max,
         };
-        let x_offset = match kind {
-            CaveType::Bottomless => paths_x_min,
-            CaveType::WithBottom => {
-                std::cmp::min(paths_x_min - paths_y_max, SAND_SOURCE_X - y_max)
-            } // in case it fills a pyramid
-        };
-        let width = match kind {
-            CaveType::Bottomless => (paths_x_max - paths_x_min + 1) as usize,
-            CaveType::WithBottom => {
-                (paths_x_max - x_offset + 1 + 2 * paths_y_max) as usize
-            }
-        };
-        let grid_size = width * (y_max + 1) as usize;
-        let mut grid = Grid {
-            data: vec![Tile::Air; grid_size],
-            width,
-        };
+        let mut grid = InfiniteGrid::new(Tile::Air);
         for path in paths.iter() {
-            let mut offset_path = path.clone();
-            offset_path.iter_mut().for_each(|p| p.x -= x_offset);
-            grid.fill_path(&offset_path, Tile::Rock);
+            grid.fill_path(path, Tile::Rock);
         }
-        if kind == CaveType::WithBottom {
-            grid.fill_line(
-                Point { x: 0, y: y_max },
-                Point {
-                    x: width as i32 - 1,
-                    y: y_max,
-                },
-                Tile::Rock,
-            );
+        grid.set(SAND_SOURCE, Tile::SandSource);
+        Cave { grid, kind, y_max }
+    }
+
+    /// The bottom is not stored in the grid since it is infinitely wide
+    fn tile(&self, p: Point) -> Tile {
+        if self.kind == CaveType::WithBottom && p.y == self.y_max {
+            Tile::Rock
+        } else {
+            *self.grid.get(p)
         }
-        grid[Point {
-            x: SAND_SOURCE_X - x_offset,
-            y: 0,
-        }] = Tile::SandSource;
-        Ok(Cave {
-            grid,
-            x_offset,
-            y_max,
-        })
     }
 }
 
-impl ToString for Cave {
-    fn to_string(&self) -> String {
-        self.grid
-            .data
-            .chunks(self.grid.width)
-            .enumerate()
-            .map(|(y, ln)| {
-                let line = ln.iter().cloned().map(char::from).collect::<String>();
-                format!("{:04} {}", y, line)
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = self.bounds();
+        for y in bounds.min.y..=bounds.max.y {
+            if y > bounds.min.y {
+                writeln!(f)?;
+            }
+            let line = (bounds.min.x..=bounds.max.x)
+                .map(|x| self.tile(Point { x, y }).to_char())
+                .collect::<String>();
+            write!(f, "{:04} {}", y, line)?;
+        }
+        Ok(())
+    }
+}
+
+impl Scene for Cave {
+    fn bounds(&self) -> Bounds {
+        let mut bounds = self.grid.bounds().unwrap_or(Bounds::of_point(SAND_SOURCE));
+        if self.kind == CaveType::WithBottom {
+            bounds = bounds.including(Point {
+                x: bounds.min.x,
+                y: self.y_max,
+            });
+        }
+        bounds
+    }
+
+    fn focus(&self) -> Point {
+        SAND_SOURCE
+    }
+
+    fn glyph(&self, p: Point) -> Glyph {
+        let tile = self.tile(p);
+        let color = match tile {
+            Tile::Air => Color::Gray,
+            Tile::Rock => Color::White,
+            Tile::Sand => Color::Yellow,
+            Tile::SandSource => Color::Red,
+        };
+        Glyph::new(tile.to_char(), color)
+    }
+}
+
+/// A cave with a unit of sand on its way down
+pub struct FallingSand<'a> {
+    pub cave: &'a Cave,
+    pub grain: Point,
+}
+
+impl Scene for FallingSand<'_> {
+    fn bounds(&self) -> Bounds {
+        self.cave.bounds().including(self.grain)
+    }
+
+    fn focus(&self) -> Point {
+        self.grain
+    }
+
+    fn glyph(&self, p: Point) -> Glyph {
+        match p == self.grain {
+            true => Glyph::new('o', Color::Red),
+            false => self.cave.glyph(p),
+        }
     }
 }
 
@@ -144,57 +187,66 @@ enum DropSandResult {
 }
 
 impl Cave {
-    fn drop_sand(&mut self, p: Point) -> DropSandResult {
+    fn drop_sand(
+        &mut self,
+        p: Point,
+        observe: &mut impl FnMut(&Cave, Point),
+    ) -> DropSandResult {
         let mut p = p;
-        if self.grid[p] == Tile::Sand {
+        if self.tile(p) == Tile::Sand {
             return DropSandResult::Overflow;
         }
         let mut falling = true;
         while falling {
             falling = false;
-            for dest in [
-                Point { x: p.x, y: p.y + 1 },
-                Point {
-                    x: p.x - 1,
-                    y: p.y + 1,
-                },
-                Point {
-                    x: p.x + 1,
-                    y: p.y + 1,
-                },
-            ] {
-                if dest.x < 0 || dest.x >= self.grid.width as i32 || dest.y > self.y_max {
+            for dest in
+                [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)].map(|off| p + off)
+            {
+                if dest.y > self.y_max {
                     return DropSandResult::Overflow;
-                } else if self.grid[dest] == Tile::Air {
+                } else if self.tile(dest) == Tile::Air {
                     p = dest;
+                    observe(self, p);
                     falling = true;
                     break;
                 }
             }
         }
-        self.grid[p] = Tile::Sand;
-        return DropSandResult::SandRests;
+        self.grid.set(p, Tile::Sand);
+        DropSandResult::SandRests
     }
 
+    /// Drops sand until it falls into the void or blocks the source
     pub fn fill_sand(&mut self) {
-        let spawn_point = Point {
-            x: SAND_SOURCE_X - self.x_offset,
-            y: 0,
-        };
+        self.fill_sand_with(|_, _| ());
+    }
+
+    /// Like `fill_sand`, calling `observe` with each position of the falling sand
+    pub fn fill_sand_with(&mut self, mut observe: impl FnMut(&Cave, Point)) {
         loop {
-            let res = self.drop_sand(spawn_point);
+            let res = self.drop_sand(SAND_SOURCE, &mut observe);
             if res == DropSandResult::Overflow {
                 return;
             }
         }
     }
 
+    pub fn to_image(&self) -> Image {
+        let bounds = self.bounds();
+        Image::from_fn(bounds.width(), bounds.height(), |x, y| {
+            match self.tile(bounds.min + Point::new(x as i32, y as i32)) {
+                Tile::Air => Rgb::WHITE,
+                Tile::Rock => Rgb::GRAY,
+                Tile::Sand => Rgb::YELLOW,
+                Tile::SandSource => Rgb::RED,
+            }
+        })
+    }
+
     pub fn count_sand(&self) -> usize {
         self.grid
-            .data
             .iter()
-            .cloned()
-            .filter(|&tile| tile == Tile::Sand)
+            .filter(|&(_, &tile)| tile == Tile::Sand)
             .count()
     }
 }
@@ -250,13 +302,15 @@ mod tests {
 
     #[test]
     fn fill_sample_cave_with_bottom() {
-        let mut cave: Cave = Cave::from_str_with_bottom(
-            "498,4 -> 498,6 -> 496,6\n\
-             503,4 -> 502,4 -> 502,9 -> 494,9",
-        )
-        .unwrap();
+        let mut cave: Cave = Cave::with_bottom(
+            &parse_paths(
+                "498,4 -> 498,6 -> 496,6\n\
+                 503,4 -> 502,4 -> 502,9 -> 494,9",
+            )
+            .unwrap(),
+        );
         cave.fill_sand();
-        println!("{}", cave.to_string());
+        println!("{cave}");
         assert_eq!(cave.count_sand(), 93);
     }
 }
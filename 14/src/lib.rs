@@ -0,0 +1,85 @@
+//! Day 14: Regolith Reservoir, pouring sand into a cave of rock paths
+
+pub mod cave;
+
+use cave::{Cave, FallingSand};
+use common::{
+    image::Drawing,
+    random::Rng,
+    solution::Solution,
+    twod::Point,
+    viz::{self, Animator},
+};
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const TITLE: &'static str = "Regolith Reservoir";
+
+    type Params = ();
+    /// Paths of rock
+    type Input = Vec<Vec<Point>>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(cave::parse_paths(input)?)
+    }
+
+    fn part1(paths: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        let mut cave = Cave::new(paths);
+        cave.fill_sand();
+        Ok(cave.count_sand())
+    }
+
+    fn part2(paths: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        let mut cave = Cave::with_bottom(paths);
+        cave.fill_sand();
+        Ok(cave.count_sand())
+    }
+
+    /// The cave of part two, filled with sand
+    fn draw(paths: &Self::Input) -> anyhow::Result<Drawing> {
+        let mut cave = Cave::with_bottom(paths);
+        cave.fill_sand();
+        Ok(cave.to_image().into())
+    }
+
+    /// `size` paths of rock below the source of the sand, each with up to five segments
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let depth = 20 + size as i64;
+        Ok((0..size)
+            .map(|_| {
+                let mut p = (rng.range(500 - depth..=500 + depth), rng.range(2..=depth));
+                let mut points = vec![p];
+                let mut horizontal = rng.chance(0.5);
+                for _ in 0..rng.range(1..=5) {
+                    let step = rng.range(1..=8) * rng.pick(&[-1, 1]);
+                    p = match horizontal {
+                        true => (p.0 + step, p.1),
+                        false => (p.0, (p.1 + step).max(2)),
+                    };
+                    points.push(p);
+                    horizontal = !horizontal;
+                }
+                let points: Vec<String> =
+                    points.iter().map(|(x, y)| format!("{x},{y}")).collect();
+                points.join(" -> ") + "\n"
+            })
+            .collect())
+    }
+}
+
+/// Shows the sand filling the caves of both parts on the terminal
+pub fn animate(paths: &[Vec<Point>], opts: &viz::Options) -> anyhow::Result<()> {
+    let mut animator = Animator::stdout(opts.clone())?;
+    for (part, mut cave) in [(1, Cave::new(paths)), (2, Cave::with_bottom(paths))] {
+        let caption = format!("part {part}");
+        cave.fill_sand_with(|cave, grain| {
+            animator.frame(&FallingSand { cave, grain }, &caption)
+        });
+        animator.key_frame(&cave, &format!("{caption}: {} units", cave.count_sand()));
+    }
+    animator.finish()
+}
@@ -1,18 +1,28 @@
-mod cave;
+use common::{
+    image,
+    input::DayInputs,
+    logging,
+    params::ParamSpec,
+    solution::{self, Format, Solution},
+    viz,
+};
+use day14::Day14;
 
-use cave::Cave;
-use std::{env, fs};
-
-fn main() {
-    let input_file_path = env::args().nth(1).unwrap_or("14/input.txt".into());
-    let input = fs::read_to_string(&input_file_path).unwrap();
-    let mut cave: Cave = input.parse().unwrap();
-    cave.fill_sand();
-    let sand_count = cave.count_sand();
-    // println!("{}", cave.to_string());
+fn main() -> anyhow::Result<()> {
+    logging::init_from_args()?;
+    let input = DayInputs::new(env!("CARGO_MANIFEST_DIR")).read_from_args()?;
+    let params = ParamSpec::from_args(Day14::DAY)?;
+    let paths = Day14::parse_with(&input, &params.resolve()?)?;
+    image::save_from_args::<Day14>(&paths)?;
+    if let Some(opts) = viz::Options::from_args()? {
+        day14::animate(&paths, &opts)?;
+    }
+    if Format::from_args()? == Format::Json {
+        return solution::print_json::<Day14>(&input, &params);
+    }
+    let sand_count = Day14::part1(&paths)?;
     println!("{sand_count} units of sand have come to rest");
-    let mut cave2 = Cave::from_str_with_bottom(&input).unwrap();
-    cave2.fill_sand();
-    let sand_count = cave2.count_sand();
+    let sand_count = Day14::part2(&paths)?;
     println!("{sand_count} units of sand have come to rest in the cave with bottom");
+    Ok(())
 }
@@ -0,0 +1,35 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day14::{
+    cave::{parse_paths, Cave},
+    Day14,
+};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn fills_caves() {
+    // a cup holding one unit of sand, and another one on top blocking the source
+    let paths = parse_paths("499,1 -> 499,2 -> 501,2 -> 501,1\n").unwrap();
+    let mut cave = Cave::new(&paths);
+    cave.fill_sand();
+    assert_eq!(cave.count_sand(), 2);
+    // without rocks, the floor two below the source holds a pyramid
+    let mut cave = Cave::with_bottom(&[]);
+    cave.fill_sand();
+    assert_eq!(cave.count_sand(), 4);
+    assert!(parse_paths("499 -> 499,2\n").is_err());
+}
+
+#[test]
+fn example_answers() {
+    let paths = Day14::parse(&example()).unwrap();
+    assert_eq!(Day14::part1(&paths).unwrap(), 24);
+    assert_eq!(Day14::part2(&paths).unwrap(), 93);
+}
@@ -0,0 +1,349 @@
+//! Day 15: Beacon Exclusion Zone, finding where sensors leave room for a beacon
+
+use anyhow::Context;
+use common::{
+    cancel::{self, Cancelled},
+    image::{Drawing, Rgb},
+    interval::{Interval, IntervalSet},
+    params::{self, Preset},
+    parse::ParseError,
+    pool,
+    progress::Progress,
+    random::Rng,
+    solution::Solution,
+    twod::Point,
+};
+use nom::{bytes::complete::tag, sequence::tuple};
+use std::collections::HashSet;
+
+/// A sensor and the beacon closest to it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SensorReport {
+    sensor: Point,
+    beacon: Point,
+}
+
+impl SensorReport {
+    pub fn new(sensor: Point, beacon: Point) -> Self {
+        Self { sensor, beacon }
+    }
+
+    pub fn sensor(&self) -> Point {
+        self.sensor
+    }
+
+    pub fn beacon(&self) -> Point {
+        self.beacon
+    }
+}
+
+fn parse_sensor_report(input: &str) -> nom::IResult<&str, SensorReport> {
+    let (input, (_, sensor_x, _, sensor_y, _, beacon_x, _, beacon_y)) = tuple((
+        tag("Sensor at x="),
+        nom::character::complete::i32,
+        tag(", y="),
+        nom::character::complete::i32,
+        tag(": closest beacon is at x="),
+        nom::character::complete::i32,
+        tag(", y="),
+        nom::character::complete::i32,
+    ))(input)?;
+    Ok((
+        input,
+        SensorReport {
+            sensor: Point {
+                x: sensor_x,
+                y: sensor_y,
+            },
+            beacon: Point {
+                x: beacon_x,
+                y: beacon_y,
+            },
+        },
+    ))
+}
+
+/// The positions of `row` that the sensor of `rep` covers, if any
+///
+/// In `i64`, as sensors near the ends of the `i32` range reach beyond them.
+fn coverage(rep: &SensorReport, row: i32) -> Option<Interval<i64>> {
+    let reach = rep.sensor.manhattan(rep.beacon) - row.abs_diff(rep.sensor.y) as i64;
+    let x = rep.sensor.x as i64;
+    (reach >= 0).then(|| Interval::closed(x - reach, x + reach))
+}
+
+/// Number of positions in the row where no beacon can be, since a sensor's closest beacon
+/// would be there otherwise
+pub fn part1_get_num_covered_positions(
+    reports: &[SensorReport],
+    row_of_interest: i32,
+) -> usize {
+    let mut no_beacon_coverage = IntervalSet::new();
+    let mut beacons_in_line = HashSet::<i32>::new();
+    for rep in reports {
+        if rep.beacon.y == row_of_interest {
+            beacons_in_line.insert(rep.beacon.x);
+        }
+        no_beacon_coverage.extend(coverage(rep, row_of_interest));
+    }
+    no_beacon_coverage.len() as usize - beacons_in_line.len()
+}
+
+/// The only position with both coordinates in `0..=xy_max` that no sensor covers
+///
+/// Stops early if [`cancel`]led, e.g., for a search area too large to search in time.
+pub fn part2_get_beacon_position(
+    reports: &[SensorReport],
+    xy_max: i32,
+) -> Result<Option<(i32, i32)>, Cancelled> {
+    // rows are independent, so they are searched in parallel
+    let rows = (xy_max as i64 + 1).max(0) as usize;
+    let progress = Progress::new(module_path!(), "rows", Some(rows as u64));
+    // a cancelled row counts as found, so that rows below it still get searched
+    pool::find_map_first(0..rows, |y| {
+        if let Err(cancelled) = cancel::check() {
+            return Some(Err(cancelled));
+        }
+        progress.add(1);
+        let y = y as i32;
+        let mut no_beacon_coverage = IntervalSet::new();
+        no_beacon_coverage.extend(reports.iter().filter_map(|rep| coverage(rep, y)));
+        let gaps = no_beacon_coverage.complement(Interval::closed(0, xy_max as i64));
+        // within the search area, so in the range of an i32
+        gaps.first().map(|gap| Ok((gap.start() as i32, y)))
+    })
+    .transpose()
+}
+
+/// Where to look: the example uses a much smaller area than the real inputs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    /// The row to check in part one
+    pub row: i32,
+    /// Largest coordinate of the search area of part two
+    pub xy_max: i32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            row: 2000000,
+            xy_max: 4000000,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Example => Self {
+                row: 10,
+                xy_max: 20,
+            },
+            Preset::Real => Self::default(),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "row" => self.row = params::parse_value(key, value)?,
+            "xy_max" => self.xy_max = params::parse_value(key, value)?,
+            _ => return Err(params::unknown(key, &["row", "xy_max"])),
+        }
+        Ok(())
+    }
+}
+
+/// The sensor reports and where to look
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scan {
+    reports: Vec<SensorReport>,
+    params: Params,
+}
+
+impl Scan {
+    pub fn reports(&self) -> &[SensorReport] {
+        &self.reports
+    }
+}
+
+fn parse_scan(input: &str, params: &Params) -> Result<Scan, ParseError> {
+    let reports = input
+        .lines()
+        .map(|ln| match parse_sensor_report(ln) {
+            Ok(("", rep)) => Ok(rep),
+            Ok((rest, _)) => {
+                Err(ParseError::at(input, rest, "unexpected text after report"))
+            }
+            Err(e) => Err(ParseError::from_nom(input, e)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Scan {
+        reports,
+        params: params.clone(),
+    })
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    const TITLE: &'static str = "Beacon Exclusion Zone";
+
+    type Params = Params;
+    type Input = Scan;
+    type Answer1 = usize;
+    /// Tuning frequency of the distress beacon
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_with(input, &Params::default())
+    }
+
+    fn parse_with(input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(parse_scan(input, params)?)
+    }
+
+    fn part1(scan: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(part1_get_num_covered_positions(
+            &scan.reports,
+            scan.params.row,
+        ))
+    }
+
+    fn part2(scan: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        let distress_beacon_pos =
+            part2_get_beacon_position(&scan.reports, scan.params.xy_max)?
+                .context("Distres beacon was not found")?;
+        Ok(4000000 * distress_beacon_pos.0 as usize + distress_beacon_pos.1 as usize)
+    }
+
+    /// The areas covered by the sensors, with the sensors in red and their beacons in
+    /// blue
+    fn draw(scan: &Self::Input) -> anyhow::Result<Drawing> {
+        let diamond = |rep: &SensorReport| {
+            let (x, y) = (rep.sensor.x as i64, rep.sensor.y as i64);
+            let r = rep.sensor.manhattan(rep.beacon);
+            [(x, y - r), (x + r, y), (x, y + r), (x - r, y)]
+        };
+        let corners: Vec<_> = scan.reports.iter().flat_map(diamond).collect();
+        let min_x = corners.iter().map(|c| c.0).min().unwrap_or(0);
+        let max_x = corners.iter().map(|c| c.0).max().unwrap_or(0);
+        let min_y = corners.iter().map(|c| c.1).min().unwrap_or(0);
+        let max_y = corners.iter().map(|c| c.1).max().unwrap_or(0);
+        let at = move |(x, y): (i64, i64)| ((x - min_x) as f64, (y - min_y) as f64);
+        let mut drawing =
+            Drawing::new((max_x - min_x + 1) as f64, (max_y - min_y + 1) as f64);
+        for (i, rep) in scan.reports.iter().enumerate() {
+            let color = Rgb::categorical(i);
+            drawing.polygon(diamond(rep).map(at), Some(color), None);
+        }
+        let row = scan.params.row as i64;
+        drawing.polyline([at((min_x, row)), at((max_x, row))], Rgb::BLACK);
+        let size = (max_x - min_x) as f64 / 200.0;
+        let marks = scan
+            .reports
+            .iter()
+            .flat_map(|rep| [(rep.sensor, Rgb::RED), (rep.beacon, Rgb::BLUE)]);
+        for (p, color) in marks {
+            let (x, y) = at((p.x as i64, p.y as i64));
+            drawing.rect(x - size / 2.0, y - size / 2.0, size, size, color);
+        }
+        Ok(drawing)
+    }
+
+    /// Reports of `size` sensors, at least 4, which leave exactly one position in the
+    /// search area uncovered
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        const XY_MAX: i64 = 4000000;
+        let hidden = (rng.range(0..XY_MAX), rng.range(0..XY_MAX));
+        // sensors diagonally around the hidden beacon, just missing it, cover the rest
+        let mut reports: Vec<_> = [(-1, -1), (-1, 1), (1, -1), (1, 1)]
+            .iter()
+            .map(|&(sx, sy)| {
+                let sensor = (hidden.0 + sx * XY_MAX, hidden.1 + sy * XY_MAX);
+                (sensor, (hidden.0, hidden.1 + sy))
+            })
+            .collect();
+        while reports.len() < size {
+            let sensor = (rng.range(0..=XY_MAX), rng.range(0..=XY_MAX));
+            let to_hidden = (sensor.0 - hidden.0).abs() + (sensor.1 - hidden.1).abs();
+            let reach = rng.range(1..to_hidden.clamp(2, XY_MAX / 2));
+            let dx = rng.range(-reach..=reach);
+            let dy = (reach - dx.abs()) * rng.pick(&[-1, 1]);
+            reports.push((sensor, (sensor.0 + dx, sensor.1 + dy)));
+        }
+        rng.shuffle(&mut reports);
+        Ok(reports
+            .iter()
+            .map(|((sx, sy), (bx, by))| {
+                format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}\n")
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covered(reports: &[SensorReport], p: Point) -> bool {
+        reports
+            .iter()
+            .any(|rep| rep.sensor.manhattan(p) <= rep.sensor.manhattan(rep.beacon))
+    }
+
+    /// Checks every position of the row, as far as any sensor reaches
+    fn naive_num_covered_positions(reports: &[SensorReport], row: i32) -> usize {
+        let reach = reports
+            .iter()
+            .map(|rep| (rep.sensor.x as i64).abs() + rep.sensor.manhattan(rep.beacon))
+            .max()
+            .unwrap_or(0);
+        (-reach..=reach)
+            .map(|x| Point::new(x as i32, row))
+            .filter(|&p| covered(reports, p) && reports.iter().all(|rep| rep.beacon != p))
+            .count()
+    }
+
+    /// Checks every position of the search area, row by row
+    fn naive_beacon_position(
+        reports: &[SensorReport],
+        xy_max: i32,
+    ) -> Option<(i32, i32)> {
+        (0..=xy_max)
+            .flat_map(|y| (0..=xy_max).map(move |x| (x, y)))
+            .find(|&(x, y)| !covered(reports, Point::new(x, y)))
+    }
+
+    fn random_reports(rng: &mut Rng) -> Vec<SensorReport> {
+        let count = rng.range(1..=12);
+        let mut coordinate =
+            || Point::new(rng.range(-5..=25) as i32, rng.range(-5..=25) as i32);
+        (0..count)
+            .map(|_| SensorReport {
+                sensor: coordinate(),
+                beacon: coordinate(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn interval_sets_match_naive_on_random_reports() {
+        let mut rng = Rng::new(15);
+        for _ in 0..1000 {
+            let reports = random_reports(&mut rng);
+            let row = rng.range(-5..=25) as i32;
+            assert_eq!(
+                part1_get_num_covered_positions(&reports, row),
+                naive_num_covered_positions(&reports, row),
+                "row {row} of {reports:?}"
+            );
+            assert_eq!(
+                part2_get_beacon_position(&reports, 20).unwrap(),
+                naive_beacon_position(&reports, 20),
+                "{reports:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use common::{
+    cancel::{self, Cancelled, Token},
+    input::{DayInputs, InputSpec},
+    params::{Params as _, Preset},
+    solution::Solution,
+    twod::Point,
+};
+use day15::{
+    part1_get_num_covered_positions, part2_get_beacon_position, Day15, Params,
+    SensorReport,
+};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn covers_diamonds() {
+    // covers the diamond of radius 2 around 0,0, the beacon sits on its edge
+    let report = SensorReport::new(Point::new(0, 0), Point::new(2, 0));
+    assert_eq!(part1_get_num_covered_positions(&[report], 0), 4);
+    assert_eq!(part1_get_num_covered_positions(&[report], 2), 1);
+    assert_eq!(part1_get_num_covered_positions(&[report], 3), 0);
+    assert_eq!(part2_get_beacon_position(&[report], 1).unwrap(), None);
+    assert_eq!(
+        part2_get_beacon_position(&[report], 2).unwrap(),
+        Some((2, 1))
+    );
+}
+
+#[test]
+fn reaches_beyond_the_ends_of_i32() {
+    // the sensor's diamond spans the whole i32 range and then some
+    let report = SensorReport::new(Point::new(i32::MAX, 0), Point::new(i32::MIN, 0));
+    let width = 2 * u32::MAX as usize + 1;
+    assert_eq!(part1_get_num_covered_positions(&[report], 0), width - 1);
+    assert_eq!(
+        part1_get_num_covered_positions(&[report], i32::MIN),
+        u32::MAX as usize
+    );
+    assert_eq!(part2_get_beacon_position(&[report], 20).unwrap(), None);
+}
+
+#[test]
+fn example_answers() {
+    let scan = Day15::parse_with(&example(), &Params::preset(Preset::Example)).unwrap();
+    assert_eq!(scan.reports().len(), 14);
+    assert_eq!(scan.reports()[0].sensor(), Point::new(2, 18));
+    assert_eq!(scan.reports()[0].beacon(), Point::new(-2, 15));
+    assert_eq!(Day15::part1(&scan).unwrap(), 26);
+    assert_eq!(Day15::part2(&scan).unwrap(), 56000011);
+}
+
+#[test]
+fn example_in_another_row() {
+    let params = Params {
+        row: 11,
+        ..Params::preset(Preset::Example)
+    };
+    let scan = Day15::parse_with(&example(), &params).unwrap();
+    // the row of the distress beacon, at 14,11
+    assert_eq!(Day15::part1(&scan).unwrap(), 28);
+}
+
+#[test]
+fn cancelled_search() {
+    let scan = Day15::parse_with(&example(), &Params::preset(Preset::Example)).unwrap();
+    let token = Token::new();
+    token.cancel();
+    let found = cancel::scope(token, || part2_get_beacon_position(scan.reports(), 20));
+    assert_eq!(found, Err(Cancelled::default()));
+    // part one has no long loop to stop
+    assert!(
+        cancel::scope(Token::with_timeout(Duration::ZERO), || Day15::part1(&scan))
+            .is_ok()
+    );
+}
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fmt::Display, str::FromStr};
 
-use anyhow::anyhow;
+use common::parse::ParseError;
 use nom::{
     branch::alt,
     bytes::complete::{tag, take},
@@ -9,7 +9,8 @@ use nom::{
     sequence::tuple,
 };
 
-#[derive(Hash, PartialEq, Eq, Copy, Clone)]
+/// Two letter name of a valve, e.g., `AA`
+#[derive(Hash, PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub struct ValveLabel([u8; 2]);
 
 #[derive(Debug, PartialEq)]
@@ -18,6 +19,7 @@ pub struct Valve {
     pub tunnels: Vec<ValveLabel>,
 }
 
+/// The scanned valves by label
 #[derive(Debug)]
 pub struct Cave(pub HashMap<ValveLabel, Valve>);
 
@@ -34,21 +36,16 @@ impl std::fmt::Debug for ValveLabel {
 }
 
 impl FromStr for ValveLabel {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() != 2 || !s.chars().all(|c| c.is_ascii()) {
-            return Err(anyhow!("Invalid valve label '{s}'"));
+        if s.len() != 2 || !s.is_ascii() {
+            return Err(ParseError::at(s, s, format!("invalid valve label '{s}'")));
         }
         Ok({
             let iter = s.bytes();
             let mut iter = iter.into_iter();
-            ValveLabel {
-                0: [
-                    iter.next().unwrap_or('?' as u8),
-                    iter.next().unwrap_or('?' as u8),
-                ],
-            }
+            ValveLabel([iter.next().unwrap_or(b'?'), iter.next().unwrap_or(b'?')])
         })
     }
 }
@@ -61,7 +58,13 @@ impl std::ops::Deref for Cave {
     }
 }
 
-fn parse_valve(i: &str) -> nom::IResult<&str, (ValveLabel, Valve)> {
+/// Where the labels of a valve's line are in the input, to point errors at them
+struct LabelSpans<'a> {
+    label: &'a str,
+    tunnels: Vec<&'a str>,
+}
+
+fn parse_valve(i: &str) -> nom::IResult<&str, (ValveLabel, Valve, LabelSpans<'_>)> {
     let (i, (_, label, _, flow_rate, _, _, tunnels)) = tuple((
         tag("Valve "),
         take(2usize),
@@ -71,33 +74,24 @@ fn parse_valve(i: &str) -> nom::IResult<&str, (ValveLabel, Valve)> {
         alt((tag("valves "), tag("valve "))),
         separated_list0(tag(", "), take(2usize)),
     ))(i)?;
+    // Point errors at the offending part of the line
+    let fail = |input, code| nom::Err::Error(nom::error::Error { input, code });
     Ok((
         i,
         (
-            label.parse().map_err(|_| {
-                nom::Err::Error(nom::error::Error {
-                    input: i,
-                    code: nom::error::ErrorKind::Tag,
-                })
-            })?,
+            label
+                .parse()
+                .map_err(|_| fail(label, nom::error::ErrorKind::Tag))?,
             Valve {
-                flow_rate: flow_rate.parse().map_err(|_| {
-                    nom::Err::Error(nom::error::Error {
-                        input: i,
-                        code: nom::error::ErrorKind::Digit,
-                    })
-                })?,
+                flow_rate: flow_rate
+                    .parse()
+                    .map_err(|_| fail(flow_rate, nom::error::ErrorKind::Digit))?,
                 tunnels: tunnels
                     .iter()
-                    .map(|e| e.parse())
-                    .collect::<Result<Vec<_>, _>>()
-                    .map_err(|_| {
-                        nom::Err::Error(nom::error::Error {
-                            input: i,
-                            code: nom::error::ErrorKind::Tag,
-                        })
-                    })?,
+                    .map(|&e| e.parse().map_err(|_| fail(e, nom::error::ErrorKind::Tag)))
+                    .collect::<Result<Vec<_>, _>>()?,
             },
+            LabelSpans { label, tunnels },
         ),
     ))
 }
@@ -105,8 +99,10 @@ fn parse_valve(i: &str) -> nom::IResult<&str, (ValveLabel, Valve)> {
 #[test]
 fn test_parse_valve() {
     let s = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB";
+    let (_, (label, valve, spans)) = parse_valve(s).unwrap();
+    assert_eq!((spans.label, spans.tunnels), ("AA", vec!["DD", "II", "BB"]));
     assert_eq!(
-        parse_valve(s).unwrap().1,
+        (label, valve),
         (
             "AA".parse().unwrap(),
             Valve {
@@ -122,14 +118,58 @@ fn test_parse_valve() {
 }
 
 impl FromStr for Cave {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Cave(
-            s.lines()
-                .map(|ln| parse_valve(ln).map(|(_, v)| v))
-                .collect::<Result<HashMap<ValveLabel, Valve>, _>>()
-                .map_err(|e| anyhow::anyhow!("Error parsing input: {e}"))?,
-        ))
+        let mut valves = HashMap::new();
+        let mut tunnels = vec![];
+        for ln in s.lines() {
+            let (label, valve, spans) = match parse_valve(ln) {
+                Ok(("", v)) => v,
+                Ok((rest, _)) => return Err(ParseError::at(s, rest, "unexpected text")),
+                Err(e) => return Err(ParseError::from_nom(s, e)),
+            };
+            tunnels.extend(spans.tunnels.into_iter().zip(valve.tunnels.clone()));
+            if valves.insert(label, valve).is_some() {
+                return Err(ParseError::at(
+                    s,
+                    spans.label,
+                    format!("duplicate valve {label}"),
+                ));
+            }
+        }
+        // checked once all valves are known, as tunnels may lead to later lines
+        if let Some((span, label)) = tunnels.iter().find(|(_, l)| !valves.contains_key(l))
+        {
+            return Err(ParseError::at(
+                s,
+                span,
+                format!("tunnel to unknown valve {label}"),
+            ));
+        }
+        Ok(Cave(valves))
     }
 }
+
+#[test]
+fn test_parse_cave_rejects_unknown_and_duplicate_valves() {
+    let s = "Valve AA has flow rate=0; tunnels lead to valves BB, CC\n\
+             Valve BB has flow rate=1; tunnel leads to valve AA";
+    let err = s.parse::<Cave>().unwrap_err();
+    assert_eq!((err.line(), err.column()), (1, 54));
+    assert_eq!(err.message(), "tunnel to unknown valve CC");
+    let s = "Valve AA has flow rate=0; tunnel leads to valve AA\n\
+             Valve AA has flow rate=1; tunnel leads to valve AA";
+    let err = s.parse::<Cave>().unwrap_err();
+    assert_eq!((err.line(), err.column()), (2, 7));
+    assert_eq!(err.message(), "duplicate valve AA");
+}
+
+#[test]
+fn test_parse_cave_reports_location() {
+    let s = "Valve AA has flow rate=0; tunnels lead to valves BB\n\
+             Valve BB has flow rate=x; tunnel leads to valve AA";
+    let err = s.parse::<Cave>().unwrap_err();
+    assert_eq!((err.line(), err.column()), (2, 24));
+    assert_eq!(err.message(), "expected digit");
+}
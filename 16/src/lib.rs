@@ -0,0 +1,303 @@
+//! Day 16: Proboscidea Volcanium, releasing the most pressure by opening valves
+
+use common::{
+    image::{Drawing, Rgb},
+    params,
+    random::Rng,
+    solution::Solution,
+};
+
+use crate::pathfinder::find_pressure_release_potential;
+
+pub mod input;
+pub mod pathfinder;
+pub mod preprocessing;
+
+/// A connected network of `count` valves, `flow_valves` of which have a flow rate
+fn generate_network(rng: &mut Rng, count: usize, flow_valves: usize) -> String {
+    let mut labels = vec!["AA".to_owned()];
+    while labels.len() < count {
+        let label: String = (0..2)
+            .map(|_| (b'A' + rng.below(26) as u8) as char)
+            .collect();
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    let mut flow_rates = vec![0; count];
+    let mut with_flow: Vec<usize> = (1..count).collect();
+    rng.shuffle(&mut with_flow);
+    for &idx in with_flow.iter().take(flow_valves) {
+        flow_rates[idx] = rng.range(1..=25);
+    }
+    // a random tree keeps the network connected, a few more tunnels add loops
+    let mut tunnels = vec![vec![]; count];
+    let mut connect = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for idx in 1..count {
+        connect(idx, rng.index(idx));
+    }
+    for _ in 0..count / 4 {
+        connect(rng.index(count), rng.index(count));
+    }
+    let mut order: Vec<usize> = (0..count).collect();
+    rng.shuffle(&mut order);
+    order
+        .iter()
+        .map(|&idx| {
+            let to: Vec<&str> =
+                tunnels[idx].iter().map(|&t| labels[t].as_str()).collect();
+            let tunnels = match to.len() {
+                1 => "tunnel leads to valve",
+                _ => "tunnels lead to valves",
+            };
+            format!(
+                "Valve {} has flow rate={}; {tunnels} {}\n",
+                labels[idx],
+                flow_rates[idx],
+                to.join(", ")
+            )
+        })
+        .collect()
+}
+
+/// Where the actors start and how long they have, the same for the example
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub start: input::ValveLabel,
+    /// Time to release pressure alone in part one
+    pub part1_minutes: u32,
+    /// Time to release pressure together with the elephant in part two
+    pub part2_minutes: u32,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            start: "AA".parse().expect("AA is a valid label"),
+            part1_minutes: 30,
+            part2_minutes: 26,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "start" => self.start = params::parse_value(key, value)?,
+            "part1_minutes" => self.part1_minutes = params::parse_value(key, value)?,
+            "part2_minutes" => self.part2_minutes = params::parse_value(key, value)?,
+            _ => {
+                return Err(params::unknown(
+                    key,
+                    &["start", "part1_minutes", "part2_minutes"],
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The valves and tunnels, and where and for how long to search them
+#[derive(Debug, Clone)]
+pub struct Volcano {
+    pub cave: preprocessing::Cave,
+    pub params: Params,
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u8 = 16;
+    const TITLE: &'static str = "Proboscidea Volcanium";
+
+    type Params = Params;
+    type Input = Volcano;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_with(input, &Params::default())
+    }
+
+    fn parse_with(input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        let cave: input::Cave = input.parse()?;
+        anyhow::ensure!(
+            cave.len() <= preprocessing::ValveBitMask::CAPACITY,
+            "Too many valves: {}, at most {} are supported",
+            cave.len(),
+            preprocessing::ValveBitMask::CAPACITY
+        );
+        Ok(Volcano {
+            cave: preprocessing::Cave::from(&cave),
+            params: params.clone(),
+        })
+    }
+
+    fn part1(volcano: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        let Params {
+            start,
+            part1_minutes,
+            ..
+        } = volcano.params;
+        find_pressure_release_potential(volcano.cave.clone(), vec![start], part1_minutes)
+    }
+
+    fn part2(volcano: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        let Params {
+            start,
+            part2_minutes,
+            ..
+        } = volcano.params;
+        find_pressure_release_potential(
+            volcano.cave.clone(),
+            vec![start, start],
+            part2_minutes,
+        )
+    }
+
+    /// The valves on a circle, ordered by label, and the tunnels between them; valves
+    /// with a flow rate in yellow, sized by the rate, and the start in red
+    fn draw(volcano: &Self::Input) -> anyhow::Result<Drawing> {
+        let (cave, start) = (&volcano.cave, volcano.params.start);
+        let mut order: Vec<usize> = (0..cave.valves.len()).collect();
+        order.sort_by_key(|&idx| cave.valve_labels[idx].to_string());
+        let mut position = vec![(0.0, 0.0); cave.valves.len()];
+        for (n, &idx) in order.iter().enumerate() {
+            let angle = n as f64 / order.len() as f64 * std::f64::consts::TAU;
+            position[idx] = (50.0 + 45.0 * angle.sin(), 50.0 - 45.0 * angle.cos());
+        }
+        let mut drawing = Drawing::new(100.0, 100.0);
+        for (from, valve) in cave.valves.iter().enumerate() {
+            for to in valve.tunnels.iter().filter(|&to| to > from) {
+                drawing.polyline([position[from], position[to]], Rgb::GRAY);
+            }
+        }
+        let max_flow = cave
+            .valves
+            .iter()
+            .map(|v| v.flow_rate)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        for (idx, valve) in cave.valves.iter().enumerate() {
+            let (x, y) = position[idx];
+            let r = 1.0 + 2.0 * valve.flow_rate as f64 / max_flow as f64;
+            let color = match valve.flow_rate {
+                _ if cave.valve_labels[idx] == start => Rgb::RED,
+                0 => Rgb::GRAY,
+                _ => Rgb::YELLOW,
+            };
+            let diamond = [(x, y - r), (x + r, y), (x, y + r), (x - r, y)];
+            drawing.polygon(diamond, Some(Rgb::BLACK), Some(color));
+        }
+        Ok(drawing)
+    }
+
+    /// A connected network of `size` valves, at least 2 and at most the supported 128,
+    /// of which up to 15 have a flow rate
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let count = size.clamp(2, preprocessing::ValveBitMask::CAPACITY);
+        Ok(generate_network(rng, count, (count / 4).clamp(1, 15)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+
+    use super::*;
+
+    /// Exhaustive search over the orders in which the valves could be opened
+    struct Reference {
+        flow_rates: Vec<u32>,
+        /// Minutes between any two valves with a flow rate, the start being the last
+        dist: Vec<Vec<u32>>,
+    }
+
+    impl Reference {
+        fn new(cave: &input::Cave) -> Self {
+            let start: input::ValveLabel = "AA".parse().unwrap();
+            let mut labels: Vec<_> =
+                cave.keys().filter(|l| cave[l].flow_rate > 0).collect();
+            labels.push(&start);
+            let dist_from = |from: &input::ValveLabel| {
+                let mut dist = HashMap::from([(*from, 0)]);
+                let mut queue = VecDeque::from([*from]);
+                while let Some(label) = queue.pop_front() {
+                    for next in &cave[&label].tunnels {
+                        if !dist.contains_key(next) {
+                            dist.insert(*next, dist[&label] + 1);
+                            queue.push_back(*next);
+                        }
+                    }
+                }
+                labels.iter().map(|l| dist[l]).collect()
+            };
+            Self {
+                flow_rates: labels.iter().map(|l| cave[l].flow_rate).collect(),
+                dist: labels.iter().map(|l| dist_from(l)).collect(),
+            }
+        }
+
+        fn start(&self) -> usize {
+            self.flow_rates.len() - 1
+        }
+
+        /// Best pressure released by one actor at `from` opening only `valves`
+        fn best(&self, from: usize, time_left: u32, valves: u32) -> u32 {
+            (0..self.start())
+                .filter(|&v| valves & (1 << v) != 0)
+                .filter(|&v| self.dist[from][v] < time_left)
+                .map(|v| {
+                    let time_left = time_left - self.dist[from][v] - 1;
+                    time_left * self.flow_rates[v]
+                        + self.best(v, time_left, valves & !(1 << v))
+                })
+                .max()
+                .unwrap_or(0)
+        }
+
+        fn part1(&self) -> u32 {
+            self.best(self.start(), 30, (1 << self.start()) - 1)
+        }
+
+        /// Tries every split of the valves between the two actors
+        fn part2(&self) -> u32 {
+            let all = (1u32 << self.start()) - 1;
+            (0..=all)
+                .map(|mine| {
+                    self.best(self.start(), 26, mine)
+                        + self.best(self.start(), 26, all & !mine)
+                })
+                .max()
+                .unwrap()
+        }
+    }
+
+    #[test]
+    fn pruned_search_matches_reference_on_random_networks() {
+        let mut rng = Rng::new(16);
+        for _ in 0..100 {
+            let count = rng.range(2..=12) as usize;
+            let flow_valves = rng.range(1..=6) as usize;
+            let input = generate_network(&mut rng, count, flow_valves);
+            let reference = Reference::new(&input.parse().unwrap());
+            let cave = Day16::parse(&input).unwrap();
+            assert_eq!(Day16::part1(&cave).unwrap(), reference.part1(), "{input}");
+            assert_eq!(Day16::part2(&cave).unwrap(), reference.part2(), "{input}");
+        }
+    }
+
+    #[test]
+    fn reference_solves_example() {
+        let reference =
+            Reference::new(&include_str!("../test_data.txt").parse().unwrap());
+        assert_eq!(reference.part1(), 1651);
+        assert_eq!(reference.part2(), 1707);
+    }
+}
@@ -1,23 +1,28 @@
-use std::{env, fs};
+use common::{
+    image,
+    input::DayInputs,
+    logging,
+    params::ParamSpec,
+    pool,
+    solution::{self, Format, Solution},
+};
+use day16::Day16;
 
-use crate::pathfinder::find_pressure_release_potential;
+fn main() -> anyhow::Result<()> {
+    logging::init_from_args()?;
+    pool::set_threads_from_args()?;
+    let input = DayInputs::new(env!("CARGO_MANIFEST_DIR")).read_from_args()?;
+    let params = ParamSpec::from_args(Day16::DAY)?;
+    let volcano = Day16::parse_with(&input, &params.resolve()?)?;
+    image::save_from_args::<Day16>(&volcano)?;
 
-mod input;
-mod pathfinder;
-mod preprocessing;
-
-fn main() {
-    let input_file_path = env::args().nth(1).unwrap_or("16/test_data.txt".into());
-    let input = fs::read_to_string(&input_file_path).unwrap();
-    let cave: input::Cave = input.parse().unwrap();
-    let cave = preprocessing::Cave::from(&cave);
-
-    let starting_positions: Vec<input::ValveLabel> = vec!["AA".parse().unwrap()];
-    let p = find_pressure_release_potential(cave.clone(), starting_positions, 30).unwrap();
+    if Format::from_args()? == Format::Json {
+        return solution::print_json::<Day16>(&input, &params);
+    }
+    let p = Day16::part1(&volcano)?;
     println!("Part1: We can potentially release {p} units of pressure");
 
-    let starting_positions: Vec<input::ValveLabel> =
-        vec!["AA".parse().unwrap(), "AA".parse().unwrap()];
-    let p = find_pressure_release_potential(cave, starting_positions, 26).unwrap();
+    let p = Day16::part2(&volcano)?;
     println!("Part2: We can potentially release {p} units of pressure");
+    Ok(())
 }
@@ -1,6 +1,18 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+};
 
 use anyhow::{anyhow, Context};
+use common::{
+    cancel::{self, Cancelled},
+    pool,
+    progress::Progress,
+};
 use local_vec::LocalVec;
 
 use crate::{input::ValveLabel, preprocessing::*};
@@ -30,43 +42,106 @@ impl State {
     fn compare_to(&self, other: &State) -> StateCmp {
         if self.score <= other.score
             && self.time_left <= other.time_left
-            && self.closed_valves.is_subset(other.closed_valves)
+            && self.closed_valves.is_subset(&other.closed_valves)
         {
             return StateCmp::StrictlyWorseOrEqual;
         }
         if self.score >= other.score
             && self.time_left >= other.time_left
-            && self.closed_valves.is_superset(other.closed_valves)
+            && self.closed_valves.is_superset(&other.closed_valves)
         {
             // We know here that the states are not equally as good due to the previous
             // check
             return StateCmp::StrictlyBetter;
         }
-        // Extra pruning: If we have more time left, but our score is still lower after an
-        // ideal run of opening valves (assuming the biggest valves are still open), then
-        // we are still worse or equal
-        //
-        // FIXME: I am not sure why we do not need to compare the closed_valves for this
-        // condition; the program produces the right answer for both example and real
-        // input, however, maybe this is just a very good heuristic
-        if self.score < other.score {
-            let score_upper_bound: u32 = self.score
-                + (self.time_left / 2..=other.time_left / 2)
-                    .zip(20..0)
-                    .zip(19..0)
-                    .map(|((t_left, v1), v2)| t_left * (v1 + v2))
-                    .sum::<u32>();
-            if score_upper_bound <= other.score {
-                return StateCmp::StrictlyWorseOrEqual;
+        StateCmp::Unknown
+    }
+
+    /// A score the state cannot beat: the pressure released if each closed valve was
+    /// opened as soon as the closest actor could get there, or if the actors could open
+    /// the largest closed valves one after the other, each taking a minute to move and a
+    /// minute to open, whichever is lower
+    fn score_upper_bound(&self, bounds: &Bounds) -> u32 {
+        let actors = self.positions.len();
+        let by_order: u32 = bounds
+            .by_flow_rate
+            .iter()
+            .filter(|&&(idx, _)| self.closed_valves.contains(idx))
+            .enumerate()
+            .map_while(|(n, &(_, flow_rate))| {
+                let time_left =
+                    self.time_left.checked_sub(1 + 2 * (n / actors) as u32)?;
+                Some(time_left * flow_rate)
+            })
+            .sum();
+        let by_distance: u32 = bounds
+            .by_flow_rate
+            .iter()
+            .filter(|&&(idx, _)| self.closed_valves.contains(idx))
+            .map(|&(idx, flow_rate)| {
+                let dist = self
+                    .positions
+                    .iter()
+                    .map(|&pos| bounds.dist[pos as usize][idx])
+                    .min()
+                    .unwrap_or(u32::MAX);
+                self.time_left.saturating_sub(dist.saturating_add(1)) * flow_rate
+            })
+            .sum();
+        self.score + by_order.min(by_distance)
+    }
+}
+
+/// What is needed to bound the scores of states
+struct Bounds {
+    /// Indices and flow rates of the valves, largest flow rate first
+    by_flow_rate: Vec<(usize, u32)>,
+    /// Minutes it takes to get from one valve to another
+    dist: Vec<Vec<u32>>,
+}
+
+impl Bounds {
+    fn new(cave: &Cave) -> Self {
+        let mut by_flow_rate: Vec<(usize, u32)> = cave
+            .valves
+            .iter()
+            .map(|v| v.flow_rate)
+            .enumerate()
+            .collect();
+        by_flow_rate.sort_by_key(|&(_, flow_rate)| Reverse(flow_rate));
+        // Floyd-Warshall
+        let n = cave.valves.len();
+        let mut dist = vec![vec![u32::MAX; n]; n];
+        for (from, valve) in cave.valves.iter().enumerate() {
+            dist[from][from] = 0;
+            for to in valve.tunnels.iter() {
+                dist[from][to] = 1;
+            }
+        }
+        for via in 0..n {
+            for from in 0..n {
+                for to in 0..n {
+                    let d = dist[from][via].saturating_add(dist[via][to]);
+                    if d < dist[from][to] {
+                        dist[from][to] = d;
+                    }
+                }
             }
         }
-        return StateCmp::Unknown;
+        Self { by_flow_rate, dist }
     }
 }
 
+/// Number of separately locked parts of the memoizer, so that threads rarely wait for
+/// each other
+const MEMOIZER_SHARDS: usize = 64;
+
+/// Best known states for a combination (bit mask) of positions
+type BestKnown = HashMap<ValveBitMask, Vec<State>>;
+
 struct StateMemoizer {
-    /// Best known state for a combination (bit mask) of positions
-    best_known: HashMap<ValveBitMask, Vec<State>>,
+    /// Best known states, sharded by the sum of the positions
+    shards: Vec<Mutex<BestKnown>>,
 }
 
 enum StateMemoizationResult {
@@ -77,14 +152,16 @@ enum StateMemoizationResult {
 impl StateMemoizer {
     fn new() -> Self {
         Self {
-            best_known: HashMap::new(),
+            shards: (0..MEMOIZER_SHARDS).map(|_| Mutex::default()).collect(),
         }
     }
 
-    fn memoize(&mut self, s: &State) -> StateMemoizationResult {
-        let states_at_valve = self
-            .best_known
-            .entry(s.positions.iter().cloned().collect())
+    fn memoize(&self, s: &State) -> StateMemoizationResult {
+        let shard =
+            s.positions.iter().map(|&p| p as usize).sum::<usize>() % MEMOIZER_SHARDS;
+        let mut best_known = self.shards[shard].lock().expect("no thread panicked");
+        let states_at_valve = best_known
+            .entry(s.positions.iter().map(|&p| p as usize).collect())
             .or_insert(vec![]);
         for known in states_at_valve.iter_mut() {
             match s.compare_to(known) {
@@ -101,29 +178,147 @@ impl StateMemoizer {
         states_at_valve.push(s.clone());
         StateMemoizationResult::PotentiallyBest
     }
+}
 
-    fn get_best_score(&self) -> Option<u32> {
-        self.best_known
+impl State {
+    fn is_leaf(&self) -> bool {
+        self.time_left == 0 || self.closed_valves.is_empty()
+    }
+
+    /// The states after the next minute, in which each actor opens the valve at its
+    /// position or moves on through a tunnel
+    ///
+    /// There are as many as the product of the actors' choices, so they are on the heap.
+    fn follow_states(&self, cave: &Cave) -> Vec<State> {
+        let choices = self
+            .positions
             .iter()
-            .flat_map(|(_, v)| v.iter())
-            .map(|s| s.score)
-            .max()
+            .map(|&pos| cave[pos].tunnels.len() + 1)
+            .product();
+        let mut follow_states_0 = Vec::with_capacity(choices);
+        let mut follow_states_1 = Vec::with_capacity(choices);
+
+        let follow_states = &mut follow_states_0;
+        let follow_states_next = &mut follow_states_1;
+
+        follow_states.push(State {
+            time_left: self.time_left - 1,
+            ..self.clone()
+        });
+
+        for (actor, pos) in self.positions.iter().enumerate() {
+            while let Some(s) = follow_states.pop() {
+                // Open valve
+                if s.closed_valves.contains(*pos as usize) {
+                    let mut closed_valves = s.closed_valves;
+                    closed_valves.remove(*pos as usize);
+                    let s_prime = State {
+                        closed_valves,
+                        score: s.score + s.time_left * cave[*pos].flow_rate,
+                        ..s.clone()
+                    };
+                    follow_states_next.push(s_prime);
+                }
+                // Move on
+                let valve = &cave[*pos];
+                for target in valve.tunnels.iter() {
+                    let mut positions = s.positions.clone();
+                    positions[actor] = target as ValveIdx;
+                    let s_prime = State {
+                        positions,
+                        ..s.clone()
+                    };
+                    follow_states_next.push(s_prime);
+                }
+            }
+            std::mem::swap(follow_states, follow_states_next);
+        }
+        follow_states_0
     }
 }
 
+/// Number of subtrees the search is split into, so that threads can search them at once
+const SUBTREES: usize = 256;
+
+#[derive(Debug, Default)]
+struct SearchStats {
+    visited: usize,
+    pruned: usize,
+    /// Whether the search stopped early, see [`cancel`]
+    cancelled: bool,
+}
+
+/// What the threads searching the subtrees share
+struct Search<'a> {
+    cave: &'a Cave,
+    bounds: Bounds,
+    best_score: AtomicU32,
+    mem: StateMemoizer,
+}
+
+impl Search<'_> {
+    /// Searches the tree below `root` depth first, raising the best score to the best
+    /// score found; other threads may raise it at the same time, which only helps pruning
+    ///
+    /// Stops early if [`cancel`]led, with the best score as good as it got.
+    fn subtree(&self, root: State) -> SearchStats {
+        let mut states = vec![root];
+        let mut stats = SearchStats::default();
+        while let Some(s) = states.pop() {
+            if cancel::is_cancelled() {
+                stats.cancelled = true;
+                break;
+            }
+            stats.visited += 1;
+
+            // Check if leaf node
+            if s.is_leaf() {
+                continue;
+            }
+
+            let mut prune = |state: &State| {
+                let best = self
+                    .best_score
+                    .fetch_max(state.score, Ordering::Relaxed)
+                    .max(state.score);
+                // states that cannot beat the best score so far are not worth following
+                let pruned = state.score_upper_bound(&self.bounds) <= best
+                    || matches!(
+                        self.mem.memoize(state),
+                        StateMemoizationResult::SeenBetter
+                    );
+                stats.pruned += pruned as usize;
+                pruned
+            };
+
+            let mut follow_states: Vec<State> = s
+                .follow_states(self.cave)
+                .into_iter()
+                .filter(|state| !prune(state))
+                .collect();
+
+            // Push follow states, the most promising last so that it is visited first
+            follow_states.sort_by_cached_key(|s| s.score_upper_bound(&self.bounds));
+            states.extend(follow_states);
+        }
+        stats
+    }
+}
+
+/// The most pressure the actors starting at the given valves can release in `time`
+/// minutes, taking a minute to move through a tunnel and a minute to open a valve
 pub fn find_pressure_release_potential(
     cave: Cave,
     starting_positions: Vec<ValveLabel>,
     time: u32,
 ) -> anyhow::Result<u32> {
-    let mut states: VecDeque<State> = VecDeque::new();
     let closed_valves = cave
         .valves
         .iter()
         .enumerate()
         // valves with zero potential flow rate might as well be considered open from the start
         .filter(|(_, v)| v.flow_rate > 0)
-        .map(|(idx, _)| idx as ValveIdx)
+        .map(|(idx, _)| idx)
         .collect();
     let starting_positions = starting_positions
         .iter()
@@ -137,89 +332,70 @@ pub fn find_pressure_release_potential(
                         .context(format!("Index of {label} valve out of bounds"))
                 })
         })
-        .fold(Ok(LocalVec::<_, 2>::new()), |acc, p| match p {
-            Ok(p) => acc.map(|mut v| {
-                v.push(p);
-                v
-            }),
-            Err(e) => Err(e),
+        .try_fold(LocalVec::<_, 2>::new(), |mut acc, p| {
+            acc.push(p?);
+            anyhow::Ok(acc)
         })?;
-    states.push_back(State {
+    let root = State {
         positions: starting_positions,
         closed_valves,
         score: 0,
         time_left: time,
-    });
-    let mut mem = StateMemoizer::new();
-    let mut state_cnt = 0usize;
-    let mut prune_cnt = 0usize;
-    while !states.is_empty() {
-        state_cnt += 1;
-        let s = states.pop_front().unwrap();
-
-        // Check if leaf node
-        if s.time_left == 0 || *s.closed_valves == 0 {
-            continue;
-        }
-
-        let mut follow_states_0 = LocalVec::<State, 100>::new();
-        let mut follow_states_1 = LocalVec::<State, 100>::new();
-
-        let follow_states = &mut follow_states_0;
-        let follow_states_next = &mut follow_states_1;
+    };
+    if root.is_leaf() {
+        return Err(anyhow!(
+            "Did not find anything! Are there any reachable valves with positive flow rate?"
+        ));
+    }
 
-        follow_states.push(State {
-            time_left: s.time_left - 1,
-            ..s.clone()
-        });
+    let search = Search {
+        cave: &cave,
+        bounds: Bounds::new(&cave),
+        best_score: AtomicU32::new(0),
+        mem: StateMemoizer::new(),
+    };
+    // Expand breadth first until there are enough subtrees to keep all threads busy
+    let mut stats = SearchStats::default();
+    let mut subtrees = vec![root];
+    while subtrees.len() < SUBTREES && subtrees.iter().any(|s| !s.is_leaf()) {
+        stats.visited += subtrees.len();
+        subtrees = subtrees
+            .iter()
+            .filter(|s| !s.is_leaf())
+            .flat_map(|s| s.follow_states(&cave))
+            .inspect(|s| {
+                search.best_score.fetch_max(s.score, Ordering::Relaxed);
+            })
+            .collect();
+    }
+    // the most promising first, so that their scores help pruning the others
+    subtrees.sort_by_cached_key(|s| Reverse(s.score_upper_bound(&search.bounds)));
 
-        for (actor, pos) in s.positions.iter().enumerate() {
-            while let Some(s) = follow_states.pop() {
-                // Open valve
-                if s.closed_valves.contains(*pos) {
-                    let mut closed_valves = s.closed_valves.clone();
-                    closed_valves.remove(*pos);
-                    let s_prime = State {
-                        closed_valves,
-                        score: s.score + s.time_left * cave[*pos].flow_rate,
-                        ..s.clone()
-                    };
-                    follow_states_next.push(s_prime);
-                }
-                // Move on
-                let valve = &cave[*pos];
-                for target in valve.tunnels.iter() {
-                    let mut positions = s.positions.clone();
-                    positions[actor] = target;
-                    let s_prime = State {
-                        positions,
-                        ..s.clone()
-                    };
-                    follow_states_next.push(s_prime);
-                }
-            }
-            std::mem::swap(follow_states, follow_states_next);
+    // the best score is the same however the threads interleave, only the stats differ
+    let progress = Progress::new(module_path!(), "subtrees", Some(subtrees.len() as u64));
+    let subtree_stats = pool::map(&subtrees, |root| {
+        let stats = search.subtree(root.clone());
+        if !stats.cancelled {
+            progress.add(1);
         }
-
-        let mut prune = |state: &State| match mem.memoize(state) {
-            StateMemoizationResult::SeenBetter => {
-                prune_cnt += 1;
-                true
-            }
-            StateMemoizationResult::PotentiallyBest => false,
-        };
-
-        // Prune
-        follow_states_next
-            .extend(follow_states.iter().filter(|state| !prune(state)).cloned());
-
-        // Enqueue follow states, best scores first
-        follow_states_next.sort_by(|a, b| b.score.cmp(&a.score));
-        states.extend(follow_states_next.iter().cloned());
+        stats
+    });
+    drop(progress);
+    for s in subtree_stats {
+        stats.visited += s.visited;
+        stats.pruned += s.pruned;
+        stats.cancelled |= s.cancelled;
     }
 
-    println!("(INFO) During the solve, {prune_cnt} states were pruned and {state_cnt} states were visited");
-    mem.get_best_score().ok_or(anyhow!(
-        "Did not find anything! Are there any reachable valves with positive flow rate?"
-    ))
+    log::info!(
+        "During the solve, {} states were pruned and {} states were visited",
+        stats.pruned,
+        stats.visited
+    );
+    let best_score = search.best_score.into_inner();
+    match stats.cancelled {
+        // a score some actors reach, but maybe not the most pressure they can release
+        true => Err(Cancelled::with_best_so_far(best_score).into()),
+        false => Ok(best_score),
+    }
 }
@@ -0,0 +1,93 @@
+use common::{
+    cancel::{self, Cancelled, Token},
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day16::{
+    input::{Cave, ValveLabel},
+    pathfinder::find_pressure_release_potential,
+    preprocessing, Day16, Params,
+};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+fn label(s: &str) -> ValveLabel {
+    s.parse().unwrap()
+}
+
+#[test]
+fn valves_are_ordered_by_label() {
+    let cave: Cave = example().parse().unwrap();
+    let cave = preprocessing::Cave::from(&cave);
+    let labels: Vec<_> = cave.valve_labels.iter().map(|l| l.to_string()).collect();
+    assert_eq!(
+        labels,
+        ["AA", "BB", "CC", "DD", "EE", "FF", "GG", "HH", "II", "JJ"]
+    );
+    assert_eq!(cave.valves[1].flow_rate, 13);
+}
+
+#[test]
+fn releases_pressure() {
+    let input = "Valve AA has flow rate=0; tunnels lead to valves BB\n\
+                 Valve BB has flow rate=10; tunnel leads to valve AA\n";
+    let cave = Day16::parse(input).unwrap().cave;
+    // a minute to walk to BB, another to open it
+    let released = find_pressure_release_potential(cave.clone(), vec![label("AA")], 5);
+    assert_eq!(released.unwrap(), 30);
+    assert!(find_pressure_release_potential(cave, vec![label("ZZ")], 5).is_err());
+    let params = Params {
+        part1_minutes: 5,
+        ..Params::default()
+    };
+    let volcano = Day16::parse_with(input, &params).unwrap();
+    assert_eq!(Day16::part1(&volcano).unwrap(), 30);
+    let params = Params {
+        start: label("BB"),
+        ..params
+    };
+    let volcano = Day16::parse_with(input, &params).unwrap();
+    assert_eq!(Day16::part1(&volcano).unwrap(), 40);
+}
+
+#[test]
+fn example_answers() {
+    let cave = Day16::parse(&example()).unwrap();
+    assert_eq!(Day16::part1(&cave).unwrap(), 1651);
+    assert_eq!(Day16::part2(&cave).unwrap(), 1707);
+}
+
+#[test]
+fn cancelled_with_the_best_score_so_far() {
+    let cave = Day16::parse(&example()).unwrap();
+    let token = Token::new();
+    token.cancel();
+    let err = cancel::scope(token, || Day16::part1(&cave)).unwrap_err();
+    let best = err.downcast::<Cancelled>().unwrap().best_so_far.unwrap();
+    // the scores of the states expanded before the threads took over are reachable
+    assert!(best.parse::<u32>().unwrap() <= 1651);
+}
+
+#[test]
+fn valves_with_many_tunnels() {
+    // both actors have eleven tunnels to choose from at AA
+    let leaves: Vec<String> = (b'A'..=b'K').map(|c| format!("B{}", c as char)).collect();
+    let mut input = format!(
+        "Valve AA has flow rate=0; tunnels lead to valves {}\n",
+        leaves.join(", ")
+    );
+    for leaf in &leaves {
+        input += &format!("Valve {leaf} has flow rate=1; tunnel leads to valve AA\n");
+    }
+    let params = Params {
+        part2_minutes: 10,
+        ..Params::default()
+    };
+    let volcano = Day16::parse_with(&input, &params).unwrap();
+    // each actor opens a leaf after 2, 5 and 8 minutes
+    assert_eq!(Day16::part2(&volcano).unwrap(), 2 * (8 + 5 + 2));
+}
@@ -1,5 +1,12 @@
-use common::twod::Point;
-use std::{collections::HashMap, fmt, str::FromStr};
+use common::{
+    cancel::{self, Cancelled},
+    cycle,
+    parse::ParseError,
+    progress::Progress,
+    twod::{Bounds, Direction, Point},
+    viz::{Color, Glyph, Scene},
+};
+use std::{fmt, str::FromStr};
 
 const CHAMBER_WIDTH: usize = 7;
 const MAX_ROCK_SHAPE_HEIGHT: usize = 4;
@@ -10,22 +17,25 @@ const GRID_ROW_INITVAL: u8 = 1 << 7;
 /// would be the initial position)
 type RockShape = [[u8; MAX_ROCK_SHAPE_HEIGHT]; CHAMBER_WIDTH + 1];
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum Jet {
-    Left,
-    Right,
-}
-
+/// Jets push either `Left` or `Right`
 #[derive(Clone)]
-pub struct JetPattern(Vec<Jet>);
+pub struct JetPattern(Vec<Direction>);
+
+/// A rock on its way down, its rows bottom up as bit mask
+#[derive(Clone, Copy)]
+pub struct FallingRock {
+    rows: [u8; MAX_ROCK_SHAPE_HEIGHT],
+    anchor: Point,
+}
 
+/// The seven units wide chamber and the tower of rocks that fell into it
 pub struct Chamber {
     grid: Vec<u8>,
     base_y: usize, // height from which we store data in grid
     high_point: i32,
     rock_shapes: Vec<RockShape>,
     next_rock_shape_idx: usize,
-    jet_pattern: Vec<Jet>,
+    jet_pattern: Vec<Direction>,
     next_jet_idx: usize,
 }
 
@@ -43,64 +53,85 @@ impl Chamber {
         }
     }
 
+    /// Height of the tower in units, 0 while the chamber is empty
     pub fn tower_height(&self) -> usize {
         self.base_y + (self.high_point + 1) as usize
     }
 
-    pub fn rumble(&mut self, n_rocks: usize) {
-        let periodicity_check_interval = self.rock_shapes.len() * self.jet_pattern.len();
-        struct CheckPoint {
-            n_rocks_dropped: usize,
-            base_y: usize,
+    /// Lets `n_rocks` more rocks fall, skipping ahead once the tower repeats itself
+    ///
+    /// Stops early if [`cancel`]led, e.g., for jets that take too long to repeat.
+    pub fn rumble(&mut self, n_rocks: usize) -> Result<(), Cancelled> {
+        // only counts the rocks actually dropped, so there is no total
+        let progress = Progress::new(module_path!(), "rocks dropped", None);
+        let mut drop_rock = |chamber: &mut Chamber| {
+            cancel::check()?;
+            chamber.drop_rock();
+            progress.add(1);
+            Ok(())
+        };
+        let res = cycle::try_extrapolate(
+            self,
+            n_rocks,
+            &mut drop_rock,
+            Chamber::state_key,
+            |chamber| chamber.tower_height() as i64,
+        )?;
+        if let Some(cycle) = res.cycle {
+            log::info!(
+                "found periodicity of len {} (rocks dropped) and height difference {}",
+                cycle.len,
+                res.cycle_gain
+            );
+            // the rocks of whole skipped cycles only count towards the height, the rest
+            // still fall onto the tower
+            let remaining = n_rocks - res.steps_run;
+            self.base_y += remaining / cycle.len * res.cycle_gain as usize;
+            for _ in 0..remaining % cycle.len {
+                drop_rock(self)?;
+            }
         }
-        let mut checkpoints = HashMap::<Vec<u8>, CheckPoint>::new();
-        let mut n_rocks_dropped = n_rocks;
-        for i in 0..n_rocks {
-            self.drop_rock();
-            if i > 0 && i % periodicity_check_interval == 0 {
-                self.adjust_view();
-                let key: Vec<u8> = self
-                    .grid
-                    .iter()
-                    .cloned()
-                    .take((self.high_point + 1) as usize)
-                    .collect();
-                match checkpoints.entry(key) {
-                    std::collections::hash_map::Entry::Occupied(entry) => {
-                        let cp = entry.get();
-                        let d_rocks = i + 1 - cp.n_rocks_dropped;
-                        assert!(d_rocks > 0);
-                        let d_base = self.base_y - cp.base_y;
-                        println!(
-                            "(INFO) found periodicity of len {}M (rocks dropped) and height difference {}M",
-                            d_rocks / 1_000_000,
-                            d_base / 1_000_000
-                        );
-                        let to_drop_still = n_rocks - i - 1;
-                        self.base_y += (to_drop_still / d_rocks) * d_base;
-                        n_rocks_dropped = i + 1 + (to_drop_still / d_rocks) * d_rocks;
-                        break;
-                    }
-                    std::collections::hash_map::Entry::Vacant(entry) => {
-                        entry.insert(CheckPoint {
-                            n_rocks_dropped: i + 1,
-                            base_y: self.base_y,
-                        });
-                    }
+        debug_assert_eq!(self.tower_height() as i64, res.measure);
+        Ok(())
+    }
+
+    /// Identifies the state by the next rock and jet and the empty cells that falling rocks
+    /// may still reach, as one bit mask per row from the top of the tower down
+    ///
+    /// Rocks only move left, right and down, and so does each of their cells: cells not
+    /// reachable from above that way can never matter again. A column that the jets never
+    /// let a rock into stays reachable down to the floor, so such towers never repeat.
+    pub(crate) fn state_key(&self) -> (usize, usize, Vec<u8>) {
+        const ALL_COLUMNS: u8 = (1 << CHAMBER_WIDTH) - 1;
+        let top = (self.high_point + 1) as usize;
+        // the row above the tower is empty
+        let mut reachable = vec![ALL_COLUMNS];
+        for y in (0..top).rev() {
+            let free = !self.grid[y] & ALL_COLUMNS;
+            // falling in from the row above, then sliding sideways
+            let mut row = reachable[reachable.len() - 1] & free;
+            loop {
+                let slid = (row | row << 1 | row >> 1) & free;
+                if slid == row {
+                    break;
                 }
+                row = slid;
             }
+            if row == 0 {
+                break;
+            }
+            reachable.push(row);
         }
-        for _ in n_rocks_dropped..n_rocks {
-            self.drop_rock();
-        }
+        (self.next_rock_shape_idx, self.next_jet_idx, reachable)
     }
 
     fn drop_rock(&mut self) {
-        let rock = self
-            .rock_shapes
-            .get(self.next_rock_shape_idx)
-            .unwrap()
-            .clone();
+        self.drop_rock_with(|_, _| ());
+    }
+
+    /// Drops the next rock, calling `observe` whenever it was pushed and fell
+    pub fn drop_rock_with(&mut self, mut observe: impl FnMut(&Chamber, FallingRock)) {
+        let rock = *self.rock_shapes.get(self.next_rock_shape_idx).unwrap();
         self.next_rock_shape_idx = (self.next_rock_shape_idx + 1) % self.rock_shapes.len();
         let req_height = (self.high_point + 1) as usize + 4 + MAX_ROCK_SHAPE_HEIGHT;
         if self.grid.len() < req_height {
@@ -111,30 +142,21 @@ impl Chamber {
             y: self.high_point + 4,
         };
         loop {
-            let jet = self.jet_pattern.get(self.next_jet_idx).unwrap().clone();
+            let jet = *self.jet_pattern.get(self.next_jet_idx).unwrap();
             self.next_jet_idx = (self.next_jet_idx + 1) % self.jet_pattern.len();
-            let anchor_pushed = match jet {
-                Jet::Left => Point {
-                    x: anchor.x - 1,
-                    ..anchor
-                },
-                Jet::Right => Point {
-                    x: anchor.x + 1,
-                    ..anchor
-                },
-            };
+            let anchor_pushed = anchor + jet.offset();
             if !self.collision(&rock, anchor_pushed) {
                 anchor = anchor_pushed;
             }
-            let anchor_fallen = Point {
-                y: anchor.y - 1,
-                ..anchor
-            };
+            let anchor_fallen = anchor - Point::new(0, 1); // y grows upwards
+
             if self.collision(&rock, anchor_fallen) {
                 break;
             } else {
                 anchor = anchor_fallen;
             }
+            let rows = rock[anchor.x as usize];
+            observe(self, FallingRock { rows, anchor });
         }
         self.come_to_rest(&rock, anchor);
     }
@@ -198,22 +220,73 @@ impl Chamber {
 impl fmt::Display for Chamber {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let height = (self.high_point + 2) as usize;
-        self.grid
-            .iter()
-            .take(height)
-            .rev()
-            .map(|&row| {
-                (0..CHAMBER_WIDTH)
-                    .map(|bit_idx| row & (1u8 << bit_idx) != 0)
-                    .map(|rock| match rock {
-                        false => '.',
-                        true => '#',
-                    })
-                    .map(|ch| write!(f, "{ch}"))
-                    .collect::<fmt::Result>()
-                    .and_then(|()| write!(f, "\n"))
-            })
-            .collect()
+        self.grid.iter().take(height).rev().try_for_each(|&row| {
+            (0..CHAMBER_WIDTH)
+                .map(|bit_idx| row & (1u8 << bit_idx) != 0)
+                .map(|rock| match rock {
+                    false => '.',
+                    true => '#',
+                })
+                .try_for_each(|ch| write!(f, "{ch}"))
+                .and_then(|()| writeln!(f))
+        })
+    }
+}
+
+/// A chamber with a falling rock, drawn upside down so that the floor is at the bottom
+pub struct ChamberView<'a> {
+    pub chamber: &'a Chamber,
+    pub rock: Option<FallingRock>,
+}
+
+impl ChamberView<'_> {
+    fn row(&self, y: i32) -> u8 {
+        let rock_row = self.rock.and_then(|rock| {
+            let offset = usize::try_from(y - rock.anchor.y).ok()?;
+            rock.rows.get(offset).copied()
+        });
+        rock_row.unwrap_or(0)
+    }
+}
+
+impl Scene for ChamberView<'_> {
+    fn bounds(&self) -> Bounds {
+        let rock_top = self.rock.map_or(0, |rock| rock.anchor.y) + 4;
+        let top = std::cmp::max(self.chamber.high_point + 4, rock_top);
+        Bounds {
+            min: Point::new(-1, -top),
+            max: Point::new(CHAMBER_WIDTH as i32, 1),
+        }
+    }
+
+    fn focus(&self) -> Point {
+        let anchor = self
+            .rock
+            .map_or(Point::new(3, self.chamber.high_point), |rock| rock.anchor);
+        Point::new(anchor.x, -anchor.y)
+    }
+
+    fn glyph(&self, p: Point) -> Glyph {
+        let y = -p.y;
+        let wall = p.x < 0 || p.x >= CHAMBER_WIDTH as i32;
+        if y < 0 {
+            return Glyph::plain(if wall { '+' } else { '-' });
+        } else if wall {
+            return Glyph::plain('|');
+        }
+        let bit = 1u8 << p.x;
+        if self.row(y) & bit != 0 {
+            Glyph::new('@', Color::Yellow)
+        } else if self
+            .chamber
+            .grid
+            .get(y as usize)
+            .is_some_and(|row| row & bit != 0)
+        {
+            Glyph::new('#', Color::White)
+        } else {
+            Glyph::new('.', Color::Gray)
+        }
     }
 }
 
@@ -235,7 +308,7 @@ fn to_string(s: RockShape) -> String {
     result
 }
 
-#[allow(dead_code)]
+/// Prints the rock shapes in all their horizontal positions
 pub fn print_shapes() {
     let shapes = get_rock_shapes();
     for sh in shapes {
@@ -244,7 +317,7 @@ pub fn print_shapes() {
 }
 
 fn get_rock_shapes() -> Vec<RockShape> {
-    let base_shapes = vec![
+    let base_shapes = [
         [
             0b00001111u8, //
             0b00000000u8, //
@@ -280,8 +353,8 @@ fn get_rock_shapes() -> Vec<RockShape> {
         .iter()
         .map(|base| {
             let mut translation_set = [*base; CHAMBER_WIDTH + 1];
-            for t in 1..CHAMBER_WIDTH + 1 {
-                translation_set[t].iter_mut().for_each(|row| {
+            for (t, shape) in translation_set.iter_mut().enumerate().skip(1) {
+                shape.iter_mut().for_each(|row| {
                     *row <<= t;
                 })
             }
@@ -291,18 +364,25 @@ fn get_rock_shapes() -> Vec<RockShape> {
 }
 
 impl FromStr for JetPattern {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let jets = s
-            .trim()
-            .chars()
-            .map(|ch| match ch {
-                '<' => Ok(Jet::Left),
-                '>' => Ok(Jet::Right),
-                other => Err(anyhow::anyhow!("Unrecognized character '{other}'")),
+        let pattern = s.trim();
+        let jets = pattern
+            .char_indices()
+            .map(|(pos, ch)| match ch {
+                '<' => Ok(Direction::Left),
+                '>' => Ok(Direction::Right),
+                other => Err(ParseError::at(
+                    s,
+                    &pattern[pos..pos + other.len_utf8()],
+                    format!("unrecognized jet '{other}', expected '<' or '>'"),
+                )),
             })
             .collect::<Result<Vec<_>, _>>()?;
+        if jets.is_empty() {
+            return Err(ParseError::at_end(s, "no jets, expected '<' or '>'"));
+        }
         Ok(JetPattern(jets))
     }
 }
@@ -315,7 +395,7 @@ mod leftwind {
     fn after_first_drop_pile_should_be_one_tall() {
         let jets = "<".parse().unwrap();
         let mut chamber = Chamber::new(jets);
-        chamber.rumble(1);
+        chamber.rumble(1).unwrap();
         assert_eq!(chamber.tower_height(), 1);
     }
 
@@ -323,7 +403,7 @@ mod leftwind {
     fn after_second_drop_pile_should_be_four_tall() {
         let jets = "<".parse().unwrap();
         let mut chamber = Chamber::new(jets);
-        chamber.rumble(2);
+        chamber.rumble(2).unwrap();
         assert_eq!(chamber.tower_height(), 4);
     }
 
@@ -331,15 +411,25 @@ mod leftwind {
     fn after_third_drop_pile_should_be_seven_tall() {
         let jets = "<".parse().unwrap();
         let mut chamber = Chamber::new(jets);
-        chamber.rumble(3);
+        chamber.rumble(3).unwrap();
         assert_eq!(chamber.tower_height(), 7);
     }
 
+    #[test]
+    fn state_key_covers_only_reachable_cells() {
+        let jets = "<".parse().unwrap();
+        let mut chamber = Chamber::new(jets);
+        chamber.rumble(2).unwrap();
+        // the plus on the bar shelters a cell to the left of its foot
+        let rows = vec![0b1111111, 0b1111101, 0b1111000, 0b1111100, 0b1110000];
+        assert_eq!(chamber.state_key(), (2, 0, rows));
+    }
+
     #[test]
     fn after_fourth_drop_pile_should_be_nine_tall() {
         let jets = "<".parse().unwrap();
         let mut chamber = Chamber::new(jets);
-        chamber.rumble(4);
+        chamber.rumble(4).unwrap();
         assert_eq!(chamber.tower_height(), 9);
     }
 }
@@ -0,0 +1,264 @@
+//! Day 17: Pyroclastic Flow, stacking falling rocks pushed around by jets of gas
+
+use chamber::{Chamber, ChamberView, JetPattern};
+use common::{
+    cancel::Cancelled,
+    image::{Drawing, Image, Rgb},
+    params,
+    random::Rng,
+    solution::Solution,
+    twod::Grid,
+    viz::{self, Animator},
+};
+
+pub mod chamber;
+
+/// How many rocks fall, the same for the example
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    pub part1_rocks: usize,
+    pub part2_rocks: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self {
+            part1_rocks: 2022,
+            part2_rocks: 1_000_000_000_000,
+        }
+    }
+}
+
+impl params::Params for Params {
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "part1_rocks" => self.part1_rocks = params::parse_value(key, value)?,
+            "part2_rocks" => self.part2_rocks = params::parse_value(key, value)?,
+            _ => return Err(params::unknown(key, &["part1_rocks", "part2_rocks"])),
+        }
+        Ok(())
+    }
+}
+
+/// The jets pushing the rocks around and how many rocks fall
+#[derive(Clone)]
+pub struct Eruption {
+    pub jets: JetPattern,
+    pub params: Params,
+}
+
+impl Eruption {
+    /// The chamber after `rocks` rocks fell
+    fn tower_after(&self, rocks: usize) -> Result<Chamber, Cancelled> {
+        let mut chamber = Chamber::new(self.jets.clone());
+        chamber.rumble(rocks)?;
+        Ok(chamber)
+    }
+}
+
+/// Shows the rocks of part one falling on the terminal
+pub fn animate(eruption: &Eruption, opts: &viz::Options) -> anyhow::Result<()> {
+    let mut animator = Animator::stdout(opts.clone())?;
+    let mut chamber = Chamber::new(eruption.jets.clone());
+    for n in 1..=eruption.params.part1_rocks {
+        let caption = format!("rock {n}");
+        chamber.drop_rock_with(|chamber, rock| {
+            let view = ChamberView {
+                chamber,
+                rock: Some(rock),
+            };
+            animator.frame(&view, &caption)
+        });
+    }
+    let view = ChamberView {
+        chamber: &chamber,
+        rock: None,
+    };
+    animator.key_frame(&view, &format!("height {}", chamber.tower_height()));
+    animator.finish()
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u8 = 17;
+    const TITLE: &'static str = "Pyroclastic Flow";
+
+    type Params = Params;
+    type Input = Eruption;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Self::parse_with(input, &Params::default())
+    }
+
+    fn parse_with(input: &str, params: &Params) -> anyhow::Result<Self::Input> {
+        Ok(Eruption {
+            jets: input.parse()?,
+            params: params.clone(),
+        })
+    }
+
+    fn part1(eruption: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        let rocks = eruption.params.part1_rocks;
+        Ok(eruption.tower_after(rocks)?.tower_height())
+    }
+
+    fn part2(eruption: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        let rocks = eruption.params.part2_rocks;
+        Ok(eruption.tower_after(rocks)?.tower_height())
+    }
+
+    /// The top of the tower after the rocks of part one
+    fn draw(eruption: &Self::Input) -> anyhow::Result<Drawing> {
+        const ROWS: usize = 100;
+        let chamber = eruption.tower_after(eruption.params.part1_rocks)?;
+        let tower = chamber.to_string();
+        let top: Vec<&str> = tower.lines().take(ROWS).collect();
+        let rows = Grid::<bool>::parse(&top.join("\n"))?;
+        Ok(
+            Image::from_grid(&rows, |&rock| if rock { Rgb::GRAY } else { Rgb::WHITE })
+                .into(),
+        )
+    }
+
+    /// A jet pattern of `size` jets
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        let jets: String = (0..size.max(1))
+            .map(|_| if rng.chance(0.5) { '<' } else { '>' })
+            .collect();
+        Ok(jets + "\n")
+    }
+}
+
+#[cfg(test)]
+mod example_input {
+    use super::*;
+
+    #[test]
+    fn test_10_rocks() {
+        let example_inp = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        let mut chamber = Chamber::new(example_inp.parse().unwrap());
+        chamber.rumble(10).unwrap();
+        assert_eq!(chamber.tower_height(), 17);
+    }
+
+    #[test]
+    fn test_2022_rocks() {
+        let example_inp = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        let mut chamber = Chamber::new(example_inp.parse().unwrap());
+        chamber.rumble(2022).unwrap();
+        assert_eq!(chamber.tower_height(), 3068);
+    }
+}
+
+#[cfg(test)]
+mod reference {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    const ROCKS: [&[(i64, i64)]; 5] = [
+        &[(0, 0), (1, 0), (2, 0), (3, 0)],
+        &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+        &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+        &[(0, 0), (0, 1), (0, 2), (0, 3)],
+        &[(0, 0), (1, 0), (0, 1), (1, 1)],
+    ];
+
+    /// Drops every single rock into a set of occupied cells
+    fn naive_tower_height(jets: &str, n_rocks: usize) -> i64 {
+        let mut occupied = HashSet::new();
+        let mut height = 0;
+        let mut jets = jets.bytes().cycle();
+        let fits = |occupied: &HashSet<(i64, i64)>, rock: &[(i64, i64)], x, y| {
+            rock.iter().all(|&(dx, dy)| {
+                let (x, y) = (x + dx, y + dy);
+                (0..7).contains(&x) && y >= 0 && !occupied.contains(&(x, y))
+            })
+        };
+        for rock in ROCKS.iter().cycle().take(n_rocks) {
+            let (mut x, mut y) = (2, height + 3);
+            loop {
+                let pushed = if jets.next() == Some(b'<') {
+                    x - 1
+                } else {
+                    x + 1
+                };
+                if fits(&occupied, rock, pushed, y) {
+                    x = pushed;
+                }
+                if !fits(&occupied, rock, x, y - 1) {
+                    break;
+                }
+                y -= 1;
+            }
+            for &(dx, dy) in rock.iter() {
+                occupied.insert((x + dx, y + dy));
+                height = height.max(y + dy + 1);
+            }
+        }
+        height
+    }
+
+    #[test]
+    fn naive_matches_example() {
+        let example = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        assert_eq!(naive_tower_height(example, 2022), 3068);
+    }
+
+    /// The chamber after dropping every single rock, without skipping cycles
+    fn dropped_one_by_one(jets: &str, n_rocks: usize) -> Chamber {
+        let mut chamber = Chamber::new(jets.parse().unwrap());
+        for _ in 0..n_rocks {
+            chamber.drop_rock_with(|_, _| ());
+        }
+        chamber
+    }
+
+    #[test]
+    fn rumbles_end_where_every_rock_fell() {
+        let example = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        let mut once = Chamber::new(example.parse().unwrap());
+        once.rumble(1000).unwrap();
+        let mut split = Chamber::new(example.parse().unwrap());
+        split.rumble(500).unwrap();
+        split.rumble(500).unwrap();
+        assert_eq!(split.tower_height(), once.tower_height());
+        let mut rng = Rng::new(1717);
+        for _ in 0..20 {
+            let len = rng.range(1..=40) as usize;
+            let jets = Day17::generate(&mut rng, len).unwrap();
+            let n_rocks = rng.range(0..=3000) as usize;
+            let mut chamber = Chamber::new(jets.parse().unwrap());
+            chamber.rumble(n_rocks / 3).unwrap();
+            chamber.rumble(n_rocks - n_rocks / 3).unwrap();
+            let naive = dropped_one_by_one(&jets, n_rocks);
+            assert_eq!(chamber.tower_height(), naive.tower_height());
+            // skipping must not change what falling rocks can still reach
+            assert_eq!(
+                chamber.state_key(),
+                naive.state_key(),
+                "{n_rocks} rocks with jets {jets}"
+            );
+        }
+    }
+
+    #[test]
+    fn extrapolation_matches_naive_on_random_jets() {
+        let mut rng = Rng::new(17);
+        for _ in 0..30 {
+            let len = rng.range(1..=40) as usize;
+            let jets = Day17::generate(&mut rng, len).unwrap();
+            let n_rocks = rng.range(0..=3000) as usize;
+            let mut chamber = Chamber::new(jets.parse().unwrap());
+            chamber.rumble(n_rocks).unwrap();
+            assert_eq!(
+                chamber.tower_height() as i64,
+                naive_tower_height(jets.trim(), n_rocks),
+                "{n_rocks} rocks with jets {jets}"
+            );
+        }
+    }
+}
@@ -1,44 +1,32 @@
-use std::{env, fs};
+use common::{
+    image,
+    input::DayInputs,
+    logging,
+    params::ParamSpec,
+    solution::{self, Format, Solution},
+    viz,
+};
+use day17::{Day17, Params};
 
-use chamber::Chamber;
-
-mod chamber;
-
-fn main() {
-    let input_file_path = env::args().nth(1).unwrap_or("17/input.txt".into());
-    let input = fs::read_to_string(&input_file_path).unwrap();
-    let mut chamber = Chamber::new(input.parse().unwrap());
-    let n_rocks_part1 = 2022;
-    let n_rocks_part2 = 1_000_000_000_000;
-    chamber.rumble(n_rocks_part1);
-    println!(
-        "Tower height after {n_rocks_part1} rocks: {}",
-        chamber.tower_height()
-    );
-    chamber.rumble(n_rocks_part2 - n_rocks_part1);
-    println!(
-        "Tower height after {n_rocks_part2} rocks: {}",
-        chamber.tower_height()
-    );
-}
-
-#[cfg(test)]
-mod example_input {
-    use super::*;
-
-    #[test]
-    fn test_10_rocks() {
-        let example_inp = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-        let mut chamber = Chamber::new(example_inp.parse().unwrap());
-        chamber.rumble(10);
-        assert_eq!(chamber.tower_height(), 17);
+fn main() -> anyhow::Result<()> {
+    logging::init_from_args()?;
+    let input = DayInputs::new(env!("CARGO_MANIFEST_DIR")).read_from_args()?;
+    let params = ParamSpec::from_args(Day17::DAY)?;
+    let eruption = Day17::parse_with(&input, &params.resolve()?)?;
+    image::save_from_args::<Day17>(&eruption)?;
+    if let Some(opts) = viz::Options::from_args()? {
+        day17::animate(&eruption, &opts)?;
     }
-
-    #[test]
-    fn test_2022_rocks() {
-        let example_inp = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
-        let mut chamber = Chamber::new(example_inp.parse().unwrap());
-        chamber.rumble(2022);
-        assert_eq!(chamber.tower_height(), 3068);
+    if Format::from_args()? == Format::Json {
+        return solution::print_json::<Day17>(&input, &params);
     }
+    let Params {
+        part1_rocks,
+        part2_rocks,
+    } = eruption.params;
+    let height = Day17::part1(&eruption)?;
+    println!("Tower height after {part1_rocks} rocks: {height}");
+    let height = Day17::part2(&eruption)?;
+    println!("Tower height after {part2_rocks} rocks: {height}");
+    Ok(())
 }
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use common::{
+    cancel::{self, Cancelled, Token},
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+};
+use day17::{
+    chamber::{Chamber, JetPattern},
+    Day17, Params,
+};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+#[test]
+fn stacks_rocks() {
+    let jets: JetPattern = example().parse().unwrap();
+    let mut chamber = Chamber::new(jets);
+    assert_eq!(chamber.tower_height(), 0);
+    // the horizontal bar, then the plus on top of it
+    chamber.rumble(1).unwrap();
+    assert_eq!(chamber.tower_height(), 1);
+    chamber.rumble(1).unwrap();
+    assert_eq!(chamber.tower_height(), 4);
+    assert!("<>x".parse::<JetPattern>().is_err());
+    let Err(err) = " \n".parse::<JetPattern>() else {
+        panic!("an empty jet pattern was accepted");
+    };
+    assert_eq!(err.message(), "no jets, expected '<' or '>'");
+}
+
+#[test]
+fn example_answers() {
+    let eruption = Day17::parse(&example()).unwrap();
+    assert_eq!(Day17::part1(&eruption).unwrap(), 3068);
+    assert_eq!(Day17::part2(&eruption).unwrap(), 1514285714288);
+}
+
+#[test]
+fn answers_for_other_numbers_of_rocks() {
+    let params = Params {
+        part1_rocks: 10,
+        part2_rocks: 2022,
+    };
+    let eruption = Day17::parse_with(&example(), &params).unwrap();
+    assert_eq!(Day17::part1(&eruption).unwrap(), 17);
+    assert_eq!(Day17::part2(&eruption).unwrap(), 3068);
+}
+
+#[test]
+fn cancelled_rumble() {
+    let mut chamber = Chamber::new(example().parse().unwrap());
+    let token = Token::with_timeout(Duration::ZERO);
+    assert_eq!(
+        cancel::scope(token, || chamber.rumble(10)),
+        Err(Cancelled::default())
+    );
+    assert_eq!(chamber.tower_height(), 0);
+}
@@ -1,65 +1,42 @@
 use std::{collections::HashSet, str::FromStr};
 
-use anyhow::anyhow;
+use common::{
+    parse::{parse_token, ParseError},
+    region::flood_fill,
+    threed::{Bounds3, Grid3, Point3},
+};
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Cube {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
-}
-
-impl Cube {
-    pub fn neighbours(&self) -> CubeNeighbours {
-        CubeNeighbours { cube: *self, i: 0 }
-    }
-}
-
-pub struct CubeNeighbours {
-    cube: Cube,
-    i: u8,
-}
-
-impl Iterator for CubeNeighbours {
-    type Item = Cube;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.i >= 6 {
-            return None;
-        }
-        let mut result = self.cube;
-        let coord =
-            &mut [&mut result.x, &mut result.y, &mut result.z][(self.i % 3) as usize];
-        let off = [-1, 1][(self.i / 3) as usize];
-        **coord += off;
-        self.i += 1;
-        Some(result)
-    }
-}
-
-pub struct Droplet(Vec<Cube>);
+/// The 1x1x1 cubes of a lava droplet, one `x,y,z` per line
+pub struct Droplet(Vec<Point3>);
 
 impl FromStr for Droplet {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Droplet(
             s.lines()
-                .map(|ln| -> Result<Cube, Self::Err> {
-                    let (x, yz) = ln.split_once(',').ok_or(anyhow!("Expected ','"))?;
-                    let (y, z) = yz.split_once(',').ok_or(anyhow!("Expected ','"))?;
-                    Ok(Cube {
-                        x: x.parse()?,
-                        y: y.parse()?,
-                        z: z.parse()?,
+                .map(|ln| -> Result<Point3, Self::Err> {
+                    let expected =
+                        || ParseError::at(s, ln, "expected a cube in format x,y,z");
+                    let (x, yz) = ln.split_once(',').ok_or_else(expected)?;
+                    let (y, z) = yz.split_once(',').ok_or_else(expected)?;
+                    Ok(Point3 {
+                        x: parse_token(s, x, "x coordinate")?,
+                        y: parse_token(s, y, "y coordinate")?,
+                        z: parse_token(s, z, "z coordinate")?,
                     })
                 })
-                .collect::<Result<Vec<Cube>, _>>()?,
+                .collect::<Result<Vec<Point3>, _>>()?,
         ))
     }
 }
 
 impl Droplet {
+    pub fn cubes(&self) -> &[Point3] {
+        &self.0
+    }
+
+    /// Number of cube sides that do not touch another cube
     pub fn surface_area(&self) -> usize {
         let cubes_map = self.0.iter().collect::<HashSet<_>>();
         let mut surface = 0;
@@ -74,66 +51,35 @@ impl Droplet {
         surface
     }
 
-    fn is_outside(cube: &Cube) -> bool {
-        // All coordinates are between 0 and 20, non-inclusive
-        const MIN: i32 = 0;
-        const MAX: i32 = 20;
-        cube.x == MIN
-            || cube.x >= MAX
-            || cube.y == MIN
-            || cube.y >= MAX
-            || cube.z == MIN
-            || cube.z >= MAX
-    }
-
-    pub fn exterior_surface_area(&self) -> usize {
-        let cubes_map = self.0.iter().cloned().collect::<HashSet<Cube>>();
-        let mut air_cubes = HashSet::<Cube>::new();
-        let mut surface = 0;
-        for cube in self.0.iter() {
-            surface += 6;
-            for n in cube.neighbours() {
-                if cubes_map.contains(&n) {
-                    surface -= 1;
-                } else {
-                    air_cubes.insert(n);
-                }
-            }
+    /// Like `surface_area`, but without the sides facing air trapped inside the droplet
+    ///
+    /// Fails if the droplet spans too large a box to fill with steam.
+    pub fn exterior_surface_area(&self) -> anyhow::Result<usize> {
+        let Some(bounds) = Bounds3::of_points(self.0.iter().cloned()) else {
+            return Ok(0);
+        };
+        let mut lava = Grid3::new(bounds, false)?;
+        for &cube in self.0.iter() {
+            lava[cube] = true;
         }
-        while !air_cubes.is_empty() {
-            let starting_air_cube = air_cubes.iter().next().unwrap().clone();
-            air_cubes.remove(&starting_air_cube);
-            let mut air_pocket = vec![starting_air_cube];
-            let mut air_pocket_map = air_pocket.iter().cloned().collect::<HashSet<Cube>>();
-            let mut pidx = 0;
-            loop {
-                if pidx >= air_pocket.len() {
-                    surface -= Droplet(air_pocket).surface_area();
-                    break;
-                }
-                let mut outside = false;
-                for n in air_pocket.get(pidx).unwrap().neighbours() {
-                    air_cubes.remove(&n);
-                    if cubes_map.contains(&n) {
-                        continue; // Lava
-                    }
-                    if air_pocket_map.contains(&n) {
-                        continue; // Already visited
-                    }
-                    if Droplet::is_outside(&n) {
-                        // No air pocket
-                        outside = true;
-                        break;
-                    }
-                    air_pocket.push(n);
-                    air_pocket_map.insert(n);
-                }
-                if outside {
-                    break;
-                }
-                pidx += 1;
-            }
-        }
-        surface
+        // the steam flows in from all around the droplet, i.e., from every side of its box
+        let on_side = |p: &Point3| {
+            [
+                (p.x, bounds.min.x, bounds.max.x),
+                (p.y, bounds.min.y, bounds.max.y),
+                (p.z, bounds.min.z, bounds.max.z),
+            ]
+            .iter()
+            .any(|&(c, min, max)| c == min || c == max)
+        };
+        let steam = flood_fill(&lava, bounds.points().filter(on_side), |&l| !l);
+        // sides facing out of the box, even beyond the range of i32, face steam as well
+        let covered = self
+            .0
+            .iter()
+            .flat_map(|cube| cube.neighbours())
+            .filter(|&n| lava.contains(n) && !steam[n])
+            .count();
+        Ok(6 * self.0.len() - covered)
     }
 }
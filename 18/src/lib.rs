@@ -0,0 +1,139 @@
+//! Day 18: Boiling Boulders, measuring the surface of a droplet of lava cubes
+
+use std::collections::HashSet;
+
+use anyhow::ensure;
+use common::{
+    image::{Drawing, Image, Rgb},
+    random::Rng,
+    solution::Solution,
+    threed::{self, Bounds3, Point3},
+};
+
+pub mod lava;
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u8 = 18;
+    const TITLE: &'static str = "Boiling Boulders";
+
+    type Params = ();
+    type Input = lava::Droplet;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(drop: &Self::Input) -> anyhow::Result<Self::Answer1> {
+        Ok(drop.surface_area())
+    }
+
+    fn part2(drop: &Self::Input) -> anyhow::Result<Self::Answer2> {
+        drop.exterior_surface_area()
+    }
+
+    /// The droplet seen along the z axis, brighter where it is thicker
+    fn draw(drop: &Self::Input) -> anyhow::Result<Drawing> {
+        let bounds = Bounds3::of_points(drop.cubes().iter().copied())
+            .unwrap_or(Bounds3::of_point(Point3::ORIGIN));
+        let (w, h, d) = bounds.size();
+        ensure!(
+            w.checked_mul(h)
+                .is_some_and(|area| area <= threed::MAX_CELLS),
+            "The droplet is too wide to draw"
+        );
+        let mut depth = vec![0usize; w * h];
+        for cube in drop.cubes() {
+            let (x, y) = (cube.x.abs_diff(bounds.min.x), cube.y.abs_diff(bounds.min.y));
+            depth[y as usize * w + x as usize] += 1;
+        }
+        let image = Image::from_fn(w, h, |x, y| match depth[y * w + x] {
+            0 => Rgb::WHITE,
+            n => Rgb::heat(n as f64 / d as f64),
+        });
+        Ok(image.into())
+    }
+
+    /// A droplet of `size` cubes, at most 8000, grown from one cube within a 20x20x20
+    /// box, so that it may enclose air pockets
+    fn generate(rng: &mut Rng, size: usize) -> anyhow::Result<String> {
+        const EXTENT: i32 = 20;
+        let inside =
+            |p: &Point3| [p.x, p.y, p.z].iter().all(|c| (1..=EXTENT).contains(c));
+        let count = size.clamp(1, (EXTENT * EXTENT * EXTENT) as usize);
+        let mut cubes = vec![Point3::new(EXTENT / 2, EXTENT / 2, EXTENT / 2)];
+        let mut seen: HashSet<Point3> = cubes.iter().copied().collect();
+        while cubes.len() < count {
+            let neighbours: Vec<Point3> = rng.pick(&cubes).neighbours().collect();
+            let cube = *rng.pick(&neighbours);
+            if inside(&cube) && seen.insert(cube) {
+                cubes.push(cube);
+            }
+        }
+        rng.shuffle(&mut cubes);
+        Ok(cubes
+            .iter()
+            .map(|c| format!("{},{},{}\n", c.x, c.y, c.z))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_cube_has_surface_of_6() {
+        let drop: lava::Droplet = "1,1,1".parse().unwrap();
+        assert_eq!(drop.surface_area(), 6);
+    }
+
+    #[test]
+    fn simple_example() {
+        let drop: lava::Droplet = "1,1,1\n2,1,1".parse().unwrap();
+        assert_eq!(drop.surface_area(), 10);
+    }
+
+    #[test]
+    fn larger_example() {
+        let drop: lava::Droplet = "2,2,2\n\
+                                   1,2,2\n\
+                                   3,2,2\n\
+                                   2,1,2\n\
+                                   2,3,2\n\
+                                   2,2,1\n\
+                                   2,2,3\n\
+                                   2,2,4\n\
+                                   2,2,6\n\
+                                   1,2,5\n\
+                                   3,2,5\n\
+                                   2,1,5\n\
+                                   2,3,5"
+            .parse()
+            .unwrap();
+        assert_eq!(drop.surface_area(), 64);
+    }
+
+    #[test]
+    fn exterior_surface_larger_example() {
+        let drop: lava::Droplet = "2,2,2\n\
+                                   1,2,2\n\
+                                   3,2,2\n\
+                                   2,1,2\n\
+                                   2,3,2\n\
+                                   2,2,1\n\
+                                   2,2,3\n\
+                                   2,2,4\n\
+                                   2,2,6\n\
+                                   1,2,5\n\
+                                   3,2,5\n\
+                                   2,1,5\n\
+                                   2,3,5"
+            .parse()
+            .unwrap();
+        assert_eq!(drop.exterior_surface_area().unwrap(), 58);
+    }
+}
@@ -0,0 +1,58 @@
+use common::{
+    input::{DayInputs, InputSpec},
+    solution::Solution,
+    threed::Point3,
+};
+use day18::{lava::Droplet, Day18};
+
+fn example() -> String {
+    DayInputs::new(env!("CARGO_MANIFEST_DIR"))
+        .read(&InputSpec::Example)
+        .unwrap()
+}
+
+/// A 3x3x3 cube without its centre
+fn hollow_cube() -> String {
+    let mut cubes = String::new();
+    for (x, y, z) in (0..27).map(|i| (i % 3, i / 3 % 3, i / 9)) {
+        if (x, y, z) != (1, 1, 1) {
+            cubes += &format!("{x},{y},{z}\n");
+        }
+    }
+    cubes
+}
+
+#[test]
+fn measures_surfaces() {
+    let droplet: Droplet = "1,1,1\n2,1,1\n".parse().unwrap();
+    assert_eq!(
+        droplet.cubes(),
+        [Point3::new(1, 1, 1), Point3::new(2, 1, 1)]
+    );
+    assert_eq!(droplet.surface_area(), 10);
+    assert_eq!(droplet.exterior_surface_area().unwrap(), 10);
+    let droplet: Droplet = hollow_cube().parse().unwrap();
+    assert_eq!(droplet.surface_area(), 6 * 9 + 6);
+    assert_eq!(droplet.exterior_surface_area().unwrap(), 6 * 9);
+    assert!("1,1\n".parse::<Droplet>().is_err());
+}
+
+#[test]
+fn measures_cubes_at_the_ends_of_i32() {
+    let droplet: Droplet = format!("{0},{0},{0}\n{1},{1},{1}\n", i32::MAX, i32::MIN)
+        .parse()
+        .unwrap();
+    assert_eq!(droplet.surface_area(), 12);
+    // far apart, too far for a grid between them
+    assert!(droplet.exterior_surface_area().is_err());
+    let droplet: Droplet = format!("{0},0,0\n{0},1,0\n", i32::MAX).parse().unwrap();
+    assert_eq!(droplet.surface_area(), 10);
+    assert_eq!(droplet.exterior_surface_area().unwrap(), 10);
+}
+
+#[test]
+fn example_answers() {
+    let droplet = Day18::parse(&example()).unwrap();
+    assert_eq!(Day18::part1(&droplet).unwrap(), 64);
+    assert_eq!(Day18::part2(&droplet).unwrap(), 58);
+}
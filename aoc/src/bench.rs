@@ -0,0 +1,265 @@
+use std::{fmt, fs, path::Path, time::Duration};
+
+use anyhow::Context;
+use common::{
+    params::ParamSpec,
+    solution::{Part, RunFn},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl From<Part> for Phase {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Phase::Part1,
+            Part::Two => Phase::Part2,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part 1",
+            Phase::Part2 => "part 2",
+        })
+    }
+}
+
+/// Summary of repeated timings, in nanoseconds
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub stddev_ns: u64,
+}
+
+impl Stats {
+    pub fn new(samples: &[Duration]) -> Self {
+        let mut ns: Vec<u64> = samples.iter().map(|d| d.as_nanos() as u64).collect();
+        ns.sort();
+        if ns.is_empty() {
+            return Stats {
+                min_ns: 0,
+                median_ns: 0,
+                stddev_ns: 0,
+            };
+        }
+        let median_ns = match ns.len() % 2 {
+            0 => (ns[ns.len() / 2 - 1] + ns[ns.len() / 2]) / 2,
+            _ => ns[ns.len() / 2],
+        };
+        let mean = ns.iter().sum::<u64>() as f64 / ns.len() as f64;
+        let variance =
+            ns.iter().map(|&x| (x as f64 - mean).powi(2)).sum::<f64>() / ns.len() as f64;
+        Stats {
+            min_ns: ns[0],
+            median_ns,
+            stddev_ns: variance.sqrt().round() as u64,
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = Duration::from_nanos;
+        write!(
+            f,
+            "min {:>10.2?}  median {:>10.2?}  stddev {:>10.2?}",
+            d(self.min_ns),
+            d(self.median_ns),
+            d(self.stddev_ns)
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: u8,
+    pub phase: Phase,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+
+/// Benchmark results as saved to and loaded from disk
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub runs: usize,
+    pub entries: Vec<Entry>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Error reading baseline {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("Error parsing baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        fs::write(path, text + "\n")
+            .with_context(|| format!("Error writing baseline {}", path.display()))
+    }
+
+    pub fn find(&self, day: u8, phase: Phase) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|e| e.day == day && e.phase == phase)
+    }
+}
+
+/// Times `runs` repetitions of parsing once and solving the given parts
+pub fn bench_day(
+    day: u8,
+    run: RunFn,
+    input: &str,
+    params: &ParamSpec,
+    parts: &[Part],
+    runs: usize,
+) -> anyhow::Result<Vec<Entry>> {
+    let mut parse_samples = vec![];
+    let mut samples = vec![vec![]; parts.len()];
+    for _ in 0..runs {
+        for (idx, (_, r)) in run(input, parts, params)?.into_iter().enumerate() {
+            let r = r?;
+            if idx == 0 {
+                parse_samples.push(r.parse_time);
+            }
+            samples[idx].push(r.solve_time);
+        }
+    }
+    let mut entries = vec![Entry {
+        day,
+        phase: Phase::Parse,
+        stats: Stats::new(&parse_samples),
+    }];
+    entries.extend(parts.iter().zip(&samples).map(|(&part, samples)| Entry {
+        day,
+        phase: part.into(),
+        stats: Stats::new(samples),
+    }));
+    Ok(entries)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Comparison {
+    Regression(f64),
+    Unchanged(f64),
+    Improvement(f64),
+}
+
+/// Compares medians; changes within `threshold` (a fraction, e.g. 0.1 for 10%) or within
+/// the measurement noise are considered unchanged
+pub fn compare(current: &Stats, baseline: &Stats, threshold: f64) -> Comparison {
+    let (cur, base) = (current.median_ns as f64, baseline.median_ns as f64);
+    let change = match base {
+        b if b > 0.0 => (cur - base) / base,
+        _ => 0.0,
+    };
+    let noise = std::cmp::max(current.stddev_ns, baseline.stddev_ns) as f64;
+    if change.abs() <= threshold || (cur - base).abs() <= noise {
+        Comparison::Unchanged(change)
+    } else if change > 0.0 {
+        Comparison::Regression(change)
+    } else {
+        Comparison::Improvement(change)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Comparison::Regression(c) => write!(f, "{:+.1}% REGRESSION", c * 100.0),
+            Comparison::Unchanged(c) => write!(f, "{:+.1}%", c * 100.0),
+            Comparison::Improvement(c) => write!(f, "{:+.1}% improvement", c * 100.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(v: &[u64]) -> Vec<Duration> {
+        v.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    fn stats(median_ms: u64, stddev_ms: u64) -> Stats {
+        Stats {
+            min_ns: 0,
+            median_ns: median_ms * 1_000_000,
+            stddev_ns: stddev_ms * 1_000_000,
+        }
+    }
+
+    #[test]
+    fn stats_of_odd_number_of_samples() {
+        let s = Stats::new(&ms(&[5, 1, 3]));
+        assert_eq!(s.min_ns, 1_000_000);
+        assert_eq!(s.median_ns, 3_000_000);
+        assert_eq!(s.stddev_ns, 1_632_993);
+    }
+
+    #[test]
+    fn stats_of_even_number_of_samples() {
+        let s = Stats::new(&ms(&[4, 2, 2, 8]));
+        assert_eq!(s.min_ns, 2_000_000);
+        assert_eq!(s.median_ns, 3_000_000);
+    }
+
+    #[test]
+    fn stats_without_samples() {
+        assert_eq!(Stats::new(&[]).median_ns, 0);
+    }
+
+    #[test]
+    fn compare_against_baseline() {
+        assert_eq!(
+            compare(&stats(150, 1), &stats(100, 1), 0.1),
+            Comparison::Regression(0.5)
+        );
+        assert_eq!(
+            compare(&stats(50, 1), &stats(100, 1), 0.1),
+            Comparison::Improvement(-0.5)
+        );
+        assert_eq!(
+            compare(&stats(105, 1), &stats(100, 1), 0.1),
+            Comparison::Unchanged(0.05)
+        );
+        // Too noisy to tell
+        assert_eq!(
+            compare(&stats(150, 60), &stats(100, 1), 0.1),
+            Comparison::Unchanged(0.5)
+        );
+    }
+
+    #[test]
+    fn baseline_json_round_trip() {
+        let baseline = Baseline {
+            runs: 3,
+            entries: vec![Entry {
+                day: 15,
+                phase: Phase::Part2,
+                stats: stats(100, 2),
+            }],
+        };
+        let json = serde_json::to_string(&baseline).unwrap();
+        assert_eq!(
+            json,
+            "{\"runs\":3,\"entries\":[{\"day\":15,\"phase\":\"part2\",\
+             \"min_ns\":0,\"median_ns\":100000000,\"stddev_ns\":2000000}]}"
+        );
+        assert_eq!(serde_json::from_str::<Baseline>(&json).unwrap(), baseline);
+        assert!(baseline.find(15, Phase::Part2).is_some());
+        assert!(baseline.find(15, Phase::Part1).is_none());
+    }
+}
@@ -0,0 +1,59 @@
+use std::path::Path;
+
+use common::{
+    input::DayInputs,
+    solution::{run, GenerateFn, RunFn, Solution},
+};
+
+pub struct Day {
+    pub day: u8,
+    pub title: &'static str,
+    /// Crate directory, relative to the workspace root
+    pub dir: &'static str,
+    pub run: RunFn,
+    pub generate: GenerateFn,
+}
+
+impl Day {
+    pub fn inputs(&self) -> DayInputs {
+        let workspace_root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .expect("the runner crate is inside the workspace");
+        DayInputs::new(workspace_root.join(self.dir))
+    }
+}
+
+const fn day<S: Solution>(dir: &'static str) -> Day {
+    Day {
+        day: S::DAY,
+        title: S::TITLE,
+        dir,
+        run: run::<S>,
+        generate: S::generate,
+    }
+}
+
+pub const DAYS: [Day; 18] = [
+    day::<day01::Day01>("01"),
+    day::<day02::Day02>("02"),
+    day::<day03::Day03>("03"),
+    day::<day04::Day04>("04"),
+    day::<day05::Day05>("05"),
+    day::<day06::Day06>("06"),
+    day::<day07::Day07>("07"),
+    day::<day08::Day08>("08"),
+    day::<day09::Day09>("09"),
+    day::<day10::Day10>("10"),
+    day::<day11::Day11>("11"),
+    day::<day12::Day12>("12"),
+    day::<day13::Day13>("13"),
+    day::<day14::Day14>("14"),
+    day::<day15::Day15>("15"),
+    day::<day16::Day16>("16"),
+    day::<day17::Day17>("17"),
+    day::<day18::Day18>("18"),
+];
+
+pub fn find(day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
@@ -0,0 +1,710 @@
+use std::{env, path::PathBuf, process::ExitCode, time::Duration};
+
+use anyhow::{anyhow, Context};
+use common::{
+    cancel::{self, Cancelled, Token},
+    input::InputSpec,
+    logging,
+    params::{Config, ParamSpec, Preset},
+    pool,
+    random::Rng,
+    solution::{Format, Part, Run},
+};
+use serde::Serialize;
+
+mod bench;
+mod days;
+mod manifest;
+mod verify;
+
+const USAGE: &str = "\
+Usage: aoc run [--day N] [--part P] [--input INPUT | --seed S [--size N]] [--threads N]
+               [--param KEY=VALUE]... [--config PATH] [--format FORMAT] [--timeout SECS]
+               [-v | -vv | -q]
+       aoc verify [--day N] [--part P] [--threads N] [--timeout SECS] [-v | -vv | -q]
+       aoc bench [--day N] [--part P] [--input INPUT | --seed S [--size N]] [--runs N]
+                 [--param KEY=VALUE]... [--config PATH] [--save PATH] [--baseline PATH]
+                 [--threshold PCT] [--threads N] [-v | -vv | -q]
+       aoc gen --day N [--seed S] [--size N]
+
+run     Runs the solutions of all days, or only the given day and part. INPUT is
+        `example` (test_data.txt), `real` (input.txt, the default), a path, or `-`
+        for stdin; gzipped inputs are decompressed. With --seed, runs on a random
+        input generated from the seed instead. FORMAT is `text` (the default) or `json`,
+        which prints one {day, part, answer, parse_ms, elapsed_ms, diagnostics} object
+        per line, where parse_ms is shared by both parts and elapsed_ms is solving only.
+        Diagnostics, such as search statistics, always go to stderr.
+verify  Checks the answers for each day's input files against the day's answers.toml.
+        Exits with a non-zero code if any answer is wrong.
+bench   Times parsing and each part over --runs repetitions (default 10) and reports
+        min, median and standard deviation. --save writes the results as a JSON
+        baseline; --baseline compares against one and exits with a non-zero code if
+        any median got slower by more than --threshold percent (default 10).
+gen     Prints a random input for the given day. The same seed (default 0) always
+        gives the same input. What --size (default 100) counts depends on the day,
+        e.g., lines, monkeys or valves.
+
+--param sets a puzzle parameter of the given day, e.g., `--day 15 --param row=11`;
+--config reads them from a TOML file with a table per day, e.g., [day15]. They start
+from the example preset for the example input and from the real one otherwise, unless
+`preset=example` or `preset=real` is given. The parameters are
+  day 7:  small_dir_limit, total_disk_space, required_disk_space
+  day 11: part1_rounds, part2_rounds
+  day 15: row, xy_max
+  day 16: start, part1_minutes, part2_minutes
+  day 17: part1_rocks, part2_rocks
+
+Logs go to stderr: info by default, such as search statistics, -q for warnings only,
+-v for debug records, such as the progress of long loops, and -vv for everything.
+RUST_LOG sets levels per day, e.g., `RUST_LOG=day16=debug`.
+
+--timeout stops each day that takes longer than SECS seconds, for both parts together.
+The parts left are reported as timed out, with the best answer found so far if the day
+has one, such as day 16's most pressure released yet, and how far long loops got as a
+warning. In JSON, they are {day, part, timed_out, best_so_far} objects. run and verify
+exit with a non-zero code then.
+
+--threads limits the number of threads, one per CPU by default. run and verify solve
+the days concurrently, bench one after the other; days 15 and 16 also spread their
+search over the threads. The answers are the same for any number of threads.";
+
+/// Size of generated inputs unless given with --size
+const DEFAULT_SIZE: usize = 100;
+
+#[derive(Debug, Default)]
+struct RunArgs {
+    day: Option<u8>,
+    part: Option<Part>,
+    input: Option<InputSpec>,
+    /// Seed of a generated input, replacing `input`
+    seed: Option<u64>,
+    size: Option<usize>,
+    threads: Option<usize>,
+    /// Given with --param
+    params: ParamSpec,
+    config: Option<PathBuf>,
+    format: Format,
+    /// The number of -v minus that of -q, `None` if there are none
+    verbosity: Option<i8>,
+    /// Time budget of each day
+    timeout: Option<Duration>,
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<RunArgs> {
+    let mut result = RunArgs::default();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(anyhow!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--day" => result.day = Some(value()?.parse().context("Invalid day")?),
+            "--part" => result.part = Some(value()?.parse()?),
+            "--input" => result.input = Some(value()?.parse()?),
+            "--seed" => result.seed = Some(value()?.parse().context("Invalid seed")?),
+            "--size" => result.size = Some(value()?.parse().context("Invalid size")?),
+            "--threads" => {
+                result.threads =
+                    Some(value()?.parse().context("Invalid number of threads")?)
+            }
+            "--param" => result.params.set_arg(&value()?)?,
+            "--config" => result.config = Some(value()?.into()),
+            "--format" => result.format = value()?.parse()?,
+            "--timeout" => {
+                let secs: f64 = value()?.parse().context("Invalid timeout")?;
+                result.timeout = Some(
+                    Duration::try_from_secs_f64(secs)
+                        .ok()
+                        .filter(|timeout| !timeout.is_zero())
+                        .ok_or(anyhow!(
+                            "--timeout must be a positive number of seconds"
+                        ))?,
+                );
+            }
+            flag @ ("-v" | "-vv" | "-q") => {
+                let more = logging::verbosity([flag.to_owned()]).unwrap_or_default();
+                result.verbosity = Some(result.verbosity.unwrap_or_default() + more);
+            }
+            other => return Err(anyhow!("Unexpected argument '{other}'")),
+        }
+    }
+    if !result.params.values.is_empty() && result.day.is_none() {
+        return Err(anyhow!(
+            "--param requires --day, see --config for several days"
+        ));
+    }
+    if result.threads == Some(0) {
+        return Err(anyhow!("--threads must be at least 1"));
+    }
+    if let Some(input @ (InputSpec::Stdin | InputSpec::Path(_))) = &result.input {
+        if result.day.is_none() {
+            return Err(anyhow!("--input {input} requires --day"));
+        }
+    }
+    if result.seed.is_some() && result.input.is_some() {
+        return Err(anyhow!("--seed and --input cannot be combined"));
+    }
+    if result.size.is_some() && result.seed.is_none() {
+        return Err(anyhow!("--size requires --seed"));
+    }
+    Ok(result)
+}
+
+#[derive(Debug)]
+struct BenchArgs {
+    run: RunArgs,
+    runs: usize,
+    save: Option<PathBuf>,
+    baseline: Option<PathBuf>,
+    threshold: f64,
+}
+
+fn parse_bench_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<BenchArgs> {
+    let mut runs = 10;
+    let (mut save, mut baseline, mut threshold) = (None, None, 10.0);
+    let mut rest = vec![];
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(anyhow!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--runs" => runs = value()?.parse().context("Invalid number of runs")?,
+            "--save" => save = Some(value()?.into()),
+            "--baseline" => baseline = Some(value()?.into()),
+            "--threshold" => threshold = value()?.parse().context("Invalid threshold")?,
+            _ => rest.push(arg),
+        }
+    }
+    if runs == 0 {
+        return Err(anyhow!("--runs must be at least 1"));
+    }
+    Ok(BenchArgs {
+        run: parse_run_args(rest.into_iter())?,
+        runs,
+        save,
+        baseline,
+        threshold,
+    })
+}
+
+impl RunArgs {
+    fn days(&self) -> anyhow::Result<Vec<&'static days::Day>> {
+        match self.day {
+            Some(n) => Ok(vec![
+                days::find(n).ok_or(anyhow!("There is no solution for day {n}"))?
+            ]),
+            None => Ok(days::DAYS.iter().collect()),
+        }
+    }
+
+    fn read_input(&self, day: &days::Day) -> anyhow::Result<String> {
+        if let Some(seed) = self.seed {
+            return generate(day, seed, self.size.unwrap_or(DEFAULT_SIZE));
+        }
+        day.inputs()
+            .read(self.input.as_ref().unwrap_or(&InputSpec::Real))
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+
+    /// The parameters of `day`: its table in --config, then --param, starting from the
+    /// preset of the input unless one is given
+    fn params(&self, day: &days::Day) -> anyhow::Result<ParamSpec> {
+        let mut params = match &self.config {
+            Some(path) => Config::load(path)?.day(day.day),
+            None => ParamSpec::default(),
+        };
+        params.extend(self.params.clone());
+        let input = self.input.as_ref().unwrap_or(&InputSpec::Real);
+        params.preset.get_or_insert(Preset::for_input(input));
+        Ok(params)
+    }
+
+    /// Applies --threads to the thread pool
+    fn set_threads(&self) {
+        pool::set_threads(self.threads.unwrap_or(0));
+    }
+
+    /// Installs the logger for -v or -q and `RUST_LOG`
+    fn init_logging(&self) -> anyhow::Result<()> {
+        logging::init(logging::filter(self.verbosity)?);
+        Ok(())
+    }
+
+    /// Runs `f`, which solves one day, cancelling it once --timeout passed
+    fn with_timeout<R>(&self, f: impl FnOnce() -> R) -> R {
+        match self.timeout {
+            Some(timeout) => cancel::scope(Token::with_timeout(timeout), f),
+            None => f(),
+        }
+    }
+}
+
+fn generate(day: &days::Day, seed: u64, size: usize) -> anyhow::Result<String> {
+    (day.generate)(&mut Rng::new(seed), size)
+        .with_context(|| format!("Cannot generate an input for day {:02}", day.day))
+}
+
+/// A part that ran out of time, as printed with `--format json`
+#[derive(Debug, Serialize)]
+struct TimedOut<'a> {
+    day: u8,
+    part: u8,
+    timed_out: bool,
+    best_so_far: Option<&'a str>,
+}
+
+/// Solves the parts of `day`; the parts that ran out of time are `Err`
+fn solve_day(
+    args: &RunArgs,
+    day: &days::Day,
+) -> anyhow::Result<Vec<(Part, Result<Run, Cancelled>)>> {
+    let input = args.read_input(day)?;
+    let params = args.params(day)?;
+    let runs = (day.run)(&input, &args.parts(), &params)
+        .with_context(|| format!("Day {:02} failed", day.day))?;
+    runs.into_iter()
+        .map(|(part, run)| match run {
+            Ok(run) => Ok((part, Ok(run))),
+            Err(e) => match e.chain().find_map(|e| e.downcast_ref::<Cancelled>()) {
+                Some(cancelled) => Ok((part, Err(cancelled.clone()))),
+                None => Err(e.context(format!("Day {:02}, part {part} failed", day.day))),
+            },
+        })
+        .collect()
+}
+
+fn run(args: RunArgs) -> anyhow::Result<ExitCode> {
+    args.init_logging()?;
+    args.set_threads();
+    let days = args.days()?;
+    let solved = pool::map(&days, |day| args.with_timeout(|| solve_day(&args, day)));
+    let mut timed_out = 0;
+    // printed in order, up to the first day that failed
+    for (day, runs) in days.iter().zip(solved) {
+        for (part, run) in runs? {
+            let run = match run {
+                Ok(run) => run,
+                Err(cancelled) => {
+                    timed_out += 1;
+                    print_timed_out(&args, day, part, &cancelled);
+                    continue;
+                }
+            };
+            if args.format == Format::Json {
+                println!("{}", run.to_json(day.day, part));
+                continue;
+            }
+            let separator = match run.answer.contains('\n') {
+                true => "\n",
+                false => " ",
+            };
+            println!(
+                "Day {:02} ({}), part {part}:{separator}{}",
+                day.day, day.title, run.answer
+            );
+        }
+    }
+    match timed_out {
+        0 => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+fn print_timed_out(args: &RunArgs, day: &days::Day, part: Part, cancelled: &Cancelled) {
+    if args.format == Format::Json {
+        let report = TimedOut {
+            day: day.day,
+            part: part.number(),
+            timed_out: true,
+            best_so_far: cancelled.best_so_far.as_deref(),
+        };
+        let json = serde_json::to_string(&report).expect("reports have no maps");
+        println!("{json}");
+        return;
+    }
+    let timeout = args.timeout.unwrap_or_default();
+    let best = match &cancelled.best_so_far {
+        Some(best) => format!(", the best answer so far is {best}"),
+        None => String::new(),
+    };
+    println!(
+        "Day {:02} ({}), part {part}: timed out after {timeout:?}{best}",
+        day.day, day.title
+    );
+}
+
+fn verify(args: RunArgs) -> anyhow::Result<ExitCode> {
+    if args.input.is_some() || args.seed.is_some() {
+        return Err(anyhow!(
+            "verify does not take --input or --seed, see answers.toml instead"
+        ));
+    }
+    if args.params != ParamSpec::default() || args.config.is_some() {
+        return Err(anyhow!(
+            "verify does not take --param or --config, the answers are for the presets"
+        ));
+    }
+    if args.format != Format::Text {
+        return Err(anyhow!("--format is only supported by run"));
+    }
+    args.init_logging()?;
+    args.set_threads();
+    let days = args.days()?;
+    let checked = pool::map(&days, |day| args.with_timeout(|| verify::verify_day(day)));
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for checks in checked {
+        for check in checks? {
+            if args.part.is_some_and(|p| p != check.part) {
+                continue;
+            }
+            match &check.status {
+                verify::Status::Pass => passed += 1,
+                verify::Status::Missing { .. } => missing += 1,
+                status if status.is_failure() => failed += 1,
+                status => unreachable!("unhandled status {status:?}"),
+            }
+            println!(
+                "Day {:02} {:<16} part {}: {}",
+                check.day, check.input_file, check.part, check.status
+            );
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    match failed {
+        0 => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+fn bench(args: BenchArgs) -> anyhow::Result<ExitCode> {
+    if args.run.format != Format::Text {
+        return Err(anyhow!(
+            "--format is only supported by run, see --save for JSON"
+        ));
+    }
+    if args.run.timeout.is_some() {
+        return Err(anyhow!("--timeout is only supported by run and verify"));
+    }
+    // the days run one after the other, so that they do not skew each other's times
+    args.run.init_logging()?;
+    args.run.set_threads();
+    let baseline = args
+        .baseline
+        .as_deref()
+        .map(bench::Baseline::load)
+        .transpose()?;
+    let parts = args.run.parts();
+    let mut entries = vec![];
+    let mut regressions = 0;
+    for day in args.run.days()? {
+        let input = args.run.read_input(day)?;
+        let params = args.run.params(day)?;
+        let results =
+            bench::bench_day(day.day, day.run, &input, &params, &parts, args.runs)
+                .with_context(|| format!("Day {:02} failed", day.day))?;
+        for entry in results {
+            let comparison = baseline
+                .as_ref()
+                .and_then(|b| b.find(entry.day, entry.phase))
+                .map(|b| bench::compare(&entry.stats, &b.stats, args.threshold / 100.0));
+            if let Some(bench::Comparison::Regression(_)) = comparison {
+                regressions += 1;
+            }
+            let comparison = comparison.map(|c| format!("  ({c})")).unwrap_or_default();
+            println!(
+                "Day {:02} {:<6}  {}{comparison}",
+                entry.day, entry.phase, entry.stats
+            );
+            entries.push(entry);
+        }
+    }
+    if let Some(path) = &args.save {
+        bench::Baseline {
+            runs: args.runs,
+            entries,
+        }
+        .save(path)?;
+        println!("Saved baseline to {}", path.display());
+    }
+    if baseline.is_some() {
+        println!("{regressions} regressions");
+    }
+    match regressions {
+        0 => Ok(ExitCode::SUCCESS),
+        _ => Ok(ExitCode::FAILURE),
+    }
+}
+
+#[derive(Debug)]
+struct GenArgs {
+    day: u8,
+    seed: u64,
+    size: usize,
+}
+
+fn parse_gen_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<GenArgs> {
+    let (mut day, mut seed, mut size) = (None, 0, DEFAULT_SIZE);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(anyhow!("Missing value for {arg}"));
+        match arg.as_str() {
+            "--day" => day = Some(value()?.parse().context("Invalid day")?),
+            "--seed" => seed = value()?.parse().context("Invalid seed")?,
+            "--size" => size = value()?.parse().context("Invalid size")?,
+            other => return Err(anyhow!("Unexpected argument '{other}'")),
+        }
+    }
+    Ok(GenArgs {
+        day: day.ok_or(anyhow!("gen requires --day"))?,
+        seed,
+        size,
+    })
+}
+
+fn gen(args: GenArgs) -> anyhow::Result<()> {
+    let day = days::find(args.day)
+        .ok_or(anyhow!("There is no solution for day {}", args.day))?;
+    print!("{}", generate(day, args.seed, args.size)?);
+    Ok(())
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let mut args = env::args().skip(1);
+    match args.next().as_deref() {
+        Some("run") => run(parse_run_args(args)?),
+        Some("verify") => verify(parse_run_args(args)?),
+        Some("bench") => bench(parse_bench_args(args)?),
+        Some("gen") => gen(parse_gen_args(args)?).map(|()| ExitCode::SUCCESS),
+        Some("--help") | Some("-h") => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        _ => Err(anyhow!("{USAGE}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(str::to_owned)
+    }
+
+    #[test]
+    fn parse_run_args_defaults() {
+        let args = parse_run_args(args("")).unwrap();
+        assert_eq!(args.day, None);
+        assert_eq!(args.part, None);
+        assert_eq!(args.input, None);
+    }
+
+    #[test]
+    fn parse_run_args_day_part_input() {
+        let args = parse_run_args(args("--day 12 --part 2 --input in.txt")).unwrap();
+        assert_eq!(args.day, Some(12));
+        assert_eq!(args.part, Some(Part::Two));
+        assert_eq!(args.input, Some(InputSpec::Path("in.txt".into())));
+    }
+
+    #[test]
+    fn parse_run_args_named_input_without_day() {
+        let args = parse_run_args(args("--input example")).unwrap();
+        assert_eq!(args.input, Some(InputSpec::Example));
+    }
+
+    #[test]
+    fn parse_run_args_errors() {
+        assert!(parse_run_args(args("--day")).is_err());
+        assert!(parse_run_args(args("--day x")).is_err());
+        assert!(parse_run_args(args("--part 3")).is_err());
+        assert!(parse_run_args(args("--input in.txt")).is_err());
+        assert!(parse_run_args(args("--input -")).is_err());
+        assert!(parse_run_args(args("--frobnicate")).is_err());
+    }
+
+    #[test]
+    fn parse_run_args_generated_input() {
+        let run = parse_run_args(args("--day 7 --seed 42 --size 10")).unwrap();
+        assert_eq!(run.seed, Some(42));
+        assert_eq!(run.size, Some(10));
+        assert!(parse_run_args(args("--seed 1 --input real")).is_err());
+        assert!(parse_run_args(args("--size 10")).is_err());
+        assert!(parse_run_args(args("--seed x")).is_err());
+    }
+
+    #[test]
+    fn parse_run_args_format() {
+        assert_eq!(parse_run_args(args("")).unwrap().format, Format::Text);
+        let run = parse_run_args(args("--format json")).unwrap();
+        assert_eq!(run.format, Format::Json);
+        assert!(parse_run_args(args("--format xml")).is_err());
+    }
+
+    #[test]
+    fn parse_run_args_params() {
+        let run = parse_run_args(args("--day 15 --param row=11 --param preset=example"))
+            .unwrap();
+        assert_eq!(run.params.preset, Some(Preset::Example));
+        assert_eq!(run.params.values, [("row".to_owned(), "11".to_owned())]);
+        let run = parse_run_args(args("--config params.toml")).unwrap();
+        assert_eq!(run.config, Some(PathBuf::from("params.toml")));
+        assert!(parse_run_args(args("--param row=11")).is_err());
+        assert!(parse_run_args(args("--day 15 --param row")).is_err());
+        assert!(parse_run_args(args("--param preset=fast")).is_err());
+    }
+
+    #[test]
+    fn params_follow_the_input() {
+        let day = days::find(15).unwrap();
+        let run = parse_run_args(args("--day 15 --input example")).unwrap();
+        assert_eq!(run.params(day).unwrap().preset, Some(Preset::Example));
+        let run = parse_run_args(args("--day 15 --seed 1")).unwrap();
+        assert_eq!(run.params(day).unwrap().preset, Some(Preset::Real));
+        let run = parse_run_args(args("--day 15 --param preset=example")).unwrap();
+        let params = run.params(day).unwrap();
+        let input = day.inputs().read(&InputSpec::Example).unwrap();
+        let runs = (day.run)(&input, &[Part::One], &params).unwrap();
+        assert_eq!(runs[0].1.as_ref().unwrap().answer, "26");
+    }
+
+    #[test]
+    fn parse_run_args_verbosity() {
+        assert_eq!(parse_run_args(args("")).unwrap().verbosity, None);
+        assert_eq!(parse_run_args(args("-v")).unwrap().verbosity, Some(1));
+        assert_eq!(parse_run_args(args("-vv -q")).unwrap().verbosity, Some(1));
+        assert_eq!(
+            parse_run_args(args("-q --day 3")).unwrap().verbosity,
+            Some(-1)
+        );
+        assert!(parse_run_args(args("-x")).is_err());
+    }
+
+    #[test]
+    fn parse_run_args_timeout() {
+        assert_eq!(parse_run_args(args("")).unwrap().timeout, None);
+        let run = parse_run_args(args("--timeout 2.5")).unwrap();
+        assert_eq!(run.timeout, Some(Duration::from_millis(2500)));
+        assert!(parse_run_args(args("--timeout 0")).is_err());
+        assert!(parse_run_args(args("--timeout -1")).is_err());
+        assert!(parse_run_args(args("--timeout soon")).is_err());
+        assert!(parse_bench_args(args("--timeout 1")).is_ok());
+    }
+
+    #[test]
+    fn days_without_long_loops_finish_despite_timeouts() {
+        let solve = |day| {
+            let run =
+                parse_run_args(args("--input example --timeout 0.000000001")).unwrap();
+            let day = days::find(day).unwrap();
+            run.with_timeout(|| solve_day(&run, day)).unwrap()
+        };
+        for (_, run) in solve(1) {
+            assert!(run.is_ok());
+        }
+        for (_, run) in solve(16) {
+            assert!(run.unwrap_err().best_so_far.is_some());
+        }
+    }
+
+    #[test]
+    fn parse_run_args_threads() {
+        assert_eq!(
+            parse_run_args(args("--threads 3")).unwrap().threads,
+            Some(3)
+        );
+        assert!(parse_run_args(args("--threads 0")).is_err());
+        assert!(parse_run_args(args("--threads many")).is_err());
+    }
+
+    #[test]
+    fn parse_gen_args_defaults() {
+        let gen = parse_gen_args(args("--day 16")).unwrap();
+        assert_eq!((gen.day, gen.seed, gen.size), (16, 0, DEFAULT_SIZE));
+        let gen = parse_gen_args(args("--size 5 --day 1 --seed 9")).unwrap();
+        assert_eq!((gen.day, gen.seed, gen.size), (1, 9, 5));
+        assert!(parse_gen_args(args("--seed 9")).is_err());
+    }
+
+    #[test]
+    fn parse_bench_args_mixed_with_run_args() {
+        let bench = parse_bench_args(args(
+            "--day 3 --runs 5 --part 1 --save b.json --threshold 2.5",
+        ))
+        .unwrap();
+        assert_eq!(bench.run.day, Some(3));
+        assert_eq!(bench.run.part, Some(Part::One));
+        assert_eq!(bench.runs, 5);
+        assert_eq!(bench.save, Some(PathBuf::from("b.json")));
+        assert_eq!(bench.baseline, None);
+        assert_eq!(bench.threshold, 2.5);
+        assert_eq!(parse_bench_args(args("")).unwrap().runs, 10);
+        assert!(parse_bench_args(args("--runs 0")).is_err());
+        assert!(parse_bench_args(args("--threshold")).is_err());
+        assert!(parse_bench_args(args("--frobnicate")).is_err());
+    }
+
+    #[test]
+    fn generated_inputs_are_reproducible_and_solvable() {
+        for day in &days::DAYS {
+            let input = generate(day, 7, 10).unwrap();
+            assert_eq!(input, generate(day, 7, 10).unwrap(), "day {}", day.day);
+            assert_ne!(input, generate(day, 8, 10).unwrap(), "day {}", day.day);
+        }
+        // the smallest sizes, a usual one and one at which days 11 and 16 clamp
+        for size in [1, 2, 10, 128] {
+            for seed in 0..3 {
+                for day in &days::DAYS {
+                    let input = generate(day, seed, size).unwrap();
+                    for part in Part::ALL {
+                        // large inputs may take too long unoptimized, e.g., day 15's
+                        // huge area, but must not fail otherwise
+                        let token = Token::with_timeout(Duration::from_millis(250));
+                        let run = cancel::scope(token, || {
+                            (day.run)(&input, &[part], &ParamSpec::default())
+                        })
+                        .and_then(|mut runs| runs.remove(0).1);
+                        let cancelled =
+                            |e: &anyhow::Error| e.downcast_ref::<Cancelled>().is_some();
+                        assert!(
+                            run.as_ref().map_or_else(cancelled, |_| true),
+                            "day {} part {part}, seed {seed}, size {size}: {run:?}",
+                            day.day
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn answers_do_not_depend_on_threads() {
+        let answers = |threads| {
+            pool::set_threads(threads);
+            [15, 16]
+                .map(|n| {
+                    let day = days::find(n).unwrap();
+                    let input = day.inputs().read(&InputSpec::Example).unwrap();
+                    let params = ParamSpec {
+                        preset: Some(Preset::Example),
+                        values: vec![],
+                    };
+                    let runs = (day.run)(&input, &Part::ALL, &params).unwrap();
+                    runs.into_iter()
+                        .map(|(_, run)| run.unwrap().answer)
+                        .collect::<Vec<_>>()
+                })
+                .concat()
+        };
+        assert_eq!(answers(1), answers(4));
+        pool::set_threads(0);
+    }
+
+    #[test]
+    fn days_are_registered_in_order() {
+        for (idx, day) in days::DAYS.iter().enumerate() {
+            assert_eq!(day.day as usize, idx + 1);
+            assert_eq!(day.dir, format!("{:02}", idx + 1));
+        }
+    }
+}
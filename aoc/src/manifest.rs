@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::Context;
+use common::solution::Part;
+use serde::Deserialize;
+
+pub const MANIFEST_FILE_NAME: &str = "answers.toml";
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Int(i64),
+    Text(String),
+    /// `{ error = "..." }`: the part fails with a message containing the text
+    Error {
+        error: String,
+    },
+}
+
+impl Answer {
+    fn to_text(&self) -> Option<String> {
+        match self {
+            Answer::Int(i) => Some(i.to_string()),
+            Answer::Text(s) => Some(s.clone()),
+            Answer::Error { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExpectedAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+/// Expected answers of a day, keyed by input file name (relative to the day's crate
+/// directory)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Manifest(BTreeMap<String, ExpectedAnswers>);
+
+impl Manifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Error reading manifest {}", path.display()))?;
+        Self::parse(&text)
+            .with_context(|| format!("Error parsing manifest {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn input_files(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    fn answer(&self, input_file: &str, part: Part) -> Option<&Answer> {
+        let answers = self.0.get(input_file)?;
+        match part {
+            Part::One => answers.part1.as_ref(),
+            Part::Two => answers.part2.as_ref(),
+        }
+    }
+
+    pub fn expected(&self, input_file: &str, part: Part) -> Option<String> {
+        self.answer(input_file, part)?.to_text()
+    }
+
+    /// Part of the error message if the part is expected to fail
+    pub fn expected_error(&self, input_file: &str, part: Part) -> Option<String> {
+        match self.answer(input_file, part)? {
+            Answer::Error { error } => Some(error.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = Manifest::parse(
+            "[\"test_data.txt\"]\n\
+             part1 = 24000\n\
+             part2 = \"MCD\"\n\
+             \n\
+             [\"input.txt\"]\n\
+             part2 = 12691026767556\n",
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.input_files().collect::<Vec<_>>(),
+            vec!["input.txt", "test_data.txt"]
+        );
+        assert_eq!(
+            manifest.expected("test_data.txt", Part::One).as_deref(),
+            Some("24000")
+        );
+        assert_eq!(
+            manifest.expected("test_data.txt", Part::Two).as_deref(),
+            Some("MCD")
+        );
+        assert_eq!(manifest.expected("input.txt", Part::One), None);
+        assert_eq!(
+            manifest.expected("input.txt", Part::Two).as_deref(),
+            Some("12691026767556")
+        );
+        assert_eq!(manifest.expected("other.txt", Part::One), None);
+        assert_eq!(manifest.expected_error("input.txt", Part::Two), None);
+    }
+
+    #[test]
+    fn parse_expected_error() {
+        let manifest =
+            Manifest::parse("[\"test_data.txt\"]\npart2 = { error = \"Unknown\" }\n")
+                .unwrap();
+        assert_eq!(manifest.expected("test_data.txt", Part::Two), None);
+        assert_eq!(
+            manifest
+                .expected_error("test_data.txt", Part::Two)
+                .as_deref(),
+            Some("Unknown")
+        );
+    }
+
+    #[test]
+    fn reject_unknown_keys() {
+        assert!(Manifest::parse("[\"input.txt\"]\npart3 = 1\n").is_err());
+    }
+}
@@ -0,0 +1,217 @@
+use std::{collections::BTreeSet, fmt};
+
+use anyhow::anyhow;
+use common::{
+    input,
+    params::{ParamSpec, Preset},
+    solution::{Part, Run},
+};
+
+use crate::{
+    days::Day,
+    manifest::{Manifest, MANIFEST_FILE_NAME},
+};
+
+/// Input files that are checked even if the manifest does not mention them
+const STANDARD_INPUT_FILES: [&str; 2] = [input::EXAMPLE_FILE_NAME, input::REAL_FILE_NAME];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    Error(String),
+    /// There is no expected answer in the manifest (or no input file to check against)
+    Missing {
+        actual: Option<String>,
+    },
+}
+
+impl Status {
+    fn new(expected: Option<String>, result: anyhow::Result<Run>) -> Self {
+        match (expected, result) {
+            (_, Err(e)) => Status::Error(format!("{e:#}")),
+            (None, Ok(run)) => Status::Missing {
+                actual: Some(run.answer),
+            },
+            (Some(expected), Ok(run)) if expected == run.answer => Status::Pass,
+            (Some(expected), Ok(run)) => Status::Fail {
+                expected,
+                actual: run.answer,
+            },
+        }
+    }
+
+    /// Status of a part that should fail with an error containing `error`
+    fn expecting_error(error: &str, result: anyhow::Result<Run>) -> Self {
+        match result {
+            Err(e) if format!("{e:#}").contains(error) => Status::Pass,
+            Err(e) => Status::Error(format!("{e:#}")),
+            Ok(run) => Status::Fail {
+                expected: format!("error '{error}'"),
+                actual: run.answer,
+            },
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Status::Fail { .. } | Status::Error(_))
+    }
+}
+
+/// Shortens multi-line answers (e.g., day 10's CRT image) to their first line
+fn one_line(answer: &str) -> String {
+    match answer.split_once('\n') {
+        Some((first, _)) => format!("{first} [...]"),
+        None => answer.to_owned(),
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { expected, actual } => write!(
+                f,
+                "FAIL (expected {}, got {})",
+                one_line(expected),
+                one_line(actual)
+            ),
+            Status::Error(e) => write!(f, "FAIL ({e})"),
+            Status::Missing { actual: Some(a) } => {
+                write!(f, "missing (got {})", one_line(a))
+            }
+            Status::Missing { actual: None } => write!(f, "missing (no input file)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub input_file: String,
+    pub part: Part,
+    pub status: Status,
+}
+
+pub fn verify_day(day: &Day) -> anyhow::Result<Vec<Check>> {
+    let inputs = day.inputs();
+    let manifest_path = inputs.dir().join(MANIFEST_FILE_NAME);
+    let manifest = match manifest_path.exists() {
+        true => Manifest::load(&manifest_path)?,
+        false => Manifest::default(),
+    };
+    let input_files: BTreeSet<&str> = STANDARD_INPUT_FILES
+        .into_iter()
+        .filter(|f| inputs.file(f).is_some())
+        .chain(manifest.input_files())
+        .collect();
+    let mut checks = vec![];
+    for input_file in input_files {
+        let input = match inputs.file(input_file) {
+            Some(input_path) => Some(input::read_file(&input_path)?),
+            None => None,
+        };
+        let params = ParamSpec {
+            preset: Some(Preset::for_file(input_file)),
+            values: vec![],
+        };
+        let runs = input
+            .as_deref()
+            .map(|input| (day.run)(input, &Part::ALL, &params));
+        // an input that fails to parse fails both parts
+        let results: Vec<Option<anyhow::Result<Run>>> = match runs {
+            Some(Ok(runs)) => runs.into_iter().map(|(_, run)| Some(run)).collect(),
+            Some(Err(e)) => Part::ALL.map(|_| Some(Err(anyhow!("{e:#}")))).into(),
+            None => Part::ALL.map(|_| None).into(),
+        };
+        for (part, result) in Part::ALL.into_iter().zip(results) {
+            let expected = manifest.expected(input_file, part);
+            let status = match (result, manifest.expected_error(input_file, part)) {
+                (Some(run), Some(error)) => Status::expecting_error(&error, run),
+                (Some(run), None) => Status::new(expected, run),
+                (None, _) => Status::Missing { actual: None },
+            };
+            checks.push(Check {
+                day: day.day,
+                input_file: input_file.to_owned(),
+                part,
+                status,
+            });
+        }
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn answer(s: &str) -> anyhow::Result<Run> {
+        Ok(Run {
+            answer: s.to_owned(),
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+            diagnostics: vec![],
+        })
+    }
+
+    #[test]
+    fn status_from_answers() {
+        assert_eq!(Status::new(Some("42".into()), answer("42")), Status::Pass);
+        assert_eq!(
+            Status::new(Some("42".into()), answer("43")),
+            Status::Fail {
+                expected: "42".into(),
+                actual: "43".into()
+            }
+        );
+        assert_eq!(
+            Status::new(None, answer("43")),
+            Status::Missing {
+                actual: Some("43".into())
+            }
+        );
+        assert!(matches!(
+            Status::new(Some("42".into()), Err(anyhow::anyhow!("oops"))),
+            Status::Error(_)
+        ));
+    }
+
+    #[test]
+    fn status_from_expected_errors() {
+        let err = || Err(anyhow::anyhow!("Unknown glyph at column 0"));
+        assert_eq!(
+            Status::expecting_error("Unknown glyph", err()),
+            Status::Pass
+        );
+        assert!(matches!(
+            Status::expecting_error("Missing", err()),
+            Status::Error(_)
+        ));
+        assert!(Status::expecting_error("Unknown glyph", answer("AB")).is_failure());
+    }
+
+    #[test]
+    fn only_mismatches_and_errors_are_failures() {
+        assert!(!Status::Pass.is_failure());
+        assert!(!Status::Missing { actual: None }.is_failure());
+        assert!(Status::Error("oops".into()).is_failure());
+        assert!(Status::Fail {
+            expected: "1".into(),
+            actual: "2".into()
+        }
+        .is_failure());
+    }
+
+    #[test]
+    fn multi_line_answers_are_shortened() {
+        let status = Status::Missing {
+            actual: Some("#..\n.#.".into()),
+        };
+        assert_eq!(status.to_string(), "missing (got #.. [...])");
+    }
+}
@@ -0,0 +1,448 @@
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign,
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Iterates the set bits of `words` in ascending order
+#[derive(Clone, Debug)]
+pub struct Ones<'a> {
+    words: &'a [u64],
+    /// Not yet returned bits of `words[0]`
+    current: u64,
+    base: usize,
+}
+
+impl<'a> Ones<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        match words.split_first() {
+            Some((&first, rest)) => Self {
+                words: rest,
+                current: first,
+                base: 0,
+            },
+            None => Self {
+                words,
+                current: 0,
+                base: 0,
+            },
+        }
+    }
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.current == 0 {
+            let (&next, rest) = self.words.split_first()?;
+            self.current = next;
+            self.words = rest;
+            self.base += WORD_BITS;
+        }
+        let bit = self.current.trailing_zeros() as usize;
+        self.current &= self.current - 1;
+        Some(self.base + bit)
+    }
+}
+
+/// Whether every bit of `a` is set in `b`; missing words count as zero
+fn words_subset(a: &[u64], b: &[u64]) -> bool {
+    a.iter()
+        .enumerate()
+        .all(|(i, &w)| w & !b.get(i).copied().unwrap_or(0) == 0)
+}
+
+fn words_disjoint(a: &[u64], b: &[u64]) -> bool {
+    a.iter().zip(b).all(|(&x, &y)| x & y == 0)
+}
+
+/// A set of small integers below `64 * W`, stored inline
+///
+/// Sets are ordered by their highest differing element, like the integers they would
+/// form as bit masks.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitSet<const W: usize> {
+    words: [u64; W],
+}
+
+impl<const W: usize> BitSet<W> {
+    pub const CAPACITY: usize = W * WORD_BITS;
+
+    pub const fn new() -> Self {
+        Self { words: [0; W] }
+    }
+
+    /// Panics if `i` is not below [`Self::CAPACITY`]
+    pub fn insert(&mut self, i: usize) -> bool {
+        assert!(i < Self::CAPACITY, "{i} does not fit into the bit set");
+        let (word, mask) = (i / WORD_BITS, 1 << (i % WORD_BITS));
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    pub fn remove(&mut self, i: usize) -> bool {
+        let Some(word) = self.words.get_mut(i / WORD_BITS) else {
+            return false;
+        };
+        let mask = 1 << (i % WORD_BITS);
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        removed
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / WORD_BITS)
+            .is_some_and(|w| w & (1 << (i % WORD_BITS)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words = [0; W];
+    }
+
+    /// The elements in ascending order
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(&self.words)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        words_subset(&self.words, &other.words)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        words_disjoint(&self.words, &other.words)
+    }
+
+    fn zip_with(mut self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        for (a, &b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a = f(*a, b);
+        }
+        self
+    }
+}
+
+impl<const W: usize> Default for BitSet<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize> PartialOrd for BitSet<W> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const W: usize> Ord for BitSet<W> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.words.iter().rev().cmp(other.words.iter().rev())
+    }
+}
+
+impl<const W: usize> std::fmt::Debug for BitSet<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const W: usize> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const W: usize> Extend<usize> for BitSet<W> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|i| {
+            self.insert(i);
+        });
+    }
+}
+
+/// A set of small integers that grows as needed
+///
+/// Sets are ordered by their highest differing element, like the integers they would
+/// form as bit masks.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct DynBitSet {
+    /// Without trailing zero words, so that equal sets compare and hash equally
+    words: Vec<u64>,
+}
+
+impl DynBitSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, mask) = (i / WORD_BITS, 1 << (i % WORD_BITS));
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    pub fn remove(&mut self, i: usize) -> bool {
+        let Some(word) = self.words.get_mut(i / WORD_BITS) else {
+            return false;
+        };
+        let mask = 1 << (i % WORD_BITS);
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        self.trim();
+        removed
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / WORD_BITS)
+            .is_some_and(|w| w & (1 << (i % WORD_BITS)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// The elements in ascending order
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(&self.words)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        words_subset(&self.words, &other.words)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        words_disjoint(&self.words, &other.words)
+    }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    fn zip_with(mut self, other: &Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (i, a) in self.words.iter_mut().enumerate() {
+            *a = f(*a, other.words.get(i).copied().unwrap_or(0));
+        }
+        self.trim();
+        self
+    }
+}
+
+impl PartialOrd for DynBitSet {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DynBitSet {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.words
+            .len()
+            .cmp(&other.words.len())
+            .then_with(|| self.words.iter().rev().cmp(other.words.iter().rev()))
+    }
+}
+
+impl std::fmt::Debug for DynBitSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for DynBitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for DynBitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|i| {
+            self.insert(i);
+        });
+    }
+}
+
+/// Set algebra with `|` (union), `&` (intersection), `-` (difference) and `^`
+/// (symmetric difference)
+macro_rules! impl_set_ops {
+    ($([$($gen:tt)*] $set:ty),*) => {$(
+        impl<$($gen)*> BitOr<&$set> for $set {
+            type Output = $set;
+            fn bitor(self, rhs: &$set) -> $set {
+                self.zip_with(rhs, |a, b| a | b)
+            }
+        }
+
+        impl<$($gen)*> BitAnd<&$set> for $set {
+            type Output = $set;
+            fn bitand(self, rhs: &$set) -> $set {
+                self.zip_with(rhs, |a, b| a & b)
+            }
+        }
+
+        impl<$($gen)*> Sub<&$set> for $set {
+            type Output = $set;
+            fn sub(self, rhs: &$set) -> $set {
+                self.zip_with(rhs, |a, b| a & !b)
+            }
+        }
+
+        impl<$($gen)*> BitXor<&$set> for $set {
+            type Output = $set;
+            fn bitxor(self, rhs: &$set) -> $set {
+                self.zip_with(rhs, |a, b| a ^ b)
+            }
+        }
+
+        impl<$($gen)*> BitOrAssign<&$set> for $set {
+            fn bitor_assign(&mut self, rhs: &$set) {
+                *self = std::mem::take(self) | rhs;
+            }
+        }
+
+        impl<$($gen)*> BitAndAssign<&$set> for $set {
+            fn bitand_assign(&mut self, rhs: &$set) {
+                *self = std::mem::take(self) & rhs;
+            }
+        }
+
+        impl<$($gen)*> SubAssign<&$set> for $set {
+            fn sub_assign(&mut self, rhs: &$set) {
+                *self = std::mem::take(self) - rhs;
+            }
+        }
+
+        impl<$($gen)*> BitXorAssign<&$set> for $set {
+            fn bitxor_assign(&mut self, rhs: &$set) {
+                *self = std::mem::take(self) ^ rhs;
+            }
+        }
+    )*};
+}
+
+impl_set_ops!([const W: usize] BitSet<W>, [] DynBitSet);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Set = BitSet<2>;
+
+    fn set(elems: &[usize]) -> Set {
+        elems.iter().copied().collect()
+    }
+
+    fn dyn_set(elems: &[usize]) -> DynBitSet {
+        elems.iter().copied().collect()
+    }
+
+    #[test]
+    fn insert_remove_contains() {
+        let mut s = Set::new();
+        assert!(s.insert(0) && s.insert(64) && s.insert(127));
+        assert!(!s.insert(64));
+        assert!(s.contains(64) && !s.contains(63) && !s.contains(128));
+        assert!(!s.contains(1000));
+        assert_eq!(s.len(), 3);
+        assert!(s.remove(64) && !s.remove(64) && !s.remove(1000));
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![0, 127]);
+        s.clear();
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_beyond_capacity() {
+        BitSet::<1>::new().insert(64);
+    }
+
+    #[test]
+    fn subsets_and_supersets() {
+        assert!(set(&[0, 1, 2]).is_subset(&set(&[0, 1, 2])));
+        assert!(set(&[1, 2]).is_subset(&set(&[0, 1, 2])));
+        assert!(!set(&[0, 1, 2]).is_subset(&set(&[1, 2])));
+        assert!(set(&[0, 1, 2, 100]).is_superset(&set(&[1, 100])));
+        assert!(!set(&[1, 2]).is_superset(&set(&[1, 2, 3])));
+        assert!(set(&[1, 70]).is_disjoint(&set(&[2, 71])));
+        assert!(dyn_set(&[1, 2]).is_subset(&dyn_set(&[1, 2, 300])));
+        assert!(!dyn_set(&[1, 300]).is_subset(&dyn_set(&[1, 2])));
+        assert!(dyn_set(&[1, 300]).is_superset(&dyn_set(&[])));
+    }
+
+    #[test]
+    fn set_algebra() {
+        let (a, b) = (set(&[1, 2, 65]), set(&[2, 3, 65, 100]));
+        assert_eq!(a | &b, set(&[1, 2, 3, 65, 100]));
+        assert_eq!(a & &b, set(&[2, 65]));
+        assert_eq!(a - &b, set(&[1]));
+        assert_eq!(a ^ &b, set(&[1, 3, 100]));
+        let mut c = a;
+        c -= &set(&[1]);
+        c |= &set(&[7]);
+        assert_eq!(c, set(&[2, 7, 65]));
+        let (a, b) = (dyn_set(&[1, 200]), dyn_set(&[1, 2]));
+        assert_eq!(a.clone() & &b, dyn_set(&[1]));
+        assert_eq!(a.clone() - &dyn_set(&[200]), dyn_set(&[1]));
+        assert_eq!(a | &b, dyn_set(&[1, 2, 200]));
+    }
+
+    #[test]
+    fn iteration_uses_all_words() {
+        let elems = [0, 5, 63, 64, 66, 127, 128, 191, 400];
+        assert_eq!(dyn_set(&elems).iter().collect::<Vec<_>>(), elems);
+        assert_eq!(BitSet::<7>::from_iter(elems).iter().count(), elems.len());
+        assert_eq!(DynBitSet::new().iter().next(), None);
+        assert_eq!(BitSet::<0>::new().iter().next(), None);
+    }
+
+    #[test]
+    fn equal_sets_compare_equal_and_order_like_masks() {
+        let mut s = dyn_set(&[3, 500]);
+        s.remove(500);
+        assert_eq!(s, dyn_set(&[3]));
+        assert!(dyn_set(&[3]) < dyn_set(&[64]));
+        assert!(dyn_set(&[0, 1, 2]) < dyn_set(&[3]));
+        assert!(set(&[0, 1, 2]) < set(&[3]));
+        assert!(set(&[100]) > set(&[0, 99]));
+        assert_eq!(set(&[1, 2]).max(set(&[1, 3])), set(&[1, 3]));
+    }
+}
@@ -0,0 +1,143 @@
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt::{self, Display},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+thread_local! {
+    /// The token of the innermost [`scope`] on this thread
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+/// Tells solvers to stop early, when cancelled by hand or once its deadline passed
+///
+/// Clones share the same state, so cancelling one cancels all.
+#[derive(Debug, Clone, Default)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Token {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token that is cancelled `timeout` from now
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            deadline: Some(Instant::now() + timeout),
+            ..Self::default()
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|deadline| {
+                let passed = Instant::now() >= deadline;
+                if passed {
+                    self.cancel();
+                }
+                passed
+            })
+    }
+}
+
+/// The error of a solver that stopped early, with the best answer it had found, if any
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cancelled {
+    pub best_so_far: Option<String>,
+}
+
+impl Cancelled {
+    pub fn with_best_so_far(best_so_far: impl Display) -> Self {
+        Self {
+            best_so_far: Some(best_so_far.to_string()),
+        }
+    }
+}
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.best_so_far {
+            Some(best) => write!(f, "Cancelled, the best answer so far is {best}"),
+            None => write!(f, "Cancelled"),
+        }
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Runs `f` with `token` as the token that [`check`] looks at on this thread
+///
+/// [`crate::pool`] passes the token on to its threads.
+pub fn scope<R>(token: Token, f: impl FnOnce() -> R) -> R {
+    let outer = CURRENT.replace(Some(token));
+    let result = f();
+    CURRENT.set(outer);
+    result
+}
+
+/// The token of the surrounding [`scope`] on this thread, if any
+pub fn current() -> Option<Token> {
+    CURRENT.with_borrow(Clone::clone)
+}
+
+/// Whether the token of the surrounding [`scope`] was cancelled, never outside of one
+pub fn is_cancelled() -> bool {
+    CURRENT.with_borrow(|token| token.as_ref().is_some_and(Token::is_cancelled))
+}
+
+/// Fails if the token of the surrounding [`scope`] was cancelled, for hot loops to bail
+/// out with `?`
+pub fn check() -> Result<(), Cancelled> {
+    match is_cancelled() {
+        true => Err(Cancelled::default()),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_the_token_of_the_scope() {
+        assert_eq!(check(), Ok(()));
+        let token = Token::new();
+        scope(token.clone(), || {
+            assert_eq!(check(), Ok(()));
+            token.cancel();
+            assert_eq!(check(), Err(Cancelled::default()));
+            scope(Token::new(), || assert_eq!(check(), Ok(())));
+            assert!(is_cancelled());
+        });
+        assert_eq!(check(), Ok(()));
+    }
+
+    #[test]
+    fn cancelled_once_the_deadline_passed() {
+        assert!(!Token::with_timeout(Duration::from_secs(3600)).is_cancelled());
+        let token = Token::with_timeout(Duration::ZERO);
+        assert!(token.clone().is_cancelled());
+        assert!(token.cancelled.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn shows_the_best_answer_so_far() {
+        assert_eq!(Cancelled::default().to_string(), "Cancelled");
+        assert_eq!(
+            Cancelled::with_best_so_far(1651).to_string(),
+            "Cancelled, the best answer so far is 1651"
+        );
+    }
+}
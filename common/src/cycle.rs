@@ -0,0 +1,222 @@
+use std::{collections::HashMap, convert::Infallible, hash::Hash};
+
+/// The states from step `start` on repeat every `len` steps
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extrapolation {
+    /// The measure after the requested number of steps
+    pub measure: i64,
+    /// The cycle that was skipped, if one was found before reaching the step count
+    pub cycle: Option<Cycle>,
+    /// Change of the measure per cycle, 0 without a cycle
+    pub cycle_gain: i64,
+    /// The steps actually run on the state, up to where the cycle was confirmed
+    pub steps_run: usize,
+}
+
+/// Runs a simulation for `steps` steps, skipping ahead once its states repeat
+///
+/// `key` identifies a state. A repeated key is only trusted after the keys and the
+/// changes of `measure` repeated for one more full cycle; a key may thus be a cheap
+/// fingerprint, e.g., the top rows of a pile. Afterwards, `measure` is extrapolated,
+/// assuming that it grows by the same amount in every cycle. `state` is left at the
+/// step at which the cycle was confirmed, see [`Extrapolation::steps_run`].
+pub fn extrapolate<S, K>(
+    state: &mut S,
+    steps: usize,
+    mut step: impl FnMut(&mut S),
+    key: impl FnMut(&S) -> K,
+    measure: impl FnMut(&S) -> i64,
+) -> Extrapolation
+where
+    K: Hash + Eq + Clone,
+{
+    let step = |state: &mut S| {
+        step(state);
+        Ok::<_, Infallible>(())
+    };
+    match try_extrapolate(state, steps, step, key, measure) {
+        Ok(extrapolation) => extrapolation,
+        Err(never) => match never {},
+    }
+}
+
+/// [`extrapolate`] with a step that can fail, e.g., once the simulation was
+/// [`crate::cancel`]led, returning the first error
+pub fn try_extrapolate<S, K, E>(
+    state: &mut S,
+    steps: usize,
+    mut step: impl FnMut(&mut S) -> Result<(), E>,
+    mut key: impl FnMut(&S) -> K,
+    mut measure: impl FnMut(&S) -> i64,
+) -> Result<Extrapolation, E>
+where
+    K: Hash + Eq + Clone,
+{
+    let mut measures = vec![measure(state)];
+    let mut keys = vec![key(state)];
+    let mut seen = HashMap::from([(keys[0].clone(), 0)]);
+    // cycle found, but not yet confirmed
+    let mut candidate: Option<(Cycle, usize)> = None;
+    for i in 1..=steps {
+        step(state)?;
+        measures.push(measure(state));
+        keys.push(key(state));
+        if let Some((cycle, confirm_at)) = candidate {
+            let gain = measures[cycle.start + cycle.len] - measures[cycle.start];
+            if keys[i] != keys[i - cycle.len]
+                || measures[i] - measures[i - cycle.len] != gain
+            {
+                candidate = None;
+            } else if i == confirm_at {
+                let remaining = steps - i;
+                let rest = remaining % cycle.len;
+                let rest_gain = measures[i - cycle.len + rest] - measures[i - cycle.len];
+                return Ok(Extrapolation {
+                    measure: measures[i]
+                        + (remaining / cycle.len) as i64 * gain
+                        + rest_gain,
+                    cycle: Some(cycle),
+                    cycle_gain: gain,
+                    steps_run: i,
+                });
+            }
+        }
+        if let Some(start) = seen.insert(keys[i].clone(), i) {
+            if candidate.is_none() {
+                let cycle = Cycle {
+                    start,
+                    len: i - start,
+                };
+                candidate = Some((cycle, i + cycle.len));
+            }
+        }
+    }
+    Ok(Extrapolation {
+        measure: measures[steps],
+        cycle: None,
+        cycle_gain: 0,
+        steps_run: steps,
+    })
+}
+
+/// Finds the cycle of the sequence `start`, `f(start)`, `f(f(start))`, ... with Brent's
+/// algorithm, comparing whole states and storing only two at a time
+///
+/// The sequence must eventually repeat, which it does if there are finitely many states.
+pub fn brent<S: Clone + Eq>(start: S, f: impl Fn(&S) -> S) -> Cycle {
+    // find the cycle length, searching in windows of growing powers of two
+    let (mut power, mut len) = (1, 1);
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = f(&hare);
+        len += 1;
+    }
+    // find the first state of the cycle, with the hare `len` steps ahead
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..len {
+        hare = f(&hare);
+    }
+    let mut cycle_start = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        cycle_start += 1;
+    }
+    Cycle {
+        start: cycle_start,
+        len,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0, 1, ..., 9, then 10, 11, 12, 13 repeating; the measure grows by the state
+    fn pattern(i: u64) -> u64 {
+        match i {
+            0..=9 => i,
+            _ => 10 + (i - 10) % 4,
+        }
+    }
+
+    #[test]
+    fn extrapolate_far_beyond_the_cycle() {
+        for steps in [0, 5, 10, 23, 24, 1000, 1_000_000_000_001] {
+            let mut state = (0, 0);
+            let res = extrapolate(
+                &mut state,
+                steps as usize,
+                |(i, sum)| {
+                    *i += 1;
+                    *sum += pattern(*i);
+                },
+                |&(i, _)| pattern(i),
+                |&(_, sum)| sum as i64,
+            );
+            let expected: u64 = match steps {
+                0..=30 => (1..=steps).map(pattern).sum(),
+                _ => {
+                    let sum_to_9: u64 = 45;
+                    let cycles = (steps - 9) / 4;
+                    let rest: u64 = (0..(steps - 9) % 4).map(|k| 10 + k).sum();
+                    sum_to_9 + cycles * 46 + rest
+                }
+            };
+            assert_eq!(res.measure, expected as i64, "after {steps} steps");
+            if steps >= 1000 {
+                assert_eq!(res.cycle, Some(Cycle { start: 10, len: 4 }));
+                assert_eq!(res.cycle_gain, 46);
+            }
+        }
+    }
+
+    #[test]
+    fn extrapolate_rejects_coincidental_keys() {
+        // the key repeats after 3 steps once, but the state only cycles later on
+        let keys = [0, 1, 2, 0, 5, 6, 7, 8, 6, 7, 8, 6, 7, 8, 6, 7, 8];
+        let mut i = 0;
+        let res = extrapolate(&mut i, 100, |i| *i += 1, |&i| keys[i], |&i| i as i64);
+        assert_eq!(res.cycle, Some(Cycle { start: 5, len: 3 }));
+        assert_eq!(res.measure, 100);
+        assert_eq!((res.steps_run, i), (11, 11));
+    }
+
+    #[test]
+    fn try_extrapolate_stops_at_the_first_error() {
+        let mut i = 0;
+        let step = |i: &mut usize| {
+            if *i == 7 {
+                return Err(format!("stuck at {i}"));
+            }
+            *i += 1;
+            Ok(())
+        };
+        let res = try_extrapolate(&mut i, 100, step, |&i| i, |&i| i as i64);
+        assert_eq!(res, Err("stuck at 7".to_owned()));
+        assert_eq!(i, 7);
+    }
+
+    #[test]
+    fn brent_finds_cycle_start_and_length() {
+        assert_eq!(
+            brent(0u64, |&i| pattern(i + 1)),
+            Cycle { start: 10, len: 4 }
+        );
+        assert_eq!(brent(3u8, |&i| i), Cycle { start: 0, len: 1 });
+        assert_eq!(brent(1u32, |&i| i * 3 % 7), Cycle { start: 0, len: 6 });
+    }
+}
@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+
+thread_local! {
+    /// Diagnostics reported during the innermost [`collect`] on this thread
+    static COLLECTED: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+}
+
+/// Adds a diagnostic, e.g., how many states a search visited, to the surrounding
+/// [`collect`] on the same thread, if any
+///
+/// Solutions report diagnostics as info log records, which the [`crate::logging`]
+/// logger passes on to here.
+pub(crate) fn push(message: String) {
+    COLLECTED.with_borrow_mut(|collected| {
+        if let Some(collected) = collected {
+            collected.push(message);
+        }
+    });
+}
+
+/// Runs `f`, returning its result and the diagnostics it reported on this thread
+pub fn collect<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    let outer = COLLECTED.replace(Some(vec![]));
+    let result = f();
+    let collected = COLLECTED.replace(outer).unwrap_or_default();
+    (result, collected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_nested_reports() {
+        push("not collected".into());
+        let ((_, inner), outer) = collect(|| {
+            push("outer".into());
+            collect(|| push("inner".into()))
+        });
+        assert_eq!(outer, ["outer"]);
+        assert_eq!(inner, ["inner"]);
+    }
+}
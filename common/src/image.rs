@@ -0,0 +1,542 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+
+use crate::{
+    input,
+    solution::Solution,
+    twod::{Grid, Point},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(80, 160, 60);
+    pub const BLUE: Rgb = Rgb(38, 110, 210);
+    pub const YELLOW: Rgb = Rgb(230, 190, 40);
+    pub const GRAY: Rgb = Rgb(128, 128, 128);
+
+    /// Distinct colours for telling things apart, repeating after ten
+    pub fn categorical(i: usize) -> Self {
+        const PALETTE: [Rgb; 10] = [
+            Rgb(31, 119, 180),
+            Rgb(255, 127, 14),
+            Rgb(44, 160, 44),
+            Rgb(214, 39, 40),
+            Rgb(148, 103, 189),
+            Rgb(140, 86, 75),
+            Rgb(227, 119, 194),
+            Rgb(127, 127, 127),
+            Rgb(188, 189, 34),
+            Rgb(23, 190, 207),
+        ];
+        PALETTE[i % PALETTE.len()]
+    }
+
+    pub fn gray(level: u8) -> Self {
+        Rgb(level, level, level)
+    }
+
+    /// Colour scale from dark blue over green to yellow for `t` in `0.0..=1.0`
+    pub fn heat(t: f64) -> Self {
+        const STOPS: [Rgb; 3] = [Rgb(30, 30, 110), Rgb(40, 160, 120), Rgb(250, 230, 50)];
+        let t = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f64;
+        let i = std::cmp::min(t as usize, STOPS.len() - 2);
+        STOPS[i].mix(STOPS[i + 1], t - i as f64)
+    }
+
+    /// Linear interpolation towards `other`
+    pub fn mix(self, other: Rgb, t: f64) -> Self {
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb(
+            channel(self.0, other.0),
+            channel(self.1, other.1),
+            channel(self.2, other.2),
+        )
+    }
+
+    fn hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// A raster image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        mut f: impl FnMut(usize, usize) -> Rgb,
+    ) -> Self {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// One pixel per cell, coloured by `color`
+    pub fn from_grid<T>(grid: &Grid<T>, mut color: impl FnMut(&T) -> Rgb) -> Self {
+        Self::from_fn(grid.width(), grid.height(), |x, y| {
+            color(&grid[Point::new(x as i32, y as i32)])
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Sets a pixel, ignoring positions outside of the image
+    pub fn set(&mut self, x: i64, y: i64, color: Rgb) {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            self.pixels[y as usize * self.width + x as usize] = color;
+        }
+    }
+
+    /// Binary PPM (P6)
+    pub fn write_ppm(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]).collect();
+        out.write_all(&bytes)
+    }
+
+    /// PNG with the image data in uncompressed (stored) zlib blocks
+    pub fn write_png(&self, mut out: impl Write) -> io::Result<()> {
+        out.write_all(b"\x89PNG\r\n\x1a\n")?;
+        let mut header = vec![];
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // 8 bit RGB, default compression, filter and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+        write_png_chunk(&mut out, b"IHDR", &header)?;
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0); // no filter
+            raw.extend(row.iter().flat_map(|p| [p.0, p.1, p.2]));
+        }
+        write_png_chunk(&mut out, b"IDAT", &zlib_stored(&raw))?;
+        write_png_chunk(&mut out, b"IEND", &[])
+    }
+}
+
+fn write_png_chunk(out: &mut impl Write, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+    let crc = crc32(kind.iter().chain(data));
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// A zlib stream of stored deflate blocks, which decoders accept like any other
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 65535;
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+/// An outline or filled area, in the coordinates of the drawing
+#[derive(Debug, Clone, PartialEq)]
+struct Shape {
+    points: Vec<(f64, f64)>,
+    closed: bool,
+    stroke: Option<Rgb>,
+    fill: Option<Rgb>,
+}
+
+/// A picture made of an optional raster background, one unit per pixel, and vector
+/// shapes on top
+///
+/// Drawings are written as SVG, or rasterized for PPM and PNG.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drawing {
+    width: f64,
+    height: f64,
+    background: Option<Image>,
+    shapes: Vec<Shape>,
+}
+
+impl From<Image> for Drawing {
+    fn from(image: Image) -> Self {
+        Self {
+            width: image.width as f64,
+            height: image.height as f64,
+            background: Some(image),
+            shapes: vec![],
+        }
+    }
+}
+
+impl Drawing {
+    /// An empty drawing spanning `0.0..width` and `0.0..height`
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            background: None,
+            shapes: vec![],
+        }
+    }
+
+    pub fn polyline(
+        &mut self,
+        points: impl IntoIterator<Item = (f64, f64)>,
+        stroke: Rgb,
+    ) {
+        self.shapes.push(Shape {
+            points: points.into_iter().collect(),
+            closed: false,
+            stroke: Some(stroke),
+            fill: None,
+        });
+    }
+
+    pub fn polygon(
+        &mut self,
+        points: impl IntoIterator<Item = (f64, f64)>,
+        stroke: Option<Rgb>,
+        fill: Option<Rgb>,
+    ) {
+        self.shapes.push(Shape {
+            points: points.into_iter().collect(),
+            closed: true,
+            stroke,
+            fill,
+        });
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: Rgb) {
+        let corners = [
+            (x, y),
+            (x + width, y),
+            (x + width, y + height),
+            (x, y + height),
+        ];
+        self.polygon(corners, None, Some(fill));
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (w, h) = (self.width, self.height);
+        let scale = output_scale(w, h);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             viewBox=\"0 0 {w} {h}\">\n",
+            (w * scale).ceil(),
+            (h * scale).ceil()
+        );
+        if let Some(image) = &self.background {
+            svg += "<g shape-rendering=\"crispEdges\">\n";
+            for y in 0..image.height {
+                // one rectangle per run of equal pixels
+                let mut x = 0;
+                while x < image.width {
+                    let color = image.get(x, y);
+                    let run = (x..image.width)
+                        .take_while(|&x2| image.get(x2, y) == color)
+                        .count();
+                    let _ = writeln!(
+                        svg,
+                        "<rect x=\"{x}\" y=\"{y}\" width=\"{run}\" height=\"1\" fill=\"{}\"/>",
+                        color.hex()
+                    );
+                    x += run;
+                }
+            }
+            svg += "</g>\n";
+        }
+        for shape in &self.shapes {
+            let points: Vec<_> = shape
+                .points
+                .iter()
+                .map(|(x, y)| format!("{x},{y}"))
+                .collect();
+            let _ = writeln!(
+                svg,
+                "<{} points=\"{}\" fill=\"{}\" stroke=\"{}\" vector-effect=\"non-scaling-stroke\"/>",
+                if shape.closed { "polygon" } else { "polyline" },
+                points.join(" "),
+                shape.fill.map_or("none".to_owned(), Rgb::hex),
+                shape.stroke.map_or("none".to_owned(), Rgb::hex),
+            );
+        }
+        svg += "</svg>\n";
+        svg
+    }
+
+    /// Draws the picture with `scale` pixels per unit
+    pub fn rasterize(&self, scale: f64) -> Image {
+        let width = (self.width * scale).ceil() as usize;
+        let height = (self.height * scale).ceil() as usize;
+        let mut image = match &self.background {
+            Some(bg) => Image::from_fn(width, height, |x, y| {
+                let bx = std::cmp::min((x as f64 / scale) as usize, bg.width - 1);
+                let by = std::cmp::min((y as f64 / scale) as usize, bg.height - 1);
+                bg.get(bx, by)
+            }),
+            None => Image::new(width, height, Rgb::WHITE),
+        };
+        for shape in &self.shapes {
+            let pixels: Vec<_> = shape
+                .points
+                .iter()
+                .map(|&(x, y)| (x * scale, y * scale))
+                .collect();
+            if let Some(fill) = shape.fill {
+                fill_polygon(&mut image, &pixels, fill);
+            }
+            if let Some(stroke) = shape.stroke {
+                let mut edges: Vec<_> = pixels.windows(2).map(|w| (w[0], w[1])).collect();
+                if let (true, Some(&first), Some(&last)) =
+                    (shape.closed, pixels.first(), pixels.last())
+                {
+                    edges.push((last, first));
+                }
+                for (a, b) in edges {
+                    draw_line(&mut image, a, b, stroke);
+                }
+            }
+        }
+        image
+    }
+}
+
+/// Sets the pixels along a line of any slope
+fn draw_line(image: &mut Image, (x1, y1): (f64, f64), (x2, y2): (f64, f64), color: Rgb) {
+    let steps = (x2 - x1).abs().max((y2 - y1).abs()).ceil().max(1.0);
+    for i in 0..=steps as i64 {
+        let t = i as f64 / steps;
+        let (x, y) = (x1 + t * (x2 - x1), y1 + t * (y2 - y1));
+        image.set(x.floor() as i64, y.floor() as i64, color);
+    }
+}
+
+/// Fills the pixels whose centres lie within the polygon, by the even-odd rule
+fn fill_polygon(image: &mut Image, points: &[(f64, f64)], color: Rgb) {
+    let Some(y_min) = points.iter().map(|p| p.1).reduce(f64::min) else {
+        return;
+    };
+    let y_max = points.iter().map(|p| p.1).fold(y_min, f64::max);
+    let first_row = y_min.floor().max(0.0) as usize;
+    let last_row = (y_max.ceil().max(0.0) as usize).min(image.height);
+    for y in first_row..last_row {
+        let cy = y as f64 + 0.5;
+        let mut crossings: Vec<f64> = points
+            .iter()
+            .zip(points.iter().cycle().skip(1))
+            .filter(|(a, b)| (a.1 <= cy) != (b.1 <= cy))
+            .map(|(a, b)| a.0 + (cy - a.1) / (b.1 - a.1) * (b.0 - a.0))
+            .collect();
+        crossings.sort_by(f64::total_cmp);
+        for span in crossings.chunks_exact(2) {
+            let from = (span[0] - 0.5).ceil().max(0.0) as i64;
+            let to = (span[1] - 0.5).floor() as i64;
+            for x in from..=to {
+                image.set(x, y as i64, color);
+            }
+        }
+    }
+}
+
+/// Pixels per unit for output files: small pictures are enlarged by whole numbers so
+/// that cells stay sharp, huge ones are shrunk
+fn output_scale(width: f64, height: f64) -> f64 {
+    const TARGET: f64 = 600.0;
+    let size = width.max(height).max(1.0);
+    match size {
+        s if s <= TARGET => (TARGET / s).floor(),
+        s if s <= 4.0 * TARGET => 1.0,
+        s => 4.0 * TARGET / s,
+    }
+}
+
+/// Writes the drawing as PNG, PPM or SVG, depending on the extension of `path`
+pub fn save(path: &Path, drawing: &Drawing) -> anyhow::Result<()> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let raster = || drawing.rasterize(output_scale(drawing.width, drawing.height));
+    let mut bytes = vec![];
+    match ext.as_str() {
+        "svg" => bytes = drawing.to_svg().into_bytes(),
+        "png" => raster().write_png(&mut bytes)?,
+        "ppm" => raster().write_ppm(&mut bytes)?,
+        _ => bail!("Unsupported image format '{ext}', expected png, ppm or svg"),
+    }
+    fs::write(path, bytes).with_context(|| format!("Error writing {}", path.display()))
+}
+
+/// The file given by `--image <path>` or `--image=<path>` on the command line
+pub fn path_from_args() -> anyhow::Result<Option<PathBuf>> {
+    Ok(input::flag_from_args("--image")?.map(PathBuf::from))
+}
+
+/// Saves the picture of `S` if `--image` is given on the command line
+pub fn save_from_args<S: Solution>(input: &S::Input) -> anyhow::Result<()> {
+    match path_from_args()? {
+        Some(path) => save(&path, &S::draw(input)?),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn zlib_stored_blocks_decompress() {
+        use flate2::read::ZlibDecoder;
+        use std::io::Read;
+        for len in [0, 10, 65535, 65536, 200_000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 7 % 251) as u8).collect();
+            let mut decoded = vec![];
+            ZlibDecoder::new(zlib_stored(&data).as_slice())
+                .read_to_end(&mut decoded)
+                .unwrap();
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn png_and_ppm_layout() {
+        let image = Image::from_fn(3, 2, |x, y| Rgb(x as u8, y as u8, 9));
+        let mut png = vec![];
+        image.write_png(&mut png).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x03\0\0\0\x02"));
+        assert!(png.ends_with(b"IEND\xae\x42\x60\x82"));
+        let mut ppm = vec![];
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n3 2\n255\n\0\0\x09\x01\0\x09"));
+        assert_eq!(ppm.len(), 11 + 18);
+    }
+
+    #[test]
+    fn grid_to_image() {
+        let grid = Grid::<bool>::parse("#.\n.#").unwrap();
+        let image =
+            Image::from_grid(&grid, |&on| if on { Rgb::WHITE } else { Rgb::BLACK });
+        assert_eq!(image.get(0, 0), Rgb::WHITE);
+        assert_eq!(image.get(1, 0), Rgb::BLACK);
+        assert_eq!(image.get(1, 1), Rgb::WHITE);
+    }
+
+    #[test]
+    fn rasterize_shapes() {
+        let mut drawing = Drawing::new(10.0, 10.0);
+        // a diamond around (5, 5)
+        drawing.polygon(
+            [(5.0, 1.0), (9.0, 5.0), (5.0, 9.0), (1.0, 5.0)],
+            None,
+            Some(Rgb::RED),
+        );
+        drawing.polyline([(0.0, 0.0), (9.0, 0.0)], Rgb::BLUE);
+        let image = drawing.rasterize(1.0);
+        assert_eq!((image.width(), image.height()), (10, 10));
+        assert_eq!(image.get(5, 5), Rgb::RED);
+        assert_eq!(image.get(2, 5), Rgb::RED);
+        assert_eq!(image.get(1, 1), Rgb::WHITE);
+        assert!((0..10).all(|x| image.get(x, 0) == Rgb::BLUE));
+        let svg = drawing.to_svg();
+        assert!(svg.contains("<polygon points=\"5,1 9,5 5,9 1,5\" fill=\"#dc322f\""));
+        assert!(
+            svg.contains("<polyline points=\"0,0 9,0\" fill=\"none\" stroke=\"#266ed2\"")
+        );
+    }
+
+    #[test]
+    fn background_runs_in_svg() {
+        let image =
+            Image::from_fn(4, 1, |x, _| if x < 3 { Rgb::BLACK } else { Rgb::WHITE });
+        let svg = Drawing::from(image).to_svg();
+        assert!(svg.contains(
+            "<rect x=\"0\" y=\"0\" width=\"3\" height=\"1\" fill=\"#000000\"/>"
+        ));
+        assert!(svg.contains(
+            "<rect x=\"3\" y=\"0\" width=\"1\" height=\"1\" fill=\"#ffffff\"/>"
+        ));
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    env, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context};
+use flate2::read::GzDecoder;
+
+pub const EXAMPLE_FILE_NAME: &str = "test_data.txt";
+pub const REAL_FILE_NAME: &str = "input.txt";
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Which input to load, as given on the command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSpec {
+    /// `-`
+    Stdin,
+    /// `example`, the day's test_data.txt
+    Example,
+    /// `real`, the day's input.txt
+    Real,
+    Path(PathBuf),
+}
+
+impl FromStr for InputSpec {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "-" => InputSpec::Stdin,
+            "example" => InputSpec::Example,
+            "real" => InputSpec::Real,
+            path => InputSpec::Path(path.into()),
+        })
+    }
+}
+
+impl fmt::Display for InputSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSpec::Stdin => write!(f, "-"),
+            InputSpec::Example => write!(f, "example"),
+            InputSpec::Real => write!(f, "real"),
+            InputSpec::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The inputs of one day, located relative to the day's crate directory
+#[derive(Debug, Clone)]
+pub struct DayInputs {
+    crate_dir: PathBuf,
+}
+
+impl DayInputs {
+    /// `crate_dir` is usually `env!("CARGO_MANIFEST_DIR")`, so that inputs are found no
+    /// matter where the binary is started from
+    pub fn new(crate_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            crate_dir: crate_dir.into(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.crate_dir
+    }
+
+    /// Finds the file with the given name in the crate directory, or its gzipped variant
+    pub fn file(&self, name: &str) -> Option<PathBuf> {
+        [name.to_owned(), format!("{name}.gz")]
+            .into_iter()
+            .map(|n| self.crate_dir.join(n))
+            .find(|p| p.is_file())
+    }
+
+    /// Resolves the file to read, `None` meaning stdin
+    ///
+    /// Relative paths are tried as given, then relative to the workspace root and then
+    /// relative to the crate directory, so `05/test_data.txt` works from both the
+    /// workspace root and the crate directory.
+    pub fn resolve(&self, spec: &InputSpec) -> anyhow::Result<Option<PathBuf>> {
+        let name = match spec {
+            InputSpec::Stdin => return Ok(None),
+            InputSpec::Example => EXAMPLE_FILE_NAME,
+            InputSpec::Real => REAL_FILE_NAME,
+            InputSpec::Path(path) => return self.resolve_path(path).map(Some),
+        };
+        self.file(name).map(Some).ok_or_else(|| {
+            anyhow!(
+                "There is no {spec} input, expected {}",
+                self.crate_dir.join(name).display()
+            )
+        })
+    }
+
+    fn resolve_path(&self, path: &Path) -> anyhow::Result<PathBuf> {
+        let mut candidates = vec![path.to_owned()];
+        if path.is_relative() {
+            if let Some(workspace_root) = self.crate_dir.parent() {
+                candidates.push(workspace_root.join(path));
+            }
+            candidates.push(self.crate_dir.join(path));
+        }
+        candidates
+            .iter()
+            .find(|p| p.is_file())
+            .cloned()
+            .ok_or_else(|| {
+                let tried: Vec<_> =
+                    candidates.iter().map(|p| p.display().to_string()).collect();
+                anyhow!(
+                    "Input file {} not found (tried {})",
+                    path.display(),
+                    tried.join(", ")
+                )
+            })
+    }
+
+    pub fn read(&self, spec: &InputSpec) -> anyhow::Result<String> {
+        match self.resolve(spec)? {
+            Some(path) => read_file(&path),
+            None => read_from(io::stdin().lock()).context("Error reading from stdin"),
+        }
+    }
+
+    /// Reads the input given by the first command line argument that is not a `--flag`
+    /// or its value, the example by default
+    pub fn read_from_args(&self) -> anyhow::Result<String> {
+        self.read(&spec_from_args()?)
+    }
+}
+
+/// The input given by the first command line argument that is not a `--flag` or its
+/// value, the example by default
+pub fn spec_from_args() -> anyhow::Result<InputSpec> {
+    Ok(match positional_arg(env::args().skip(1)) {
+        Some(arg) => arg.parse()?,
+        None => InputSpec::Example,
+    })
+}
+
+/// Flags that take their value as the next argument
+const FLAGS_WITH_VALUE: [&str; 5] =
+    ["--image", "--threads", "--format", "--param", "--config"];
+
+/// The value given with `flag`, e.g., `--format`, as `--format <value>` or
+/// `--format=<value>` on the command line
+pub fn flag_from_args(flag: &str) -> anyhow::Result<Option<String>> {
+    Ok(flag_values(env::args().skip(1), flag)?.into_iter().next())
+}
+
+/// The values of each `flag` on the command line, for flags that can be repeated
+pub fn flag_values_from_args(flag: &str) -> anyhow::Result<Vec<String>> {
+    flag_values(env::args().skip(1), flag)
+}
+
+fn flag_values(
+    args: impl IntoIterator<Item = String>,
+    flag: &str,
+) -> anyhow::Result<Vec<String>> {
+    let mut values = vec![];
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix(flag).and_then(|v| v.strip_prefix('=')) {
+            values.push(value.to_owned());
+        } else if arg == flag {
+            values.push(args.next().ok_or(anyhow!("Missing value for {flag}"))?);
+        }
+    }
+    Ok(values)
+}
+
+fn positional_arg(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+            args.next();
+        } else if arg == "-" || !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+/// Reads a text file, decompressing it if it is gzipped
+pub fn read_file(path: &Path) -> anyhow::Result<String> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Error opening input file {}", path.display()))?;
+    read_from(file)
+        .with_context(|| format!("Error reading input file {}", path.display()))
+}
+
+/// Reads all text from `reader`, decompressing it if it is gzipped
+pub fn read_from(mut reader: impl Read) -> anyhow::Result<String> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut decompressed = vec![];
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decompressed)
+            .context("Invalid gzip data")?;
+        bytes = decompressed;
+    }
+    String::from_utf8(bytes).context("Input is not valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            env::temp_dir().join(format!("common-input-{name}-{}", std::process::id()));
+        fs::create_dir_all(dir.join("05")).unwrap();
+        dir
+    }
+
+    fn gzip(text: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(vec![], Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn parse_spec() {
+        assert_eq!("-".parse(), Ok(InputSpec::Stdin));
+        assert_eq!("example".parse(), Ok(InputSpec::Example));
+        assert_eq!("real".parse(), Ok(InputSpec::Real));
+        assert_eq!("a/b.txt".parse(), Ok(InputSpec::Path("a/b.txt".into())));
+    }
+
+    #[test]
+    fn positional_arg_skips_flags() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            positional_arg(args(&["--viz", "real"])),
+            Some("real".into())
+        );
+        assert_eq!(
+            positional_arg(args(&["--image", "a.png", "-"])),
+            Some("-".into())
+        );
+        assert_eq!(positional_arg(args(&["--image=a.png"])), None);
+        assert_eq!(positional_arg(args(&["-v", "-q"])), None);
+        assert_eq!(
+            positional_arg(args(&["--threads", "4", "real"])),
+            Some("real".into())
+        );
+        assert_eq!(
+            positional_arg(args(&["--param", "row=10", "a.txt"])),
+            Some("a.txt".into())
+        );
+    }
+
+    #[test]
+    fn values_of_flags() {
+        let args = |a: &[&str]| a.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let values = |a: &[&str]| flag_values(args(a), "--format");
+        assert_eq!(values(&["real", "--format", "json"]).unwrap(), ["json"]);
+        assert_eq!(values(&["--format=json"]).unwrap(), ["json"]);
+        assert!(values(&["--formats", "json"]).unwrap().is_empty());
+        assert!(values(&["--format"]).is_err());
+        let values = |a: &[&str]| flag_values(args(a), "--param");
+        assert_eq!(
+            values(&["--param", "a=1", "real", "--param=b=2"]).unwrap(),
+            ["a=1", "b=2"]
+        );
+    }
+
+    #[test]
+    fn read_plain_and_gzipped() {
+        assert_eq!(read_from("abc\n".as_bytes()).unwrap(), "abc\n");
+        assert_eq!(read_from(gzip("abc\n").as_slice()).unwrap(), "abc\n");
+        assert!(read_from([0x1f, 0x8b, 0x00].as_slice()).is_err());
+        assert!(read_from([0xff, 0xfe].as_slice()).is_err());
+    }
+
+    #[test]
+    fn named_inputs() {
+        let root = temp_dir("named");
+        fs::write(root.join("05").join(EXAMPLE_FILE_NAME), "example").unwrap();
+        fs::write(root.join("05").join("input.txt.gz"), gzip("real")).unwrap();
+        let inputs = DayInputs::new(root.join("05"));
+        assert_eq!(inputs.read(&InputSpec::Example).unwrap(), "example");
+        assert_eq!(inputs.read(&InputSpec::Real).unwrap(), "real");
+        fs::remove_dir_all(root).unwrap();
+        assert!(inputs.read(&InputSpec::Example).is_err());
+    }
+
+    #[test]
+    fn paths_relative_to_workspace_root_or_crate_dir() {
+        let root = temp_dir("paths");
+        fs::write(root.join("05").join("other.txt"), "other").unwrap();
+        let inputs = DayInputs::new(root.join("05"));
+        let expected = Some(root.join("05").join("other.txt"));
+        assert_eq!(
+            inputs.resolve(&"05/other.txt".parse().unwrap()).unwrap(),
+            expected
+        );
+        let expected = Some(root.join("05").join("other.txt"));
+        assert_eq!(
+            inputs.resolve(&"other.txt".parse().unwrap()).unwrap(),
+            expected
+        );
+        let err = inputs.resolve(&"missing.txt".parse().unwrap()).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Input file missing.txt not found"));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
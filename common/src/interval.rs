@@ -0,0 +1,333 @@
+use std::{collections::BTreeMap, fmt};
+
+/// Integer-like values that intervals can be measured and closed over
+pub trait Discrete: Copy + Ord {
+    /// The next value
+    fn succ(self) -> Self;
+    /// Number of values in `start..end`, where `start <= end`
+    fn distance(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_discrete {
+    ($($t:ty),*) => {
+        $(impl Discrete for $t {
+            fn succ(self) -> Self {
+                self + 1
+            }
+
+            fn distance(start: Self, end: Self) -> u64 {
+                (end as i128 - start as i128) as u64
+            }
+        })*
+    };
+}
+
+impl_discrete!(i16, i32, i64, u8, u16, u32, u64, usize);
+
+/// A half-open interval `start..end`; closed intervals are converted on construction
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+}
+
+impl<T: Copy + Ord> Interval<T> {
+    /// `start..end`, empty if `end <= start`
+    pub fn new(start: T, end: T) -> Self {
+        Self {
+            start,
+            end: std::cmp::max(start, end),
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    /// The first value after the interval
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.start <= x && x < self.end
+    }
+
+    /// Whether all of `other` lies within this interval; true for an empty `other`
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    /// Whether the intervals have a value in common
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The common part, `None` if there is none
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let start = std::cmp::max(self.start, other.start);
+        let end = std::cmp::min(self.end, other.end);
+        (start < end).then_some(Self { start, end })
+    }
+}
+
+impl<T: Discrete> Interval<T> {
+    /// `first..=last`
+    pub fn closed(first: T, last: T) -> Self {
+        Self::new(first, last.succ())
+    }
+
+    pub fn len(&self) -> u64 {
+        T::distance(self.start, self.end)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}..{:?}", self.start, self.end)
+    }
+}
+
+/// A set of values stored as disjoint intervals
+///
+/// Point queries take O(log n) for n intervals and the total length is kept up to date
+/// on every change.
+#[derive(Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    /// Start to end of disjoint, non-adjacent, non-empty intervals
+    intervals: BTreeMap<T, T>,
+    len: u64,
+}
+
+impl<T: Discrete> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self {
+            intervals: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// The total number of values in the set
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        self.intervals
+            .range(..=x)
+            .next_back()
+            .is_some_and(|(_, &end)| x < end)
+    }
+
+    /// The disjoint intervals in ascending order; adjacent intervals are merged
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Interval<T>> + '_ {
+        self.intervals
+            .iter()
+            .map(|(&start, &end)| Interval { start, end })
+    }
+
+    /// The lowest interval
+    pub fn first(&self) -> Option<Interval<T>> {
+        self.iter().next()
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let Interval { mut start, mut end } = interval;
+        if let Some((&s, &e)) = self.intervals.range(..=start).next_back() {
+            if e >= start {
+                start = s;
+                end = std::cmp::max(end, e);
+            }
+        }
+        while let Some((&s, &e)) = self.intervals.range(start..=end).next() {
+            self.intervals.remove(&s);
+            self.len -= T::distance(s, e);
+            end = std::cmp::max(end, e);
+        }
+        self.intervals.insert(start, end);
+        self.len += T::distance(start, end);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let first = self
+            .intervals
+            .range(..interval.start)
+            .next_back()
+            .filter(|(_, &e)| e > interval.start)
+            .map(|(&s, &e)| (s, e));
+        let mut next = first.or_else(|| self.first_in(interval));
+        while let Some((s, e)) = next {
+            self.intervals.remove(&s);
+            self.len -= T::distance(s, e);
+            if s < interval.start {
+                self.intervals.insert(s, interval.start);
+                self.len += T::distance(s, interval.start);
+            }
+            if e > interval.end {
+                self.intervals.insert(interval.end, e);
+                self.len += T::distance(interval.end, e);
+            }
+            next = self.first_in(interval);
+        }
+    }
+
+    /// The first interval starting within `interval`
+    fn first_in(&self, interval: Interval<T>) -> Option<(T, T)> {
+        self.intervals
+            .range(interval.start..interval.end)
+            .next()
+            .map(|(&s, &e)| (s, e))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        result.extend(other.iter());
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut result = Self::new();
+        let (mut lhs, mut rhs) = (self.iter().peekable(), other.iter().peekable());
+        while let (Some(a), Some(b)) = (lhs.peek(), rhs.peek()) {
+            if let Some(common) = a.intersection(b) {
+                result.insert(common);
+            }
+            if a.end < b.end {
+                lhs.next();
+            } else {
+                rhs.next();
+            }
+        }
+        result
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        other.iter().for_each(|iv| result.remove(iv));
+        result
+    }
+
+    /// The values within `bounds` that are not in the set
+    pub fn complement(&self, bounds: Interval<T>) -> Self {
+        let mut result = Self::new();
+        let mut gap_start = bounds.start;
+        let first = self.intervals.range(..bounds.start).next_back();
+        let within = self.intervals.range(bounds.start..bounds.end);
+        for (&start, &end) in first.into_iter().chain(within) {
+            result.insert(Interval::new(gap_start, std::cmp::min(start, bounds.end)));
+            gap_start = std::cmp::max(gap_start, end);
+        }
+        result.insert(Interval::new(gap_start, bounds.end));
+        result
+    }
+}
+
+impl<T: Discrete> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Discrete> Extend<Interval<T>> for IntervalSet<T> {
+    fn extend<I: IntoIterator<Item = Interval<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|iv| self.insert(iv));
+    }
+}
+
+impl<T: Discrete> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut result = Self::new();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T: Discrete + fmt::Debug> fmt::Debug for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i32, i32)]) -> IntervalSet<i32> {
+        intervals
+            .iter()
+            .map(|&(first, last)| Interval::closed(first, last))
+            .collect()
+    }
+
+    fn closed(set: &IntervalSet<i32>) -> Vec<(i32, i32)> {
+        set.iter().map(|iv| (iv.start(), iv.end() - 1)).collect()
+    }
+
+    #[test]
+    fn interval_relations() {
+        let a = Interval::closed(2, 6);
+        assert_eq!((a.start(), a.end(), a.len()), (2, 7, 5));
+        assert!(a.contains(6) && !a.contains(7));
+        assert!(a.contains_interval(&Interval::closed(3, 6)));
+        assert!(!a.contains_interval(&Interval::closed(3, 7)));
+        assert!(a.overlaps(&Interval::closed(6, 9)));
+        assert!(!a.overlaps(&Interval::new(7, 9)));
+        assert_eq!(
+            a.intersection(&Interval::new(0, 4)),
+            Some(Interval::new(2, 4))
+        );
+        assert!(Interval::new(5, 3).is_empty());
+    }
+
+    #[test]
+    fn insert_merges_overlapping_and_adjacent() {
+        let mut s = set(&[(0, 2), (10, 12), (5, 6)]);
+        assert_eq!(closed(&s), vec![(0, 2), (5, 6), (10, 12)]);
+        assert_eq!(s.len(), 8);
+        s.insert(Interval::closed(3, 4));
+        assert_eq!(closed(&s), vec![(0, 6), (10, 12)]);
+        s.insert(Interval::closed(-5, 20));
+        assert_eq!(closed(&s), vec![(-5, 20)]);
+        assert_eq!(s.len(), 26);
+        assert!(s.contains(-5) && s.contains(20) && !s.contains(21));
+    }
+
+    #[test]
+    fn remove_splits_intervals() {
+        let mut s = set(&[(0, 10), (20, 30)]);
+        s.remove(Interval::closed(5, 24));
+        assert_eq!(closed(&s), vec![(0, 4), (25, 30)]);
+        assert_eq!(s.len(), 11);
+        s.remove(Interval::closed(-3, 0));
+        assert_eq!(closed(&s), vec![(1, 4), (25, 30)]);
+        assert!(!s.contains(0) && s.contains(1));
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = set(&[(0, 5), (10, 15)]);
+        let b = set(&[(3, 12)]);
+        assert_eq!(closed(&a.union(&b)), vec![(0, 15)]);
+        assert_eq!(closed(&a.intersection(&b)), vec![(3, 5), (10, 12)]);
+        assert_eq!(closed(&a.difference(&b)), vec![(0, 2), (13, 15)]);
+        assert_eq!(
+            closed(&a.complement(Interval::closed(-2, 20))),
+            vec![(-2, -1), (6, 9), (16, 20)]
+        );
+        assert!(a.complement(Interval::closed(1, 4)).is_empty());
+    }
+}
@@ -1 +1,20 @@
+pub mod bitset;
+pub mod cancel;
+pub mod cycle;
+pub mod diagnostics;
+pub mod image;
+pub mod input;
+pub mod interval;
+pub mod logging;
+pub mod ocr;
+pub mod params;
+pub mod parse;
+pub mod pool;
+pub mod progress;
+pub mod random;
+pub mod region;
+pub mod search;
+pub mod solution;
+pub mod threed;
 pub mod twod;
+pub mod viz;
@@ -0,0 +1,215 @@
+use std::{env, str::FromStr, sync::RwLock};
+
+use anyhow::{anyhow, Context};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::diagnostics;
+
+/// Which records are printed: a level per target, e.g., `day16`, and one for the rest
+///
+/// A target's level also applies to its modules, e.g., `day16::pathfinder`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    default: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new(LevelFilter::Info)
+    }
+}
+
+impl Filter {
+    pub const fn new(default: LevelFilter) -> Self {
+        Self {
+            default,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Replaces the level of the targets that have none of their own
+    pub fn with_default(self, default: LevelFilter) -> Self {
+        Self { default, ..self }
+    }
+
+    /// The level of `target`, that of its longest listed prefix
+    pub fn level(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+/// `RUST_LOG` style, e.g., `warn`, `day16=debug` or `info,day11=trace`
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let parse_level = |level: &str| {
+                level
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid log level '{level}' in '{s}'"))
+            };
+            match directive.split_once('=') {
+                Some((target, level)) => filter
+                    .targets
+                    .push((target.to_owned(), parse_level(level)?)),
+                None => filter.default = parse_level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// The level for the number of `-v` minus the number of `-q`, info by default
+pub fn verbosity_level(verbosity: i8) -> LevelFilter {
+    match verbosity {
+        ..=-2 => LevelFilter::Error,
+        -1 => LevelFilter::Warn,
+        0 => LevelFilter::Info,
+        1 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Counts `-v` (`-vv` counting twice) and `-q` in `args`, `None` if there are none
+pub fn verbosity(args: impl IntoIterator<Item = String>) -> Option<i8> {
+    args.into_iter()
+        .filter_map(|arg| match arg.as_str() {
+            "-q" => Some(-1),
+            "-v" => Some(1),
+            "-vv" => Some(2),
+            _ => None,
+        })
+        .reduce(|a, b| a + b)
+}
+
+/// Prints records to stderr and hands info records and above to [`diagnostics`]
+struct Logger {
+    filter: RwLock<Filter>,
+}
+
+static LOGGER: Logger = Logger {
+    filter: RwLock::new(Filter::new(LevelFilter::Info)),
+};
+
+impl Logger {
+    fn prints(&self, metadata: &Metadata) -> bool {
+        let filter = self.filter.read().unwrap_or_else(|e| e.into_inner());
+        metadata.level() <= filter.level(metadata.target())
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info || self.prints(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let message = record.args().to_string();
+        if self.prints(record.metadata()) {
+            eprintln!("[{} {}] {message}", record.level(), record.target());
+        }
+        if record.level() <= Level::Info {
+            diagnostics::push(message);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger with the given filter, or only changes the filter if it is
+/// installed already
+///
+/// Info records are always passed to the logger, so that [`diagnostics::collect`] sees
+/// them even if they are not printed.
+pub fn init(filter: Filter) {
+    let max_level = filter.max_level().max(LevelFilter::Info);
+    *LOGGER.filter.write().unwrap_or_else(|e| e.into_inner()) = filter;
+    // fails if another logger is installed, which then gets the records instead
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(max_level);
+}
+
+/// The filter given by `RUST_LOG`, if set, with its default level replaced by that of
+/// `verbosity`, if given
+pub fn filter(verbosity: Option<i8>) -> anyhow::Result<Filter> {
+    let filter = match env::var("RUST_LOG") {
+        Ok(spec) => spec.parse().context("Invalid RUST_LOG")?,
+        Err(_) => Filter::default(),
+    };
+    Ok(match verbosity {
+        Some(verbosity) => filter.with_default(verbosity_level(verbosity)),
+        None => filter,
+    })
+}
+
+/// Installs the logger with `RUST_LOG` and `-v`, `-vv` or `-q` from the command line
+pub fn init_from_args() -> anyhow::Result<()> {
+    init(filter(verbosity(env::args().skip(1)))?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn levels_per_target() {
+        let filter: Filter = "warn, day16=debug,day16::pathfinder=trace".parse().unwrap();
+        assert_eq!(filter.level("day11"), LevelFilter::Warn);
+        assert_eq!(filter.level("day16"), LevelFilter::Debug);
+        assert_eq!(filter.level("day16::input"), LevelFilter::Debug);
+        assert_eq!(filter.level("day16::pathfinder"), LevelFilter::Trace);
+        assert_eq!(filter.level("day160"), LevelFilter::Warn);
+        assert_eq!(filter.max_level(), LevelFilter::Trace);
+        let filter = filter.with_default(LevelFilter::Off);
+        assert_eq!(filter.level("day11"), LevelFilter::Off);
+        assert_eq!("".parse::<Filter>().unwrap(), Filter::default());
+        assert!("day16=loud".parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn verbosity_from_flags() {
+        assert_eq!(verbosity(args("real --threads 2")), None);
+        assert_eq!(verbosity(args("-v real")), Some(1));
+        assert_eq!(verbosity(args("-vv -v")), Some(3));
+        assert_eq!(verbosity(args("-q")), Some(-1));
+        assert_eq!(verbosity_level(3), LevelFilter::Trace);
+        assert_eq!(verbosity_level(-1), LevelFilter::Warn);
+        assert_eq!(verbosity_level(-5), LevelFilter::Error);
+    }
+
+    #[test]
+    fn info_records_become_diagnostics() {
+        init(Filter::new(LevelFilter::Error));
+        let ((), collected) = diagnostics::collect(|| {
+            log::info!("visited 3 states");
+            log::debug!("visiting state 3");
+        });
+        assert_eq!(collected, ["visited 3 states"]);
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::bail;
+
+use crate::twod::{Grid, Point};
+
+pub const LETTER_WIDTH: usize = 4;
+pub const LETTER_HEIGHT: usize = 6;
+/// Letters are separated by one column
+pub const LETTER_PITCH: usize = LETTER_WIDTH + 1;
+
+/// The letters of the 4x6 font used by the puzzles, row by row
+const FONT: [(char, &str); 18] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Y', "#...#....#.#..#...#...#."),
+    ('Z', "####...#..#..#..#...####"),
+];
+
+/// The glyph starting at column `x`, in the format of `FONT`
+fn glyph_at(screen: &Grid<bool>, x: usize) -> String {
+    (0..LETTER_HEIGHT)
+        .flat_map(|dy| (0..LETTER_WIDTH).map(move |dx| (x + dx, dy)))
+        .map(|(x, y)| {
+            let lit = screen.get(Point::new(x as i32, y as i32));
+            if lit == Some(&true) {
+                '#'
+            } else {
+                '.'
+            }
+        })
+        .collect()
+}
+
+/// Reads the capital letters lit on a screen of 4x6 letters with one column of space
+/// between them
+pub fn read_letters(screen: &Grid<bool>) -> anyhow::Result<String> {
+    if screen.height() != LETTER_HEIGHT {
+        bail!(
+            "Expected a screen {LETTER_HEIGHT} pixels high, got {}",
+            screen.height()
+        );
+    }
+    (0..screen.width())
+        .step_by(LETTER_PITCH)
+        .map(|x| {
+            let glyph = glyph_at(screen, x);
+            match FONT.iter().find(|(_, pattern)| *pattern == glyph) {
+                Some(&(letter, _)) => Ok(letter),
+                None => {
+                    let rows: Vec<_> = glyph
+                        .as_bytes()
+                        .chunks(LETTER_WIDTH)
+                        .map(|row| String::from_utf8_lossy(row).into_owned())
+                        .collect();
+                    bail!("Unknown glyph at column {x}:\n{}", rows.join("\n"))
+                }
+            }
+        })
+        .collect()
+}
+
+/// The letters the font has glyphs for
+pub fn alphabet() -> impl Iterator<Item = char> {
+    FONT.iter().map(|&(letter, _)| letter)
+}
+
+/// Renders `text` like the puzzles' screens do, the inverse of [`read_letters`]
+pub fn render(text: &str) -> anyhow::Result<Grid<bool>> {
+    let patterns = text
+        .chars()
+        .map(|ch| match FONT.iter().find(|&&(letter, _)| letter == ch) {
+            Some((_, pattern)) => Ok(pattern.as_bytes()),
+            None => bail!("No glyph for '{ch}'"),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let width = patterns.len() * LETTER_PITCH;
+    Ok(Grid::from_fn(width, LETTER_HEIGHT, |p| {
+        let (x, y) = (p.x as usize, p.y as usize);
+        let (pattern, dx) = (patterns[x / LETTER_PITCH], x % LETTER_PITCH);
+        dx < LETTER_WIDTH && pattern[y * LETTER_WIDTH + dx] == b'#'
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen(text: &str) -> Grid<bool> {
+        render(text).unwrap()
+    }
+
+    #[test]
+    fn read_all_letters() {
+        let alphabet: String = alphabet().collect();
+        assert_eq!(read_letters(&screen(&alphabet)).unwrap(), alphabet);
+        assert_eq!(read_letters(&screen("PLPAFBCL")).unwrap(), "PLPAFBCL");
+    }
+
+    #[test]
+    fn unknown_glyphs_are_reported() {
+        let mut screen = screen("AB");
+        screen[Point::new(5, 0)] = false;
+        let err = read_letters(&screen).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "Unknown glyph at column 5:\n\
+             .##.\n\
+             #..#\n\
+             ###.\n\
+             #..#\n\
+             #..#\n\
+             ###."
+        );
+        assert!(read_letters(&Grid::from_fn(5, 5, |_| false)).is_err());
+        assert!(render("ABD").is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use std::{collections::BTreeMap, fmt::Debug, path::Path, str::FromStr};
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::input::{self, InputSpec};
+
+/// Which values a day's parameters start from
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// For the example of the puzzle text
+    Example,
+    /// For the puzzle inputs
+    #[default]
+    Real,
+}
+
+impl FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "example" => Ok(Preset::Example),
+            "real" => Ok(Preset::Real),
+            other => Err(anyhow!(
+                "Invalid preset '{other}', expected example or real"
+            )),
+        }
+    }
+}
+
+impl Preset {
+    /// The example preset for files named like the example, e.g., `test_data_2.txt`
+    pub fn for_file(name: &str) -> Self {
+        match name.starts_with("test_data") {
+            true => Preset::Example,
+            false => Preset::Real,
+        }
+    }
+
+    /// The example preset for the example input, see [`Preset::for_file`] for paths
+    pub fn for_input(spec: &InputSpec) -> Self {
+        match spec {
+            InputSpec::Example => Preset::Example,
+            InputSpec::Path(path) => path
+                .file_name()
+                .map_or(Preset::Real, |name| Self::for_file(&name.to_string_lossy())),
+            InputSpec::Stdin | InputSpec::Real => Preset::Real,
+        }
+    }
+}
+
+/// Puzzle constants of a day that can be changed without recompiling, e.g., the number
+/// of rounds
+///
+/// The default values are those of the real preset.
+pub trait Params: Default + Clone + Debug {
+    /// The values for `preset`; the example uses the real values unless overridden
+    fn preset(_preset: Preset) -> Self {
+        Self::default()
+    }
+
+    /// Sets the parameter named `key`, see [`parse_value`] and [`unknown`]
+    fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()>;
+}
+
+impl Params for () {
+    fn set(&mut self, key: &str, _value: &str) -> anyhow::Result<()> {
+        Err(unknown(key, &[]))
+    }
+}
+
+/// Parses the value of the parameter named `key`
+pub fn parse_value<T: FromStr>(key: &str, value: &str) -> anyhow::Result<T> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("Invalid value '{value}' for parameter {key}"))
+}
+
+/// The error for a parameter that does not exist; `names` are those that do
+pub fn unknown(key: &str, names: &[&str]) -> anyhow::Error {
+    match names {
+        [] => anyhow!("Unknown parameter '{key}', there are no parameters"),
+        names => anyhow!("Unknown parameter '{key}', expected {}", names.join(", ")),
+    }
+}
+
+/// Parameters by name, as given on the command line or in a [`Config`], and the preset
+/// they apply to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParamSpec {
+    /// `None` for the real preset, unless the caller chooses one by the input
+    pub preset: Option<Preset>,
+    /// Applied in order, so later values win
+    pub values: Vec<(String, String)>,
+}
+
+impl ParamSpec {
+    /// Adds a value; the key `preset` selects the preset instead
+    pub fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "preset" => self.preset = Some(value.parse()?),
+            _ => self.values.push((key.to_owned(), value.to_owned())),
+        }
+        Ok(())
+    }
+
+    /// Adds a value given as `key=value`
+    pub fn set_arg(&mut self, arg: &str) -> anyhow::Result<()> {
+        let (key, value) = arg
+            .split_once('=')
+            .ok_or(anyhow!("Invalid parameter '{arg}', expected key=value"))?;
+        self.set(key.trim(), value.trim())
+    }
+
+    /// Adds the values of `other` after these, taking its preset if it has one
+    pub fn extend(&mut self, other: ParamSpec) {
+        self.preset = other.preset.or(self.preset);
+        self.values.extend(other.values);
+    }
+
+    /// The preset's parameters with the values applied
+    pub fn resolve<P: Params>(&self) -> anyhow::Result<P> {
+        let mut params = P::preset(self.preset.unwrap_or_default());
+        for (key, value) in &self.values {
+            params.set(key, value)?;
+        }
+        Ok(params)
+    }
+
+    /// The parameters of `day` given on the command line: its table in the file given
+    /// by `--config`, then each `--param key=value`
+    ///
+    /// The preset is chosen by the input unless given.
+    pub fn from_args(day: u8) -> anyhow::Result<Self> {
+        let mut spec = match input::flag_from_args("--config")? {
+            Some(path) => Config::load(Path::new(&path))?.day(day),
+            None => ParamSpec::default(),
+        };
+        for arg in input::flag_values_from_args("--param")? {
+            spec.set_arg(&arg)?;
+        }
+        spec.preset
+            .get_or_insert(Preset::for_input(&input::spec_from_args()?));
+        Ok(spec)
+    }
+}
+
+/// Parameters of several days, from a TOML file with a table per day:
+///
+/// ```toml
+/// [day15]
+/// preset = "example"
+/// row = 11
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    days: BTreeMap<u8, ParamSpec>,
+}
+
+impl FromStr for Config {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tables: BTreeMap<String, toml::value::Table> = toml::from_str(s)?;
+        let mut days = BTreeMap::new();
+        for (name, table) in tables {
+            let day = name
+                .strip_prefix("day")
+                .and_then(|n| n.parse().ok())
+                .ok_or(anyhow!("Invalid table [{name}], expected e.g. [day15]"))?;
+            let mut spec = ParamSpec::default();
+            for (key, value) in table {
+                let value = match value {
+                    toml::Value::String(s) => s,
+                    toml::Value::Integer(_)
+                    | toml::Value::Float(_)
+                    | toml::Value::Boolean(_) => value.to_string(),
+                    other => bail!("Invalid value {other} for {key} in [{name}]"),
+                };
+                spec.set(&key, &value)
+                    .with_context(|| format!("Invalid {key} in [{name}]"))?;
+            }
+            days.insert(day, spec);
+        }
+        Ok(Self { days })
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        input::read_file(path)?
+            .parse()
+            .with_context(|| format!("Error parsing config file {}", path.display()))
+    }
+
+    /// The parameters of `day`, none if the file has no table for it
+    pub fn day(&self, day: u8) -> ParamSpec {
+        self.days.get(&day).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Rows {
+        rows: usize,
+        start: String,
+    }
+
+    impl Default for Rows {
+        fn default() -> Self {
+            Self {
+                rows: 100,
+                start: "AA".into(),
+            }
+        }
+    }
+
+    impl Params for Rows {
+        fn preset(preset: Preset) -> Self {
+            match preset {
+                Preset::Example => Self {
+                    rows: 10,
+                    ..Self::default()
+                },
+                Preset::Real => Self::default(),
+            }
+        }
+
+        fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+            match key {
+                "rows" => self.rows = parse_value(key, value)?,
+                "start" => self.start = parse_value(key, value)?,
+                _ => return Err(unknown(key, &["rows", "start"])),
+            }
+            Ok(())
+        }
+    }
+
+    fn spec(args: &[&str]) -> anyhow::Result<ParamSpec> {
+        let mut spec = ParamSpec::default();
+        for arg in args {
+            spec.set_arg(arg)?;
+        }
+        Ok(spec)
+    }
+
+    #[test]
+    fn values_apply_to_the_preset() {
+        assert_eq!(
+            spec(&[]).unwrap().resolve::<Rows>().unwrap(),
+            Rows::default()
+        );
+        let rows: Rows = spec(&["start=BB", "preset=example"])
+            .unwrap()
+            .resolve()
+            .unwrap();
+        assert_eq!(rows.rows, 10);
+        assert_eq!(rows.start, "BB");
+        let rows: Rows = spec(&["rows = 5", "rows=7"]).unwrap().resolve().unwrap();
+        assert_eq!(rows.rows, 7);
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(spec(&["rows"]).is_err());
+        assert!(spec(&["preset=small"]).is_err());
+        let err = spec(&["rows=many"]).unwrap().resolve::<Rows>().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid value 'many' for parameter rows");
+        let err = spec(&["cols=1"]).unwrap().resolve::<Rows>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Unknown parameter 'cols', expected rows, start"
+        );
+        assert!(spec(&["rows=1"]).unwrap().resolve::<()>().is_err());
+    }
+
+    #[test]
+    fn config_tables_per_day() {
+        let config: Config = "[day15]\npreset = \"example\"\nrows = 12\n\n[day16]\n"
+            .parse()
+            .unwrap();
+        let mut day15 = config.day(15);
+        assert_eq!(day15.preset, Some(Preset::Example));
+        day15.extend(spec(&["start=CC"]).unwrap());
+        let rows: Rows = day15.resolve().unwrap();
+        assert_eq!((rows.rows, rows.start.as_str()), (12, "CC"));
+        assert_eq!(config.day(16), ParamSpec::default());
+        assert_eq!(config.day(1), ParamSpec::default());
+        assert!("[fifteen]\nrows = 1\n".parse::<Config>().is_err());
+        assert!("[day15]\nrows = [1, 2]\n".parse::<Config>().is_err());
+    }
+
+    #[test]
+    fn presets_by_input() {
+        assert_eq!(Preset::for_input(&InputSpec::Example), Preset::Example);
+        assert_eq!(Preset::for_input(&InputSpec::Real), Preset::Real);
+        assert_eq!(
+            Preset::for_input(&"09/test_data_2.txt".parse().unwrap()),
+            Preset::Example
+        );
+        assert_eq!(Preset::for_input(&"big.txt".parse().unwrap()), Preset::Real);
+    }
+}
@@ -0,0 +1,247 @@
+use std::{error::Error, fmt, str::FromStr};
+
+/// A parse error pointing at the offending text of an input
+///
+/// Errors reported by a parser for a part of the input (e.g., a single line) can be
+/// moved to their place in the whole input with [`ParseError::within`]. The error is
+/// rendered like a rustc diagnostic:
+///
+/// ```text
+/// invalid direction 'X'
+///  --> 3:1
+///   |
+/// 3 | X 4
+///   | ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    message: String,
+    /// Byte offset of the offending text in the source
+    offset: usize,
+    /// Byte length of the offending text
+    len: usize,
+    /// 1-based
+    line: usize,
+    /// 1-based, in characters
+    column: usize,
+    line_text: String,
+}
+
+/// Byte offset of `span` in `source`; `span` must be a subslice of `source`
+fn offset_in(source: &str, span: &str) -> usize {
+    let start = source.as_ptr() as usize;
+    let span_start = span.as_ptr() as usize;
+    assert!(
+        span_start >= start && span_start + span.len() <= start + source.len(),
+        "span is not part of the parsed source"
+    );
+    span_start - start
+}
+
+impl ParseError {
+    /// Points at `span`, which must be a subslice of `source`
+    pub fn at(source: &str, span: &str, message: impl Into<String>) -> Self {
+        Self::at_offset(source, offset_in(source, span), span.len(), message)
+    }
+
+    /// Points at the end of `source`, e.g., for missing input
+    pub fn at_end(source: &str, message: impl Into<String>) -> Self {
+        Self::at_offset(source, source.len(), 0, message)
+    }
+
+    pub fn at_offset(
+        source: &str,
+        offset: usize,
+        len: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        let line_start = source[..offset].rfind('\n').map_or(0, |pos| pos + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |pos| offset + pos);
+        let line_text = source[line_start..line_end].trim_end_matches('\r');
+        Self {
+            message: message.into(),
+            offset,
+            len,
+            line: source[..line_start].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            line_text: line_text.to_owned(),
+        }
+    }
+
+    /// Moves an error reported for `part`, a subslice of `source`, to its place in
+    /// `source`
+    pub fn within(self, source: &str, part: &str) -> Self {
+        let offset = offset_in(source, part) + self.offset;
+        Self::at_offset(source, offset, self.len, self.message)
+    }
+
+    /// Converts an error of a nom parser that was run on `source`
+    #[cfg(feature = "nom")]
+    pub fn from_nom(source: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                let rest_of_line = e.input.split('\n').next().unwrap_or_default();
+                let msg = format!("expected {}", e.code.description().to_lowercase());
+                Self::at(source, rest_of_line, msg)
+            }
+            nom::Err::Incomplete(_) => Self::at_end(source, "unexpected end of input"),
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The offending text, cut off at the end of its line
+    pub fn offending_text(&self) -> &str {
+        let start = self
+            .line_text
+            .char_indices()
+            .nth(self.column - 1)
+            .map_or(self.line_text.len(), |(idx, _)| idx);
+        let end = std::cmp::min(start + self.len, self.line_text.len());
+        self.line_text.get(start..end).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        let carets = std::cmp::max(self.offending_text().chars().count(), 1);
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{gutter}--> {}:{}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.line_text)?;
+        write!(
+            f,
+            "{gutter} | {}{}",
+            " ".repeat(self.column - 1),
+            "^".repeat(carets)
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+/// Parses `token`, a subslice of `source`, describing it as `what` on errors
+pub fn parse_token<T>(source: &str, token: &str, what: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    token
+        .parse()
+        .map_err(|e| ParseError::at(source, token, format!("invalid {what}: {e}")))
+}
+
+/// Checks that `token`, a subslice of `source`, is the `expected` keyword
+pub fn expect_token(source: &str, token: &str, expected: &str) -> Result<(), ParseError> {
+    match token == expected {
+        true => Ok(()),
+        false => Err(ParseError::at(
+            source,
+            token,
+            format!("expected '{expected}', found '{token}'"),
+        )),
+    }
+}
+
+/// Takes the next item of `tokens`, or reports that the `what` is missing at the end of
+/// `source`
+pub fn next_token<'a>(
+    source: &str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+    what: &str,
+) -> Result<&'a str, ParseError> {
+    tokens
+        .next()
+        .ok_or_else(|| ParseError::at_end(source, format!("missing {what}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "R 4\nU 4\nX 3\n";
+
+    #[test]
+    fn locate_span() {
+        let line = INPUT.lines().nth(2).unwrap();
+        let err = ParseError::at(INPUT, &line[..1], "invalid direction 'X'");
+        assert_eq!((err.line(), err.column()), (3, 1));
+        assert_eq!(err.offending_text(), "X");
+        assert_eq!(
+            err.to_string(),
+            "invalid direction 'X'\n --> 3:1\n  |\n3 | X 3\n  | ^"
+        );
+    }
+
+    #[test]
+    fn move_error_within_source() {
+        let line = INPUT.lines().nth(1).unwrap();
+        assert_eq!(parse_token::<u8>(line, &line[2..], "count"), Ok(4));
+        let err = parse_token::<u8>(line, &line[..1], "count").unwrap_err();
+        assert_eq!((err.line(), err.column()), (1, 1));
+        let err = err.within(INPUT, line);
+        assert_eq!((err.line(), err.column()), (2, 1));
+        assert_eq!(err.offending_text(), "U");
+        assert_eq!(
+            err.message(),
+            "invalid count: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn point_at_end_of_line() {
+        let line = "move 1 from";
+        let err = ParseError::at_end(line, "missing target stack");
+        assert_eq!((err.line(), err.column()), (1, 12));
+        assert_eq!(err.offending_text(), "");
+        assert!(err
+            .to_string()
+            .ends_with("1 | move 1 from\n  |            ^"));
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let input = "äö x";
+        let err = ParseError::at(input, &input[5..], "bad");
+        assert_eq!(err.column(), 4);
+        assert_eq!(err.offending_text(), "x");
+    }
+
+    #[test]
+    fn wide_gutter_and_multiple_carets() {
+        let input = "\n".repeat(11) + "abc def";
+        let err = ParseError::at(&input, &input[15..], "bad word");
+        assert_eq!(
+            err.to_string(),
+            "bad word\n  --> 12:5\n   |\n12 | abc def\n   |     ^^^"
+        );
+    }
+
+    #[test]
+    fn tokens() {
+        let line = "move 1 from 2";
+        let mut words = line.split_whitespace();
+        assert!(
+            expect_token(line, next_token(line, &mut words, "a").unwrap(), "move")
+                .is_ok()
+        );
+        let err = expect_token(line, next_token(line, &mut words, "b").unwrap(), "to");
+        assert_eq!(err.unwrap_err().column(), 6);
+        words.next();
+        words.next();
+        assert_eq!(next_token(line, &mut words, "c").unwrap_err().column(), 14);
+    }
+}
@@ -0,0 +1,199 @@
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use anyhow::bail;
+
+use crate::{cancel, input};
+
+/// Threads to use at most, 0 for one per CPU
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+/// Worker threads currently running, in addition to the threads that called into the pool
+static WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the number of threads to use at most, 0 for one per CPU
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// The number of threads to use at most
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Applies `--threads <n>` or `--threads=<n>` from the command line
+pub fn set_threads_from_args() -> anyhow::Result<()> {
+    if let Some(value) = input::flag_from_args("--threads")? {
+        match value.parse() {
+            Ok(threads) if threads > 0 => set_threads(threads),
+            _ => bail!("Invalid --threads {value}, expected at least 1"),
+        }
+    }
+    Ok(())
+}
+
+/// Worker threads reserved from the budget given by [`threads`], released on drop
+///
+/// Nested calls into the pool, e.g., a day splitting its hot loop while the runner is
+/// running all days at once, get no workers while the budget is used up and then run on
+/// the calling thread alone.
+struct Workers(usize);
+
+impl Workers {
+    fn reserve(wanted: usize) -> Self {
+        let budget = threads().saturating_sub(1);
+        let mut reserved = 0;
+        let _ = WORKERS.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |running| {
+            reserved = wanted.min(budget.saturating_sub(running));
+            Some(running + reserved)
+        });
+        Self(reserved)
+    }
+}
+
+impl Drop for Workers {
+    fn drop(&mut self) {
+        WORKERS.fetch_sub(self.0, Ordering::Relaxed);
+    }
+}
+
+/// Runs `work` on the calling thread and `workers` scoped threads, returning all results
+///
+/// The threads see the calling thread's [`cancel`] token.
+fn scoped<R: Send>(workers: &Workers, work: impl Fn() -> R + Sync) -> Vec<R> {
+    let token = cancel::current();
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.0)
+            .map(|_| {
+                let token = token.clone();
+                let work = &work;
+                scope.spawn(move || match token {
+                    Some(token) => cancel::scope(token, work),
+                    None => work(),
+                })
+            })
+            .collect();
+        let mut results = vec![work()];
+        for handle in handles {
+            match handle.join() {
+                Ok(result) => results.push(result),
+                Err(panic) => std::panic::resume_unwind(panic),
+            }
+        }
+        results
+    })
+}
+
+/// `items.iter().map(f).collect()`, with the items spread over the threads
+///
+/// Threads take the next item as soon as they are done with one, so items that take
+/// very different times are fine. The results are in the order of the items.
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = Workers::reserve(items.len().saturating_sub(1));
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = scoped(&workers, || {
+        let mut results = vec![];
+        loop {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(idx) else {
+                return results;
+            };
+            results.push((idx, f(item)));
+        }
+    })
+    .into_iter()
+    .flatten()
+    .collect();
+    results.sort_unstable_by_key(|&(idx, _)| idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// `range.find_map(f)`, with the range spread over the threads
+///
+/// The result is always that of the lowest index `f` returns something for, no matter
+/// how many threads there are. Indices above one that had a result are skipped.
+pub fn find_map_first<R: Send>(
+    range: Range<usize>,
+    f: impl Fn(usize) -> Option<R> + Sync,
+) -> Option<R> {
+    let workers = Workers::reserve(range.len().saturating_sub(1));
+    // enough chunks to even out the load, few enough to not fight over `next`
+    let chunk = (range.len() / ((workers.0 + 1) * 64)).clamp(1, 4096);
+    let next = AtomicUsize::new(range.start);
+    let found = AtomicUsize::new(usize::MAX);
+    scoped(&workers, || loop {
+        let start = next.fetch_add(chunk, Ordering::Relaxed);
+        let end = range.end.min(start.saturating_add(chunk));
+        for idx in start..end {
+            if idx > found.load(Ordering::Relaxed) {
+                return None;
+            }
+            if let Some(result) = f(idx) {
+                found.fetch_min(idx, Ordering::Relaxed);
+                return Some((idx, result));
+            }
+        }
+        if end == range.end {
+            return None;
+        }
+    })
+    .into_iter()
+    .flatten()
+    .min_by_key(|&(idx, _)| idx)
+    .map(|(_, result)| result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let squares = map(&items, |&n| n * n);
+        assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+        assert_eq!(map(&[] as &[u8], |&n| n), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn find_map_first_finds_lowest_index() {
+        let is_hit = |idx: usize| idx.is_multiple_of(7919) && idx > 100_000;
+        for range in [0..1_000_000, 5..100_000, 200_000..200_001, 3..3] {
+            let expected = range.clone().find(|&idx| is_hit(idx));
+            let found = find_map_first(range, |idx| is_hit(idx).then_some(idx));
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn nested_calls_share_the_budget() {
+        let outer: Vec<usize> = (0..16).collect();
+        let sums = map(&outer, |&n| {
+            let inner: Vec<usize> = (0..n).collect();
+            map(&inner, |&m| m).iter().sum::<usize>()
+        });
+        assert_eq!(
+            sums,
+            outer
+                .iter()
+                .map(|n| n * n.saturating_sub(1) / 2)
+                .collect::<Vec<_>>()
+        );
+        assert!(WORKERS.load(Ordering::Relaxed) <= threads());
+    }
+
+    #[test]
+    fn threads_see_the_cancel_token() {
+        let token = cancel::Token::new();
+        token.cancel();
+        let items: Vec<u8> = (0..64).collect();
+        let checks = cancel::scope(token, || map(&items, |_| cancel::check()));
+        assert!(checks.iter().all(Result::is_err));
+        assert!(map(&items, |_| cancel::check()).iter().all(Result::is_ok));
+    }
+}
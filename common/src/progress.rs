@@ -0,0 +1,127 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use log::Level;
+
+use crate::cancel;
+
+/// Time between two reports
+const INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reports how far a long loop got, how fast it goes and, if the total is known, how long
+/// it will take
+///
+/// Reports are debug log records of the given target, e.g., `day15`, so they show with
+/// `-v`. Otherwise, counting is a single atomic add. Threads can count concurrently. If
+/// the loop was [`cancel`]led, how far it got is a warning.
+pub struct Progress {
+    target: &'static str,
+    /// What is counted, e.g., `rows`
+    what: &'static str,
+    total: Option<u64>,
+    done: AtomicU64,
+    start: Instant,
+    /// Time of the last report, `None` if reports are not printed
+    last_report: Option<Mutex<Instant>>,
+}
+
+impl Progress {
+    pub fn new(target: &'static str, what: &'static str, total: Option<u64>) -> Self {
+        let start = Instant::now();
+        Self {
+            target,
+            what,
+            total,
+            done: AtomicU64::new(0),
+            start,
+            last_report: log::log_enabled!(target: target, Level::Debug)
+                .then(|| Mutex::new(start)),
+        }
+    }
+
+    /// Counts `n` more done, reporting if the last report is a while ago
+    pub fn add(&self, n: u64) {
+        let done = self.done.fetch_add(n, Ordering::Relaxed) + n;
+        let Some(last_report) = &self.last_report else {
+            return;
+        };
+        // another thread is reporting just now
+        let Ok(mut last_report) = last_report.try_lock() else {
+            return;
+        };
+        let now = Instant::now();
+        if now - *last_report >= INTERVAL {
+            *last_report = now;
+            let status = status(self.what, done, self.total, now - self.start);
+            log::debug!(target: self.target, "{status}");
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        let done = *self.done.get_mut();
+        let elapsed = self.start.elapsed();
+        if cancel::is_cancelled() {
+            let status = status(self.what, done, self.total, elapsed);
+            log::warn!(target: self.target, "Cancelled after {elapsed:.2?} at {status}");
+        } else if self.last_report.is_some() {
+            log::debug!(target: self.target, "{} {} in {elapsed:.2?}", done, self.what);
+        }
+    }
+}
+
+/// E.g., `rows: 250/1000 (25%), 50/s, 15s left`
+fn status(what: &str, done: u64, total: Option<u64>, elapsed: Duration) -> String {
+    let rate = done as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    match total {
+        Some(total) => {
+            let percent = done as f64 / total.max(1) as f64 * 100.0;
+            // nothing to estimate the time left from yet
+            let left = match done {
+                0 => String::new(),
+                _ => {
+                    let left = total.saturating_sub(done) as f64 / rate;
+                    format!(", {}s left", left.ceil())
+                }
+            };
+            format!("{what}: {done}/{total} ({percent:.0}%), {rate:.0}/s{left}")
+        }
+        None => format!("{what}: {done}, {rate:.0}/s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_with_rate_and_time_left() {
+        let elapsed = Duration::from_secs(5);
+        assert_eq!(
+            status("rows", 250, Some(1000), elapsed),
+            "rows: 250/1000 (25%), 50/s, 15s left"
+        );
+        assert_eq!(status("rocks", 20, None, elapsed), "rocks: 20, 4/s");
+        assert_eq!(
+            status("rows", 0, Some(1000), elapsed),
+            "rows: 0/1000 (0%), 0/s"
+        );
+    }
+
+    #[test]
+    fn counts_across_threads() {
+        let progress = Progress::new("test", "items", Some(400));
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| (0..100).for_each(|_| progress.add(1)));
+            }
+        });
+        assert_eq!(progress.done.load(Ordering::Relaxed), 400);
+    }
+}
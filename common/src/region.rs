@@ -0,0 +1,201 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    threed::{Bounds3, Grid3, Point3},
+    twod::{Bounds, Grid, Point},
+};
+
+/// A dense grid of cells that regions can be found in, either 2D or 3D
+pub trait Cells {
+    type Pos: Copy + Eq;
+    type Cell;
+    type Bounds: Copy;
+    /// A grid of the same shape with other values
+    type Map<V>: Index<Self::Pos, Output = V> + IndexMut<Self::Pos>;
+
+    fn cell(&self, p: Self::Pos) -> &Self::Cell;
+    fn positions(&self) -> impl Iterator<Item = Self::Pos> + '_;
+    /// Adjacent positions within the grid (4-connected in 2D, 6-connected in 3D)
+    fn adjacent(&self, p: Self::Pos) -> impl Iterator<Item = Self::Pos> + '_;
+    fn map_with<V: Clone>(&self, value: V) -> Self::Map<V>;
+    fn bounds_including(bounds: Option<Self::Bounds>, p: Self::Pos) -> Self::Bounds;
+}
+
+impl<T> Cells for Grid<T> {
+    type Pos = Point;
+    type Cell = T;
+    type Bounds = Bounds;
+    type Map<V> = Grid<V>;
+
+    fn cell(&self, p: Point) -> &T {
+        &self[p]
+    }
+
+    fn positions(&self) -> impl Iterator<Item = Point> + '_ {
+        self.enumerate().map(|(p, _)| p)
+    }
+
+    fn adjacent(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(p)
+    }
+
+    fn map_with<V: Clone>(&self, value: V) -> Grid<V> {
+        Grid::from_fn(self.width(), self.height(), |_| value.clone())
+    }
+
+    fn bounds_including(bounds: Option<Bounds>, p: Point) -> Bounds {
+        bounds.map_or(Bounds::of_point(p), |b| b.including(p))
+    }
+}
+
+impl<T> Cells for Grid3<T> {
+    type Pos = Point3;
+    type Cell = T;
+    type Bounds = Bounds3;
+    type Map<V> = Grid3<V>;
+
+    fn cell(&self, p: Point3) -> &T {
+        &self[p]
+    }
+
+    fn positions(&self) -> impl Iterator<Item = Point3> + '_ {
+        self.bounds().points()
+    }
+
+    fn adjacent(&self, p: Point3) -> impl Iterator<Item = Point3> + '_ {
+        self.neighbours(p)
+    }
+
+    fn map_with<V: Clone>(&self, value: V) -> Grid3<V> {
+        Grid3::new(self.bounds(), value).expect("a grid of these bounds exists already")
+    }
+
+    fn bounds_including(bounds: Option<Bounds3>, p: Point3) -> Bounds3 {
+        bounds.map_or(Bounds3::of_point(p), |b| b.including(p))
+    }
+}
+
+/// Marks all cells reachable from any of `seeds` through `passable` cells
+///
+/// Seeds that are not passable themselves are ignored.
+pub fn flood_fill<G: Cells>(
+    grid: &G,
+    seeds: impl IntoIterator<Item = G::Pos>,
+    passable: impl Fn(&G::Cell) -> bool,
+) -> G::Map<bool> {
+    let mut filled = grid.map_with(false);
+    let mut stack: Vec<G::Pos> = seeds.into_iter().collect();
+    while let Some(p) = stack.pop() {
+        if filled[p] || !passable(grid.cell(p)) {
+            continue;
+        }
+        filled[p] = true;
+        stack.extend(grid.adjacent(p).filter(|&n| !filled[n]));
+    }
+    filled
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Component<P, B> {
+    /// Some cell of the component
+    pub seed: P,
+    /// Number of cells
+    pub size: usize,
+    pub bounds: B,
+}
+
+/// The connected regions of `passable` cells
+pub struct Components<G: Cells> {
+    /// Index into `components` for each passable cell
+    pub labels: G::Map<Option<usize>>,
+    pub components: Vec<Component<G::Pos, G::Bounds>>,
+}
+
+/// Labels the connected regions of `passable` cells
+pub fn label_components<G: Cells>(
+    grid: &G,
+    passable: impl Fn(&G::Cell) -> bool,
+) -> Components<G> {
+    let mut labels = grid.map_with(None);
+    let mut components = Vec::new();
+    let mut stack = Vec::new();
+    for seed in grid.positions() {
+        if labels[seed].is_some() || !passable(grid.cell(seed)) {
+            continue;
+        }
+        let label = components.len();
+        let mut component = Component {
+            seed,
+            size: 0,
+            bounds: G::bounds_including(None, seed),
+        };
+        labels[seed] = Some(label);
+        stack.push(seed);
+        while let Some(p) = stack.pop() {
+            component.size += 1;
+            component.bounds = G::bounds_including(Some(component.bounds), p);
+            for n in grid.adjacent(p) {
+                if labels[n].is_none() && passable(grid.cell(n)) {
+                    labels[n] = Some(label);
+                    stack.push(n);
+                }
+            }
+        }
+        components.push(component);
+    }
+    Components { labels, components }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOMS: &str = "\
+        ..#..\n\
+        ###..\n\
+        ..#.#\n\
+        ..#.#";
+
+    #[test]
+    fn flood_fill_2d() {
+        let grid = Grid::<bool>::parse(ROOMS).unwrap();
+        let filled = flood_fill(&grid, [Point::new(4, 0)], |&wall| !wall);
+        assert_eq!(filled.data.iter().filter(|&&f| f).count(), 6);
+        assert!(filled[Point::new(3, 3)]);
+        assert!(!filled[Point::new(0, 0)]);
+        let filled = flood_fill(&grid, [Point::new(0, 0), Point::new(2, 0)], |&w| !w);
+        assert_eq!(filled.data.iter().filter(|&&f| f).count(), 2);
+    }
+
+    #[test]
+    fn label_components_2d() {
+        let grid = Grid::<bool>::parse(ROOMS).unwrap();
+        let found = label_components(&grid, |&wall| !wall);
+        let sizes: Vec<_> = found.components.iter().map(|c| c.size).collect();
+        assert_eq!(sizes, vec![2, 6, 4]);
+        let bounds = found.components[1].bounds;
+        assert_eq!(
+            (bounds.min, bounds.max),
+            (Point::new(3, 0), Point::new(4, 3))
+        );
+        assert_eq!(found.labels[Point::new(1, 3)], Some(2));
+        assert_eq!(found.labels[Point::new(2, 3)], None);
+    }
+
+    #[test]
+    fn enclosed_cavity_3d() {
+        // a hollow 3x3x3 cube
+        let bounds = Bounds3::new(Point3::ORIGIN, Point3::new(4, 4, 4));
+        let solid = Grid3::from_fn(bounds, |p| {
+            Bounds3::new(Point3::new(1, 1, 1), Point3::new(3, 3, 3)).contains(p)
+                && p != Point3::new(2, 2, 2)
+        })
+        .unwrap();
+        let outside = flood_fill(&solid, [Point3::ORIGIN], |&s| !s);
+        assert!(!outside[Point3::new(2, 2, 2)]);
+        let found = label_components(&solid, |&s| !s);
+        assert_eq!(found.components.len(), 2);
+        assert_eq!(found.components[1].size, 1);
+        assert_eq!(found.components[0].size, 125 - 27);
+    }
+}
@@ -0,0 +1,380 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    marker::PhantomData,
+};
+
+use crate::twod::{Grid, Point};
+
+/// A directed graph with non-negative step costs
+pub trait Graph {
+    type Node: Copy + Eq + Hash;
+
+    /// Calls `visit` with each node reachable in one step from `node` and the cost of
+    /// that step
+    fn edges(&self, node: Self::Node, visit: impl FnMut(Self::Node, u64));
+}
+
+/// A graph given by a closure listing the neighbours of a node; every step costs 1
+pub struct FromFn<N, F> {
+    neighbours: F,
+    node: PhantomData<fn(N)>,
+}
+
+pub fn from_fn<N, F, I>(neighbours: F) -> FromFn<N, F>
+where
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    FromFn {
+        neighbours,
+        node: PhantomData,
+    }
+}
+
+impl<N, F, I> Graph for FromFn<N, F>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    type Node = N;
+
+    fn edges(&self, node: N, mut visit: impl FnMut(N, u64)) {
+        (self.neighbours)(node)
+            .into_iter()
+            .for_each(|n| visit(n, 1));
+    }
+}
+
+/// A graph given by a closure listing the neighbours of a node with step costs
+pub struct WeightedFromFn<N, F> {
+    edges: F,
+    node: PhantomData<fn(N)>,
+}
+
+pub fn weighted_from_fn<N, F, I>(edges: F) -> WeightedFromFn<N, F>
+where
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    WeightedFromFn {
+        edges,
+        node: PhantomData,
+    }
+}
+
+impl<N, F, I> Graph for WeightedFromFn<N, F>
+where
+    N: Copy + Eq + Hash,
+    F: Fn(N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    type Node = N;
+
+    fn edges(&self, node: N, mut visit: impl FnMut(N, u64)) {
+        (self.edges)(node)
+            .into_iter()
+            .for_each(|(n, cost)| visit(n, cost));
+    }
+}
+
+/// The 4-connected cells of a grid, where `can_step(from, to)` tells whether a step
+/// between two adjacent cells is allowed; every step costs 1
+pub struct GridGraph<'a, T, F> {
+    grid: &'a Grid<T>,
+    can_step: F,
+}
+
+impl<'a, T, F> GridGraph<'a, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    pub fn new(grid: &'a Grid<T>, can_step: F) -> Self {
+        Self { grid, can_step }
+    }
+}
+
+impl<T, F> Graph for GridGraph<'_, T, F>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    type Node = Point;
+
+    fn edges(&self, p: Point, mut visit: impl FnMut(Point, u64)) {
+        for n in self.grid.neighbours(p) {
+            if (self.can_step)(&self.grid[p], &self.grid[n]) {
+                visit(n, 1);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Visit<N> {
+    distance: u64,
+    predecessor: Option<N>,
+}
+
+/// Distances of the nodes reached by a search, with the predecessors to reconstruct
+/// the paths
+#[derive(Clone, Debug)]
+pub struct Distances<N> {
+    visits: HashMap<N, Visit<N>>,
+}
+
+impl<N: Copy + Eq + Hash> Distances<N> {
+    fn new() -> Self {
+        Self {
+            visits: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, node: N) -> Option<u64> {
+        self.visits.get(&node).map(|v| v.distance)
+    }
+
+    pub fn contains(&self, node: N) -> bool {
+        self.visits.contains_key(&node)
+    }
+
+    /// The number of reached nodes
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+
+    /// Reached nodes with their distance, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (N, u64)> + '_ {
+        self.visits.iter().map(|(&n, v)| (n, v.distance))
+    }
+
+    /// The path from the nearest start to `node`, including both ends
+    pub fn path_to(&self, node: N) -> Option<Vec<N>> {
+        let mut path = vec![node];
+        let mut visit = self.visits.get(&node)?;
+        while let Some(pred) = visit.predecessor {
+            path.push(pred);
+            visit = &self.visits[&pred];
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl Distances<Point> {
+    /// The distances as a grid, `None` for cells that were not reached
+    pub fn to_grid(&self, width: usize, height: usize) -> Grid<Option<u64>> {
+        Grid::from_fn(width, height, |p| self.get(p))
+    }
+}
+
+/// Breadth-first search from a single start, counting steps and ignoring their costs
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> Distances<G::Node> {
+    multi_bfs(graph, [start])
+}
+
+/// Breadth-first search from several starts at once, counting steps and ignoring their
+/// costs; each node gets the distance to its nearest start
+pub fn multi_bfs<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+) -> Distances<G::Node> {
+    let mut distances = Distances::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(entry) = distances.visits.entry(start) {
+            entry.insert(Visit {
+                distance: 0,
+                predecessor: None,
+            });
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let distance = distances.visits[&node].distance + 1;
+        graph.edges(node, |n, _| {
+            if let Entry::Vacant(entry) = distances.visits.entry(n) {
+                entry.insert(Visit {
+                    distance,
+                    predecessor: Some(node),
+                });
+                queue.push_back(n);
+            }
+        });
+    }
+    distances
+}
+
+/// Lowest-cost distances from the nearest of `starts`
+pub fn dijkstra<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+) -> Distances<G::Node> {
+    best_first(graph, starts, None, |_| 0)
+}
+
+/// The lowest-cost path from `start` to `goal` and its cost
+///
+/// `heuristic` must never overestimate the remaining cost to `goal`.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: G::Node,
+    heuristic: impl Fn(G::Node) -> u64,
+) -> Option<(u64, Vec<G::Node>)> {
+    let distances = best_first(graph, [start], Some(goal), heuristic);
+    Some((distances.get(goal)?, distances.path_to(goal)?))
+}
+
+/// A queue entry ordered by priority only, lowest first
+struct Queued<N> {
+    priority: u64,
+    distance: u64,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+fn best_first<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    goal: Option<G::Node>,
+    heuristic: impl Fn(G::Node) -> u64,
+) -> Distances<G::Node> {
+    let mut distances = Distances::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        distances.visits.insert(
+            start,
+            Visit {
+                distance: 0,
+                predecessor: None,
+            },
+        );
+        queue.push(Queued {
+            priority: heuristic(start),
+            distance: 0,
+            node: start,
+        });
+    }
+    while let Some(Queued { distance, node, .. }) = queue.pop() {
+        if distances.visits[&node].distance < distance {
+            continue; // outdated entry
+        }
+        if Some(node) == goal {
+            break;
+        }
+        graph.edges(node, |n, cost| {
+            let distance = distance + cost;
+            let improved = match distances.visits.get(&n) {
+                Some(visit) => distance < visit.distance,
+                None => true,
+            };
+            if improved {
+                distances.visits.insert(
+                    n,
+                    Visit {
+                        distance,
+                        predecessor: Some(node),
+                    },
+                );
+                queue.push(Queued {
+                    priority: distance + heuristic(n),
+                    distance,
+                    node: n,
+                });
+            }
+        });
+    }
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+        ..#.\n\
+        .##.\n\
+        ....";
+
+    fn maze() -> Grid<bool> {
+        Grid::parse(MAZE).unwrap()
+    }
+
+    #[test]
+    fn bfs_on_grid_with_path() {
+        let grid = maze();
+        let graph = GridGraph::new(&grid, |_, &wall| !wall);
+        let distances = bfs(&graph, Point::new(0, 0));
+        assert_eq!(distances.len(), 9);
+        assert_eq!(distances.get(Point::new(3, 0)), Some(7));
+        assert_eq!(distances.get(Point::new(2, 0)), None);
+        let path = distances.path_to(Point::new(3, 0)).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(path[0], Point::new(0, 0));
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+        let map = distances.to_grid(grid.width(), grid.height());
+        assert_eq!(map[Point::new(1, 2)], Some(3));
+    }
+
+    #[test]
+    fn multi_bfs_takes_nearest_start() {
+        let graph =
+            from_fn(|n: i32| [n - 1, n + 1].into_iter().filter(|n| n.abs() <= 10));
+        let distances = multi_bfs(&graph, [-10, 10]);
+        assert_eq!(distances.get(0), Some(10));
+        assert_eq!(distances.get(7), Some(3));
+        assert_eq!(distances.path_to(-8), Some(vec![-10, -9, -8]));
+    }
+
+    #[test]
+    fn dijkstra_and_astar_prefer_cheap_detours() {
+        // 0 -> 3 directly costs 10, via 1 and 2 it costs 3
+        let graph = weighted_from_fn(|n: u8| match n {
+            0 => vec![(3, 10), (1, 1)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        });
+        let distances = dijkstra(&graph, [0]);
+        assert_eq!(distances.get(3), Some(3));
+        assert_eq!(distances.path_to(3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(astar(&graph, 0, 3, |_| 0), Some((3, vec![0, 1, 2, 3])));
+        assert_eq!(astar(&graph, 3, 0, |_| 0), None);
+    }
+
+    #[test]
+    fn astar_on_grid() {
+        let grid = maze();
+        let graph = GridGraph::new(&grid, |_, &wall| !wall);
+        let goal = Point::new(3, 0);
+        let (cost, path) =
+            astar(&graph, Point::new(0, 0), goal, |p| p.manhattan(goal) as u64).unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.last(), Some(&goal));
+    }
+}
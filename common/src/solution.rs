@@ -0,0 +1,298 @@
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail};
+use serde::Serialize;
+
+use crate::{
+    diagnostics,
+    image::Drawing,
+    input,
+    params::{ParamSpec, Params},
+    random::Rng,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => Err(anyhow!("Invalid part '{other}', expected 1 or 2")),
+        }
+    }
+}
+
+/// A puzzle solution: parses the puzzle input once and solves both parts on it
+pub trait Solution {
+    const DAY: u8;
+    const TITLE: &'static str;
+
+    /// Puzzle constants that can be changed without recompiling, `()` if there are none
+    type Params: Params;
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    /// Parses the input for the default, real parameters
+    fn parse(input: &str) -> anyhow::Result<Self::Input>;
+
+    /// Parses the input for the given parameters, which days with parameters keep in
+    /// their input
+    fn parse_with(input: &str, _params: &Self::Params) -> anyhow::Result<Self::Input> {
+        Self::parse(input)
+    }
+    fn part1(input: &Self::Input) -> anyhow::Result<Self::Answer1>;
+    fn part2(input: &Self::Input) -> anyhow::Result<Self::Answer2>;
+
+    /// A picture of the puzzle, written by `--image`
+    fn draw(_input: &Self::Input) -> anyhow::Result<Drawing> {
+        bail!("Day {} has no picture", Self::DAY)
+    }
+
+    /// A random, valid puzzle input; `size` is a day-specific count, e.g., of lines
+    fn generate(_rng: &mut Rng, _size: usize) -> anyhow::Result<String> {
+        bail!("Day {} has no input generator", Self::DAY)
+    }
+}
+
+/// Result of solving one part, with the answer already rendered to text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub answer: String,
+    /// Parsing the input, which all parts that [`run`] solved share
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// What the solution reported while solving, see [`diagnostics`]; for the first part
+    /// also what it reported while parsing
+    pub diagnostics: Vec<String>,
+}
+
+/// One answer as printed with `--format json`
+#[derive(Debug, Serialize)]
+struct Report<'a> {
+    day: u8,
+    part: u8,
+    answer: &'a str,
+    /// Parsing, shared with the other part
+    parse_ms: f64,
+    /// Solving
+    elapsed_ms: f64,
+    diagnostics: &'a [String],
+}
+
+impl Run {
+    /// A JSON object with the day, part, answer, parse and solve times and diagnostics, on
+    /// one line
+    pub fn to_json(&self, day: u8, part: Part) -> String {
+        let report = Report {
+            day,
+            part: part.number(),
+            answer: &self.answer,
+            parse_ms: self.parse_time.as_secs_f64() * 1000.0,
+            elapsed_ms: self.solve_time.as_secs_f64() * 1000.0,
+            diagnostics: &self.diagnostics,
+        };
+        serde_json::to_string(&report).expect("reports have no maps or non-string keys")
+    }
+}
+
+/// How answers are printed
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Sentences for humans
+    #[default]
+    Text,
+    /// One JSON object per answer and line, see [`Run::to_json`]
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => Err(anyhow!("Invalid format '{other}', expected text or json")),
+        }
+    }
+}
+
+impl Format {
+    /// The format given by `--format` on the command line, text by default
+    pub fn from_args() -> anyhow::Result<Self> {
+        input::flag_from_args("--format")?
+            .map_or(Ok(Format::Text), |format| format.parse())
+    }
+}
+
+/// Solves both parts, printing the answers as JSON lines
+pub fn print_json<S: Solution>(input: &str, params: &ParamSpec) -> anyhow::Result<()> {
+    for (part, run) in run::<S>(input, &Part::ALL, params)? {
+        println!("{}", run?.to_json(S::DAY, part));
+    }
+    Ok(())
+}
+
+/// The result of each part that [`run`] solved, in the order they were asked for
+pub type PartRuns = Vec<(Part, anyhow::Result<Run>)>;
+
+/// Type-erased entry point to a solution, see [`run`]
+pub type RunFn = fn(&str, &[Part], &ParamSpec) -> anyhow::Result<PartRuns>;
+
+/// Type-erased [`Solution::generate`]
+pub type GenerateFn = fn(&mut Rng, usize) -> anyhow::Result<String>;
+
+/// Parses `input` once and solves each of `parts` on it
+///
+/// Fails if the parameters or the input are invalid; a part that fails only fails its own
+/// result.
+pub fn run<S: Solution>(
+    input: &str,
+    parts: &[Part],
+    params: &ParamSpec,
+) -> anyhow::Result<PartRuns> {
+    let params = params.resolve()?;
+    let start = Instant::now();
+    let (parsed, mut parse_diagnostics) =
+        diagnostics::collect(|| S::parse_with(input, &params));
+    let parse_time = start.elapsed();
+    let parsed = parsed?;
+    let mut solve = |part| {
+        let start = Instant::now();
+        let (answer, diagnostics) = diagnostics::collect(|| match part {
+            Part::One => S::part1(&parsed).map(|answer| answer.to_string()),
+            Part::Two => S::part2(&parsed).map(|answer| answer.to_string()),
+        });
+        let solve_time = start.elapsed();
+        // the first part reports what parsing did
+        let diagnostics = [std::mem::take(&mut parse_diagnostics), diagnostics].concat();
+        answer.map(|answer| Run {
+            answer,
+            parse_time,
+            solve_time,
+            diagnostics,
+        })
+    };
+    Ok(parts.iter().map(|&part| (part, solve(part))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+        const TITLE: &'static str = "Sum";
+
+        type Params = ();
+        type Input = Vec<u32>;
+        type Answer1 = u32;
+        type Answer2 = String;
+
+        fn parse(input: &str) -> anyhow::Result<Self::Input> {
+            Ok(input
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<_, _>>()?)
+        }
+
+        fn part1(input: &Self::Input) -> anyhow::Result<Self::Answer1> {
+            Ok(input.iter().sum())
+        }
+
+        fn part2(input: &Self::Input) -> anyhow::Result<Self::Answer2> {
+            Ok(format!("{} numbers", input.len()))
+        }
+    }
+
+    fn sum(input: &str, part: Part) -> anyhow::Result<Run> {
+        let mut runs = run::<Sum>(input, &[part], &ParamSpec::default())?;
+        runs.remove(0).1
+    }
+
+    #[test]
+    fn run_renders_answers() {
+        assert_eq!(sum("1 2 3", Part::One).unwrap().answer, "6");
+        assert_eq!(sum("1 2 3", Part::Two).unwrap().answer, "3 numbers");
+    }
+
+    #[test]
+    fn run_parses_once_for_all_parts() {
+        let runs = run::<Sum>("1 2 3", &[Part::Two, Part::One], &ParamSpec::default());
+        let runs: Vec<_> = runs
+            .unwrap()
+            .into_iter()
+            .map(|(part, run)| (part, run.unwrap()))
+            .collect();
+        assert_eq!(runs[0].0, Part::Two);
+        assert_eq!(runs[1].1.answer, "6");
+        assert_eq!(runs[0].1.parse_time, runs[1].1.parse_time);
+    }
+
+    #[test]
+    fn run_propagates_parse_errors() {
+        assert!(sum("1 two 3", Part::One).is_err());
+    }
+
+    #[test]
+    fn run_rejects_unknown_params() {
+        let mut params = ParamSpec::default();
+        params.set("rows", "3").unwrap();
+        assert!(run::<Sum>("1 2 3", &[Part::One], &params).is_err());
+    }
+
+    #[test]
+    fn run_renders_json() {
+        let mut run = sum("1 2 3", Part::One).unwrap();
+        run.parse_time = Duration::from_micros(1500);
+        run.solve_time = Duration::from_millis(1);
+        run.diagnostics = vec!["said \"hi\"".to_owned()];
+        assert_eq!(
+            run.to_json(7, Part::One),
+            r#"{"day":7,"part":1,"answer":"6","parse_ms":1.5,"elapsed_ms":1.0,"diagnostics":["said \"hi\""]}"#
+        );
+        assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn parse_part() {
+        assert_eq!("1".parse::<Part>().unwrap(), Part::One);
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+    }
+}
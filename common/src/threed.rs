@@ -0,0 +1,337 @@
+use anyhow::bail;
+
+/// Most cells a [`Grid3`] may have, so that huge bounds fail instead of exhausting memory
+pub const MAX_CELLS: usize = 1 << 28;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Point3 {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl Point3 {
+    pub const ORIGIN: Point3 = Point3::new(0, 0, 0);
+
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// In `i64`, as the distance of far apart points does not fit into an `i32`
+    pub fn manhattan(self, other: Point3) -> i64 {
+        self.x.abs_diff(other.x) as i64
+            + self.y.abs_diff(other.y) as i64
+            + self.z.abs_diff(other.z) as i64
+    }
+
+    /// `None` if a coordinate leaves the range of `i32`
+    pub fn checked_add(self, rhs: Point3) -> Option<Point3> {
+        Some(Point3::new(
+            self.x.checked_add(rhs.x)?,
+            self.y.checked_add(rhs.y)?,
+            self.z.checked_add(rhs.z)?,
+        ))
+    }
+
+    /// `None` if a coordinate leaves the range of `i32`
+    pub fn checked_sub(self, rhs: Point3) -> Option<Point3> {
+        Some(Point3::new(
+            self.x.checked_sub(rhs.x)?,
+            self.y.checked_sub(rhs.y)?,
+            self.z.checked_sub(rhs.z)?,
+        ))
+    }
+
+    /// The 6 points sharing a face, without those beyond the range of `i32`
+    pub fn neighbours(self) -> impl Iterator<Item = Point3> {
+        const OFFSETS: [Point3; 6] = [
+            Point3::new(-1, 0, 0),
+            Point3::new(1, 0, 0),
+            Point3::new(0, -1, 0),
+            Point3::new(0, 1, 0),
+            Point3::new(0, 0, -1),
+            Point3::new(0, 0, 1),
+        ];
+        OFFSETS
+            .into_iter()
+            .filter_map(move |off| self.checked_add(off))
+    }
+
+    /// The 26 points sharing a face, an edge or a corner, without those beyond the range
+    /// of `i32`
+    pub fn neighbours26(self) -> impl Iterator<Item = Point3> {
+        Bounds3::new(Point3::new(-1, -1, -1), Point3::new(1, 1, 1))
+            .points()
+            .filter(|&off| off != Point3::ORIGIN)
+            .filter_map(move |off| self.checked_add(off))
+    }
+}
+
+impl std::ops::Add for Point3 {
+    type Output = Point3;
+    fn add(self, rhs: Point3) -> Point3 {
+        Point3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl std::ops::AddAssign for Point3 {
+    fn add_assign(&mut self, rhs: Point3) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Point3 {
+    type Output = Point3;
+    fn sub(self, rhs: Point3) -> Point3 {
+        Point3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl std::ops::SubAssign for Point3 {
+    fn sub_assign(&mut self, rhs: Point3) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul<i32> for Point3 {
+    type Output = Point3;
+    fn mul(self, rhs: i32) -> Point3 {
+        Point3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl std::ops::Neg for Point3 {
+    type Output = Point3;
+    fn neg(self) -> Point3 {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// Inclusive, axis-aligned bounding box
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds3 {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Bounds3 {
+    pub fn new(min: Point3, max: Point3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn of_point(p: Point3) -> Self {
+        Self { min: p, max: p }
+    }
+
+    /// The smallest box containing all `points`, `None` if there are none
+    pub fn of_points(points: impl IntoIterator<Item = Point3>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = Self::of_point(points.next()?);
+        Some(points.fold(first, Self::including))
+    }
+
+    pub fn including(self, p: Point3) -> Self {
+        Self {
+            min: Point3::new(
+                self.min.x.min(p.x),
+                self.min.y.min(p.y),
+                self.min.z.min(p.z),
+            ),
+            max: Point3::new(
+                self.max.x.max(p.x),
+                self.max.y.max(p.y),
+                self.max.z.max(p.z),
+            ),
+        }
+    }
+
+    /// Grows the box by `margin` in every direction, `None` if it would leave the range
+    /// of `i32`
+    pub fn grow(self, margin: i32) -> Option<Self> {
+        let margin = Point3::new(margin, margin, margin);
+        Some(Self {
+            min: self.min.checked_sub(margin)?,
+            max: self.max.checked_add(margin)?,
+        })
+    }
+
+    pub fn size(&self) -> (usize, usize, usize) {
+        (
+            self.max.x.abs_diff(self.min.x) as usize + 1,
+            self.max.y.abs_diff(self.min.y) as usize + 1,
+            self.max.z.abs_diff(self.min.z) as usize + 1,
+        )
+    }
+
+    /// `None` if the number of points does not fit into a `usize`
+    pub fn volume(&self) -> Option<usize> {
+        let (w, h, d) = self.size();
+        w.checked_mul(h)?.checked_mul(d)
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    /// All points in the box, x changing fastest
+    pub fn points(self) -> impl Iterator<Item = Point3> {
+        (self.min.z..=self.max.z).flat_map(move |z| {
+            (self.min.y..=self.max.y).flat_map(move |y| {
+                (self.min.x..=self.max.x).map(move |x| Point3::new(x, y, z))
+            })
+        })
+    }
+}
+
+/// A dense 3D grid covering a bounding box, which may reach into negative coordinates
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid3<T> {
+    data: Vec<T>,
+    bounds: Bounds3,
+}
+
+/// The number of cells of a grid covering `bounds`, failing beyond [`MAX_CELLS`]
+fn cells_within(bounds: Bounds3) -> anyhow::Result<usize> {
+    match bounds.volume() {
+        Some(volume) if volume <= MAX_CELLS => Ok(volume),
+        _ => bail!(
+            "A grid from {:?} to {:?} would have more than {MAX_CELLS} cells",
+            bounds.min,
+            bounds.max
+        ),
+    }
+}
+
+impl<T> Grid3<T> {
+    /// Fails if the grid would have more than [`MAX_CELLS`] cells
+    pub fn from_fn(bounds: Bounds3, f: impl FnMut(Point3) -> T) -> anyhow::Result<Self> {
+        cells_within(bounds)?;
+        Ok(Self {
+            data: bounds.points().map(f).collect(),
+            bounds,
+        })
+    }
+
+    pub fn bounds(&self) -> Bounds3 {
+        self.bounds
+    }
+
+    pub fn contains(&self, p: Point3) -> bool {
+        self.bounds.contains(p)
+    }
+
+    fn index_of(&self, p: Point3) -> Option<usize> {
+        if !self.contains(p) {
+            return None;
+        }
+        let (w, h, _) = self.bounds.size();
+        let off = p - self.bounds.min;
+        Some(off.x as usize + w * (off.y as usize + h * off.z as usize))
+    }
+
+    pub fn get(&self, p: Point3) -> Option<&T> {
+        self.index_of(p).map(|idx| &self.data[idx])
+    }
+
+    pub fn get_mut(&mut self, p: Point3) -> Option<&mut T> {
+        self.index_of(p).map(|idx| &mut self.data[idx])
+    }
+
+    /// The 6 face neighbours of `p` that lie within the grid
+    pub fn neighbours(&self, p: Point3) -> impl Iterator<Item = Point3> + '_ {
+        p.neighbours().filter(|&n| self.contains(n))
+    }
+
+    /// All cells with their position, x changing fastest
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point3, &T)> {
+        self.bounds.points().zip(self.data.iter())
+    }
+}
+
+impl<T: Clone> Grid3<T> {
+    /// Fails if the grid would have more than [`MAX_CELLS`] cells
+    pub fn new(bounds: Bounds3, value: T) -> anyhow::Result<Self> {
+        Ok(Self {
+            data: vec![value; cells_within(bounds)?],
+            bounds,
+        })
+    }
+}
+
+impl<T> std::ops::Index<Point3> for Grid3<T> {
+    type Output = T;
+    fn index(&self, p: Point3) -> &Self::Output {
+        self.get(p).expect("point out of grid bounds")
+    }
+}
+
+impl<T> std::ops::IndexMut<Point3> for Grid3<T> {
+    fn index_mut(&mut self, p: Point3) -> &mut Self::Output {
+        self.get_mut(p).expect("point out of grid bounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_arithmetic_and_neighbours() {
+        let p = Point3::new(1, -2, 3);
+        assert_eq!(p + p * 2, Point3::new(3, -6, 9));
+        assert_eq!(p - p, Point3::ORIGIN);
+        assert_eq!(-p, Point3::new(-1, 2, -3));
+        assert_eq!(p.manhattan(Point3::ORIGIN), 6);
+        assert_eq!(p.neighbours().count(), 6);
+        assert!(p.neighbours().all(|n| n.manhattan(p) == 1));
+        let (min, max) = (Point3::new(i32::MIN, 0, 0), Point3::new(i32::MAX, 0, 1));
+        assert_eq!(min.manhattan(max), u32::MAX as i64 + 1);
+        assert_eq!(p.neighbours26().count(), 26);
+        assert!(!p.neighbours26().any(|n| n == p));
+        let max = Point3::new(i32::MAX, i32::MAX, 0);
+        assert_eq!(max.neighbours().count(), 4);
+        assert_eq!(max.neighbours26().count(), 11);
+    }
+
+    #[test]
+    fn bounds_of_points() {
+        assert_eq!(Bounds3::of_points([]), None);
+        let bounds =
+            Bounds3::of_points([Point3::new(1, 5, -1), Point3::new(3, 2, 0)]).unwrap();
+        assert_eq!(bounds.min, Point3::new(1, 2, -1));
+        assert_eq!(bounds.max, Point3::new(3, 5, 0));
+        assert_eq!(bounds.size(), (3, 4, 2));
+        assert_eq!(Some(bounds.points().count()), bounds.volume());
+        assert!(bounds.grow(1).unwrap().contains(Point3::new(0, 1, -2)));
+        assert!(!bounds.contains(Point3::new(0, 1, -2)));
+        let max = Point3::new(i32::MAX, i32::MAX, i32::MAX);
+        let everything = Bounds3::new(-max - Point3::new(1, 1, 1), max);
+        assert_eq!(everything.size(), (1 << 32, 1 << 32, 1 << 32));
+        assert_eq!(everything.volume(), None);
+        assert_eq!(Bounds3::of_point(max).grow(1), None);
+    }
+
+    #[test]
+    fn grid_indexing_with_negative_coordinates() {
+        let bounds = Bounds3::new(Point3::new(-1, -1, -1), Point3::new(1, 2, 3));
+        let mut grid = Grid3::new(bounds, 0).unwrap();
+        grid[Point3::new(-1, 2, 3)] = 7;
+        grid[Point3::ORIGIN] += 1;
+        assert_eq!(grid.get(Point3::new(-1, 2, 3)), Some(&7));
+        assert_eq!(grid.get(Point3::new(2, 2, 3)), None);
+        assert_eq!(grid.enumerate().filter(|(_, &v)| v > 0).count(), 2);
+        assert_eq!(grid.neighbours(Point3::new(-1, -1, -1)).count(), 3);
+        let coords = Grid3::from_fn(bounds, |p| p).unwrap();
+        assert!(coords.enumerate().all(|(p, &q)| p == q));
+    }
+
+    #[test]
+    fn grids_of_huge_bounds_fail() {
+        let far = Point3::new(1 << 20, 1 << 20, 1 << 20);
+        let bounds = Bounds3::new(Point3::ORIGIN, far);
+        assert!(Grid3::new(bounds, false).is_err());
+        assert!(Grid3::from_fn(bounds, |_| false).is_err());
+    }
+}
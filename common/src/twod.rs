@@ -1,3 +1,7 @@
+use std::{collections::HashMap, fmt};
+
+use crate::parse::ParseError;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Point {
     pub x: i32,
@@ -5,46 +9,179 @@ pub struct Point {
 }
 
 impl Point {
-    pub fn neighbours(&self, width: i32, height: i32) -> PointNeighbours {
-        PointNeighbours {
-            x: self.x,
-            y: self.y,
-            x_lim: width,
-            y_lim: height,
-            i: 0,
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    /// In `i64`, as the distance of far apart points does not fit into an `i32`
+    pub fn manhattan(self, other: Point) -> i64 {
+        let (dx, dy) = self.abs_diff(other);
+        dx + dy
+    }
+
+    /// In `i64`, see [`Point::manhattan`]
+    pub fn chebyshev(self, other: Point) -> i64 {
+        let (dx, dy) = self.abs_diff(other);
+        dx.max(dy)
+    }
+
+    fn abs_diff(self, other: Point) -> (i64, i64) {
+        (
+            self.x.abs_diff(other.x) as i64,
+            self.y.abs_diff(other.y) as i64,
+        )
+    }
+
+    /// The 4-connected neighbours, not limited to any grid
+    pub fn neighbours(self) -> impl Iterator<Item = Point> {
+        Direction::ALL.into_iter().map(move |d| self + d.offset())
+    }
+
+    /// The 8-connected neighbours, not limited to any grid
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        const OFFSETS: [Point; 8] = [
+            Point::new(-1, -1),
+            Point::new(0, -1),
+            Point::new(1, -1),
+            Point::new(1, 0),
+            Point::new(1, 1),
+            Point::new(0, 1),
+            Point::new(-1, 1),
+            Point::new(-1, 0),
+        ];
+        OFFSETS.into_iter().map(move |off| self + off)
+    }
+}
+
+impl std::ops::Add for Point {
+    type Output = Point;
+    fn add(self, rhs: Point) -> Point {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl std::ops::AddAssign for Point {
+    fn add_assign(&mut self, rhs: Point) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Point {
+    type Output = Point;
+    fn sub(self, rhs: Point) -> Point {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl std::ops::SubAssign for Point {
+    fn sub_assign(&mut self, rhs: Point) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul<i32> for Point {
+    type Output = Point;
+    fn mul(self, rhs: i32) -> Point {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl std::ops::Neg for Point {
+    type Output = Point;
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Points of a horizontal, vertical or diagonal line, including both ends
+pub fn line(p1: Point, p2: Point) -> impl Iterator<Item = Point> {
+    let (step_x, step_y) = ((p2.x - p1.x).signum(), (p2.y - p1.y).signum());
+    let len = std::cmp::max((p2.x - p1.x).abs(), (p2.y - p1.y).abs());
+    (0..=len).map(move |i| Point {
+        x: p1.x + i * step_x,
+        y: p1.y + i * step_y,
+    })
+}
+
+/// A direction on the grid; y grows downwards, like the rows of a [`Grid`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting with `Up`
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The unit step in this direction
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
         }
     }
+
+    /// Rotates by `quarter_turns` clockwise, or counter-clockwise if negative
+    pub fn rotate(self, quarter_turns: i32) -> Self {
+        Self::ALL[(self as i32 + quarter_turns).rem_euclid(4) as usize]
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn reverse(self) -> Self {
+        self.rotate(2)
+    }
 }
 
-pub struct PointNeighbours {
-    x: i32,
-    y: i32,
-    x_lim: i32,
-    y_lim: i32,
-    i: u8,
+/// A grid cell that is written as a single character in text grids
+pub trait GridCell: Sized {
+    fn from_char(ch: char) -> Option<Self>;
+    fn to_char(&self) -> char;
 }
 
-impl Iterator for PointNeighbours {
-    type Item = Point;
-    fn next(&mut self) -> Option<Self::Item> {
-        const OFFSETS: [(i32, i32); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
-        let mut point;
-        loop {
-            if self.i as usize >= OFFSETS.len() {
-                return None;
-            }
-            let off = OFFSETS[self.i as usize];
-            self.i += 1;
-            point = Point {
-                x: self.x + off.0,
-                y: self.y + off.1,
-            };
-            if point.x >= 0 && point.y >= 0 && point.x < self.x_lim && point.y < self.y_lim
-            {
-                break;
-            }
+impl GridCell for char {
+    fn from_char(ch: char) -> Option<Self> {
+        Some(ch)
+    }
+
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+/// `#` is set, `.` is not
+impl GridCell for bool {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        }
+    }
+
+    fn to_char(&self) -> char {
+        match self {
+            true => '#',
+            false => '.',
         }
-        Some(point)
     }
 }
 
@@ -67,18 +204,142 @@ impl<T> std::ops::IndexMut<Point> for Grid<T> {
     }
 }
 
-impl<T> Grid<T>
-where
-    T: Copy,
-{
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Point) -> T) -> Self {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                f(Point {
+                    x: x as i32,
+                    y: y as i32,
+                })
+            })
+            .collect();
+        Self { data, width }
+    }
+
     pub fn height(&self) -> usize {
-        self.data.len() / self.width
+        match self.width {
+            0 => 0,
+            width => self.data.len() / width,
+        }
     }
 
     pub fn width(&self) -> usize {
         self.width
     }
 
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= 0
+            && p.y >= 0
+            && (p.x as usize) < self.width
+            && (p.y as usize) < self.height()
+    }
+
+    /// The 4-connected neighbours of `p` that lie within the grid
+    pub fn neighbours(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours().filter(|&n| self.contains(n))
+    }
+
+    /// The 8-connected neighbours of `p` that lie within the grid
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        p.neighbours8().filter(|&n| self.contains(n))
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        match self.contains(p) {
+            true => Some(&self[p]),
+            false => None,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        self.data.chunks(std::cmp::max(self.width, 1))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.rows().map(move |row| &row[x])
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Iterates over all cells in row-major order, along with their positions
+    pub fn enumerate(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        let width = std::cmp::max(self.width, 1);
+        self.data.iter().enumerate().map(move |(idx, cell)| {
+            let p = Point {
+                x: (idx % width) as i32,
+                y: (idx / width) as i32,
+            };
+            (p, cell)
+        })
+    }
+
+    /// Position of the first cell in row-major order that satisfies `pred`
+    pub fn position(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.enumerate()
+            .find(|(_, cell)| pred(cell))
+            .map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            data: self.data.iter().map(f).collect(),
+            width: self.width,
+        }
+    }
+}
+
+impl<T: GridCell> Grid<T> {
+    /// Parses a text grid, one row per line
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let width = s.lines().next().map_or(0, |ln| ln.chars().count());
+        let mut data = Vec::with_capacity(s.len());
+        for ln in s.lines() {
+            let mut row_len = 0;
+            for (pos, ch) in ln.char_indices() {
+                let cell = T::from_char(ch).ok_or_else(|| {
+                    let span = &ln[pos..pos + ch.len_utf8()];
+                    ParseError::at(s, span, format!("unexpected character '{ch}'"))
+                })?;
+                data.push(cell);
+                row_len += 1;
+            }
+            if row_len != width {
+                let msg = format!("expected {width} cells in each row, found {row_len}");
+                return Err(ParseError::at(s, ln, msg));
+            }
+        }
+        Ok(Self { data, width })
+    }
+}
+
+impl<T: GridCell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Grid<T>
+where
+    T: Copy,
+{
     pub fn ensure_height(&mut self, min_height: usize, fill: T) {
         let min_size = min_height * self.width;
         if self.data.len() < min_size {
@@ -88,17 +349,291 @@ where
 
     pub fn fill_path(&mut self, path: &[Point], item: T) {
         for segment in path.windows(2) {
-            self.fill_line(*segment.get(0).unwrap(), *segment.get(1).unwrap(), item);
+            self.fill_line(segment[0], segment[1], item);
         }
     }
 
     pub fn fill_line(&mut self, p1: Point, p2: Point, item: T) {
-        let mut p = p1;
-        while p != p2 {
+        for p in line(p1, p2) {
             self[p] = item;
-            let (step_x, step_y) = ((p2.x - p1.x).signum(), (p2.y - p1.y).signum());
-            p = Point { x: p.x + step_x, y: p.y + step_y }
         }
-        self[p2] = item;
+    }
+}
+
+/// Inclusive, axis-aligned bounding box
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn of_point(p: Point) -> Self {
+        Self { min: p, max: p }
+    }
+
+    /// Smallest bounds that contain both `self` and `p`
+    #[must_use]
+    pub fn including(self, p: Point) -> Self {
+        Self {
+            min: Point {
+                x: self.min.x.min(p.x),
+                y: self.min.y.min(p.y),
+            },
+            max: Point {
+                x: self.max.x.max(p.x),
+                y: self.max.y.max(p.y),
+            },
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1) as usize
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+    }
+}
+
+const CHUNK_BITS: i32 = 4;
+const CHUNK_SIZE: i32 = 1 << CHUNK_BITS;
+
+/// A grid without bounds that takes any coordinates, including negative ones
+///
+/// Cells are allocated in chunks as they are written to; all other cells have the
+/// default value given on construction.
+#[derive(Clone, Debug)]
+pub struct InfiniteGrid<T> {
+    chunks: HashMap<Point, Box<[T]>>,
+    default: T,
+}
+
+impl<T: Clone + PartialEq> InfiniteGrid<T> {
+    pub fn new(default: T) -> Self {
+        Self {
+            chunks: HashMap::new(),
+            default,
+        }
+    }
+
+    fn chunk_and_index(p: Point) -> (Point, usize) {
+        let chunk = Point {
+            x: p.x >> CHUNK_BITS,
+            y: p.y >> CHUNK_BITS,
+        };
+        let mask = CHUNK_SIZE - 1;
+        let idx = (p.x & mask) + CHUNK_SIZE * (p.y & mask);
+        (chunk, idx as usize)
+    }
+
+    pub fn get(&self, p: Point) -> &T {
+        let (chunk, idx) = Self::chunk_and_index(p);
+        self.chunks.get(&chunk).map_or(&self.default, |c| &c[idx])
+    }
+
+    pub fn set(&mut self, p: Point, value: T) {
+        // Writing the default value does not need a chunk
+        let (chunk, _) = Self::chunk_and_index(p);
+        if value == self.default && !self.chunks.contains_key(&chunk) {
+            return;
+        }
+        self[p] = value;
+    }
+
+    /// Iterates over all cells that differ from the default, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.chunks.iter().flat_map(move |(chunk, cells)| {
+            cells.iter().enumerate().filter_map(move |(idx, cell)| {
+                let p = Point {
+                    x: (chunk.x << CHUNK_BITS) + idx as i32 % CHUNK_SIZE,
+                    y: (chunk.y << CHUNK_BITS) + idx as i32 / CHUNK_SIZE,
+                };
+                (*cell != self.default).then_some((p, cell))
+            })
+        })
+    }
+
+    pub fn fill_path(&mut self, path: &[Point], item: T) {
+        for segment in path.windows(2) {
+            for p in line(segment[0], segment[1]) {
+                self.set(p, item.clone());
+            }
+        }
+    }
+
+    /// Bounding box of all cells that differ from the default
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.iter()
+            .map(|(p, _)| p)
+            .fold(None, |bounds: Option<Bounds>, p| {
+                Some(bounds.map_or(Bounds::of_point(p), |b| b.including(p)))
+            })
+    }
+
+    /// Copies the cells within `bounds` to a dense grid
+    pub fn to_grid(&self, bounds: Bounds) -> Grid<T> {
+        Grid::from_fn(bounds.width(), bounds.height(), |p| {
+            self.get(Point {
+                x: bounds.min.x + p.x,
+                y: bounds.min.y + p.y,
+            })
+            .clone()
+        })
+    }
+}
+
+impl<T: Clone + PartialEq> std::ops::Index<Point> for InfiniteGrid<T> {
+    type Output = T;
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get(p)
+    }
+}
+
+/// Allocates the chunk of `p` if necessary
+impl<T: Clone + PartialEq> std::ops::IndexMut<Point> for InfiniteGrid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        let (chunk, idx) = Self::chunk_and_index(p);
+        let default = &self.default;
+        let cells = self.chunks.entry(chunk).or_insert_with(|| {
+            vec![default.clone(); (CHUNK_SIZE * CHUNK_SIZE) as usize].into_boxed_slice()
+        });
+        &mut cells[idx]
+    }
+}
+
+/// Renders the bounding box of all cells that differ from the default
+impl<T: GridCell + Clone + PartialEq> fmt::Display for InfiniteGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.bounds() {
+            Some(bounds) => write!(f, "{}", self.to_grid(bounds)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "#..\n.#.\n##.";
+
+    #[test]
+    fn parse_and_display_round_trip() {
+        let grid = Grid::<bool>::parse(TEXT).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert!(grid[Point { x: 1, y: 2 }]);
+        assert_eq!(grid.to_string(), TEXT);
+        assert_eq!(Grid::<bool>::parse(&grid.to_string()).unwrap(), grid);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Grid::<bool>::parse("#.\n#x").unwrap_err();
+        assert_eq!((err.line(), err.column()), (2, 2));
+        let err = Grid::<bool>::parse("#.\n#").unwrap_err();
+        assert_eq!(err.message(), "expected 2 cells in each row, found 1");
+    }
+
+    #[test]
+    fn from_fn_rows_and_columns() {
+        let grid = Grid::from_fn(3, 2, |p| p.x + 10 * p.y);
+        assert_eq!(grid.data, vec![0, 1, 2, 10, 11, 12]);
+        assert_eq!(grid.row(1), &[10, 11, 12]);
+        assert_eq!(grid.rows().next_back(), Some(&[10, 11, 12][..]));
+        assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![2, 12]);
+        let column_sums: Vec<i32> = grid.columns().map(|c| c.sum()).collect();
+        assert_eq!(column_sums, vec![10, 12, 14]);
+    }
+
+    #[test]
+    fn enumerate_and_lookup() {
+        let grid = Grid::<char>::parse("ab\ncd").unwrap();
+        let cells: Vec<_> = grid.enumerate().map(|(p, &c)| (p.x, p.y, c)).collect();
+        assert_eq!(
+            cells,
+            vec![(0, 0, 'a'), (1, 0, 'b'), (0, 1, 'c'), (1, 1, 'd')]
+        );
+        assert_eq!(grid.position(|&c| c == 'c'), Some(Point { x: 0, y: 1 }));
+        assert_eq!(grid.get(Point { x: 2, y: 0 }), None);
+        assert_eq!(grid.get(Point { x: -1, y: 0 }), None);
+        assert_eq!(grid.map(|c| c.is_ascii_lowercase()).data, vec![true; 4]);
+    }
+
+    #[test]
+    fn empty_grid() {
+        let grid = Grid::<char>::parse("").unwrap();
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.to_string(), "");
+    }
+
+    #[test]
+    fn infinite_grid_takes_negative_coordinates() {
+        let mut grid = InfiniteGrid::new(false);
+        assert_eq!(grid.bounds(), None);
+        grid.set(Point { x: -20, y: 3 }, true);
+        grid[Point { x: 17, y: -1 }] = true;
+        assert!(grid[Point { x: -20, y: 3 }]);
+        assert!(!grid[Point { x: -19, y: 3 }]);
+        assert!(!grid[Point { x: 1000, y: -1000 }]);
+        assert_eq!(grid.iter().count(), 2);
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(bounds.min, Point { x: -20, y: -1 });
+        assert_eq!(bounds.max, Point { x: 17, y: 3 });
+        assert_eq!((bounds.width(), bounds.height()), (38, 5));
+    }
+
+    #[test]
+    fn infinite_grid_renders_its_bounds() {
+        let mut grid = InfiniteGrid::new('.');
+        grid.set(Point { x: -1, y: -1 }, '#');
+        grid.set(Point { x: 1, y: 0 }, 'o');
+        grid.set(Point { x: 5, y: 5 }, '.');
+        assert_eq!(grid.to_string(), "#..\n..o");
+        assert_eq!(grid.chunks.len(), 2);
+    }
+
+    #[test]
+    fn point_arithmetic_and_distances() {
+        let (a, b) = (Point::new(1, -2), Point::new(-3, 4));
+        assert_eq!(a + b, Point::new(-2, 2));
+        assert_eq!(a - b, Point::new(4, -6));
+        assert_eq!(a * 3, Point::new(3, -6));
+        assert_eq!(-a, Point::new(-1, 2));
+        assert_eq!(a.manhattan(b), 10);
+        assert_eq!(a.chebyshev(b), 6);
+        let (min, max) = (Point::new(i32::MIN, i32::MIN), Point::new(i32::MAX, 0));
+        assert_eq!(min.manhattan(max), u32::MAX as i64 + (1 << 31));
+        assert_eq!(min.chebyshev(max), u32::MAX as i64);
+    }
+
+    #[test]
+    fn directions_turn_and_rotate() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        assert_eq!(Direction::Down.rotate(-7), Direction::Left);
+        let sum = Direction::ALL
+            .iter()
+            .fold(Point::ORIGIN, |acc, d| acc + d.offset());
+        assert_eq!(sum, Point::ORIGIN);
+    }
+
+    #[test]
+    fn neighbours_bounded_and_unbounded() {
+        let p = Point::ORIGIN;
+        assert_eq!(p.neighbours().count(), 4);
+        assert!(p.neighbours().all(|n| n.manhattan(p) == 1));
+        assert_eq!(p.neighbours8().count(), 8);
+        assert!(p.neighbours8().all(|n| n.chebyshev(p) == 1));
+        let grid = Grid::<bool>::parse(TEXT).unwrap();
+        assert_eq!(grid.neighbours(p).count(), 2);
+        assert_eq!(grid.neighbours8(p).count(), 3);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
     }
 }
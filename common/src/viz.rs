@@ -0,0 +1,582 @@
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufWriter, IsTerminal, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::twod::{Bounds, Point};
+
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+const CLEAR_LINE: &str = "\x1b[K";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const RESET: &str = "\x1b[0m";
+
+/// Frame rates that can be given or reached with `+` and `-`, besides 0 for unlimited
+const FPS_RANGE: RangeInclusive<f64> = 0.01..=1000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Default,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    /// The ANSI foreground colour code
+    fn code(self) -> u8 {
+        match self {
+            Color::Default => 39,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+            Color::Gray => 90,
+        }
+    }
+}
+
+/// A character with its colour, making up one cell of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyph {
+    pub ch: char,
+    pub color: Color,
+}
+
+impl Glyph {
+    pub const BLANK: Glyph = Glyph::plain(' ');
+
+    pub const fn new(ch: char, color: Color) -> Self {
+        Self { ch, color }
+    }
+
+    pub const fn plain(ch: char) -> Self {
+        Self::new(ch, Color::Default)
+    }
+}
+
+/// Something that can be drawn as a 2D picture, with y growing downwards
+pub trait Scene {
+    /// The area with content
+    fn bounds(&self) -> Bounds;
+    /// Where the action is; the viewport scrolls to keep it in view
+    fn focus(&self) -> Point;
+    /// The glyph at `p`, which lies within `bounds()`
+    fn glyph(&self, p: Point) -> Glyph;
+}
+
+/// The part of a scene that fits on the screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub origin: Point,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            origin: Point::ORIGIN,
+            width,
+            height,
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds {
+            min: self.origin,
+            max: self.origin + Point::new(self.width as i32 - 1, self.height as i32 - 1),
+        }
+    }
+
+    /// Scrolls as little as possible to keep `focus` away from the edges, without showing
+    /// more than necessary outside of `bounds`
+    pub fn follow(&mut self, focus: Point, bounds: Bounds) {
+        self.origin = Point::new(
+            scroll(
+                self.origin.x,
+                self.width,
+                focus.x,
+                bounds.min.x,
+                bounds.max.x,
+            ),
+            scroll(
+                self.origin.y,
+                self.height,
+                focus.y,
+                bounds.min.y,
+                bounds.max.y,
+            ),
+        );
+    }
+
+    /// Draws the visible part of `scene`, one line per row
+    pub fn render(&self, scene: &impl Scene) -> String {
+        let bounds = scene.bounds();
+        let mut out = String::new();
+        for y in self.origin.y..self.origin.y + self.height as i32 {
+            let mut color = Color::Default;
+            for x in self.origin.x..self.origin.x + self.width as i32 {
+                let p = Point::new(x, y);
+                let glyph = match bounds.contains(p) {
+                    true => scene.glyph(p),
+                    false => Glyph::BLANK,
+                };
+                if glyph.color != color && glyph.ch != ' ' {
+                    color = glyph.color;
+                    out += &format!("\x1b[{}m", color.code());
+                }
+                out.push(glyph.ch);
+            }
+            out += RESET;
+            out += CLEAR_LINE;
+            out += "\r\n";
+        }
+        out
+    }
+}
+
+/// New origin along one axis for a window of `size` cells
+fn scroll(origin: i32, size: usize, focus: i32, min: i32, max: i32) -> i32 {
+    let size = size as i32;
+    if max - min < size {
+        return min;
+    }
+    let margin = size / 4;
+    let origin = origin.max(focus - (size - 1 - margin)).min(focus - margin);
+    origin.clamp(min, max - size + 1)
+}
+
+/// How to animate, given on the command line as `--viz`, `--viz-fps=30`,
+/// `--viz-every=10`, `--viz-size=80x24`, `--viz-paused` and `--viz-record=out.cast`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// Frames per second, unlimited if 0, otherwise within 0.01 and 1000
+    pub fps: f64,
+    /// Only every n-th frame is shown
+    pub every: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Start paused, waiting for a step
+    pub paused: bool,
+    /// Record the frames to this asciicast file
+    pub record: Option<PathBuf>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        let env_size = |name, default| {
+            env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        Self {
+            fps: 30.0,
+            every: 1,
+            width: env_size("COLUMNS", 80),
+            // leave room for the status line
+            height: env_size("LINES", 24).saturating_sub(1),
+            paused: false,
+            record: None,
+        }
+    }
+}
+
+impl Options {
+    /// The options given on the command line, `None` if no `--viz` flag is given
+    pub fn from_args() -> anyhow::Result<Option<Self>> {
+        Self::parse(env::args().skip(1))
+    }
+
+    pub fn parse(
+        args: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> anyhow::Result<Option<Self>> {
+        let mut opts = None;
+        for arg in args {
+            let arg = arg.as_ref();
+            let Some(flag) = arg.strip_prefix("--viz") else {
+                continue;
+            };
+            let o: &mut Options = opts.get_or_insert_with(Options::default);
+            let (name, value) = flag.split_once('=').unwrap_or((flag, ""));
+            let invalid = || anyhow!("Invalid value in {arg}");
+            match name {
+                "" => {}
+                "-paused" => o.paused = true,
+                "-fps" => {
+                    o.fps = value.parse().map_err(|_| invalid())?;
+                    if o.fps != 0.0 && !FPS_RANGE.contains(&o.fps) {
+                        return Err(invalid());
+                    }
+                }
+                "-every" => {
+                    o.every = value.parse().map_err(|_| invalid())?;
+                    if o.every == 0 {
+                        return Err(invalid());
+                    }
+                }
+                "-size" => {
+                    let (w, h) = value.split_once('x').ok_or_else(invalid)?;
+                    o.width = w.parse().map_err(|_| invalid())?;
+                    o.height = h.parse().map_err(|_| invalid())?;
+                }
+                "-record" if !value.is_empty() => o.record = Some(value.into()),
+                _ => bail!("Unknown visualisation flag {arg}"),
+            }
+        }
+        Ok(opts)
+    }
+}
+
+/// Records frames in the asciicast v2 format, which `asciinema play` can replay
+struct Recording {
+    out: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recording {
+    fn create(path: &Path, width: usize, height: usize) -> anyhow::Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Error creating recording {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        writeln!(
+            out,
+            r#"{{"version": 2, "width": {width}, "height": {height}}}"#
+        )?;
+        Ok(Self {
+            out,
+            start: Instant::now(),
+        })
+    }
+
+    fn write(&mut self, data: &str) -> io::Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        let data = serde_json::to_string(data)?;
+        writeln!(self.out, "[{time:.6}, \"o\", {data}]")
+    }
+}
+
+/// Commands typed on stdin, each followed by Enter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Command {
+    /// Empty line: pause when running, one frame ahead when paused
+    Step,
+    /// `c`
+    Continue,
+    /// `q`: stop animating, the simulation goes on
+    Quit,
+    /// `+` and `-` double or halve the frame rate
+    Faster,
+    Slower,
+}
+
+fn spawn_controls() -> Receiver<Command> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let cmd = match line.as_deref().map(str::trim) {
+                Ok("") => Command::Step,
+                Ok("c") => Command::Continue,
+                Ok("q") | Err(_) => Command::Quit,
+                Ok("+") => Command::Faster,
+                Ok("-") => Command::Slower,
+                Ok(_) => continue,
+            };
+            if tx.send(cmd).is_err() || cmd == Command::Quit {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+/// Shows the frames of a simulation one after the other
+///
+/// Errors while drawing stop the animation and are reported by `finish`, so that
+/// simulations can call `frame` without handling them. Quitting with `q` only skips the
+/// remaining frames but key frames.
+pub struct Animator<W: Write> {
+    out: W,
+    opts: Options,
+    viewport: Viewport,
+    controls: Option<Receiver<Command>>,
+    recording: Option<Recording>,
+    frame: usize,
+    last_shown: Option<Instant>,
+    quit: bool,
+    error: Option<anyhow::Error>,
+}
+
+impl Animator<io::Stdout> {
+    /// Animates on the terminal, with controls on stdin if it is a terminal
+    pub fn stdout(opts: Options) -> anyhow::Result<Self> {
+        let mut animator = Self::new(io::stdout(), opts)?;
+        if io::stdin().is_terminal() {
+            animator.controls = Some(spawn_controls());
+        }
+        Ok(animator)
+    }
+}
+
+impl<W: Write> Animator<W> {
+    pub fn new(out: W, opts: Options) -> anyhow::Result<Self> {
+        let recording = match &opts.record {
+            Some(path) => Some(Recording::create(path, opts.width, opts.height + 1)?),
+            None => None,
+        };
+        let mut animator = Self {
+            out,
+            viewport: Viewport::new(opts.width, opts.height),
+            opts,
+            controls: None,
+            recording,
+            frame: 0,
+            last_shown: None,
+            quit: false,
+            error: None,
+        };
+        animator.emit(&format!("{CLEAR_SCREEN}{HIDE_CURSOR}"));
+        Ok(animator)
+    }
+
+    /// Shows the next frame, unless it is skipped
+    pub fn frame(&mut self, scene: &impl Scene, caption: &str) {
+        self.frame += 1;
+        if self.quit || !(self.frame - 1).is_multiple_of(self.opts.every) {
+            return;
+        }
+        self.show(scene, caption);
+        self.wait();
+    }
+
+    /// Shows the next frame even if frames are skipped or the animation was quit, e.g.,
+    /// to show a final state
+    pub fn key_frame(&mut self, scene: &impl Scene, caption: &str) {
+        self.frame += 1;
+        self.show(scene, caption);
+        if !self.quit {
+            self.wait();
+        }
+    }
+
+    fn show(&mut self, scene: &impl Scene, caption: &str) {
+        self.viewport.follow(scene.focus(), scene.bounds());
+        let status = match (self.opts.paused, self.controls.is_some()) {
+            (true, _) => "paused; Enter: step, c: continue, q: quit",
+            (false, true) => "Enter: pause, +/-: speed, q: quit",
+            (false, false) => "",
+        };
+        let frame = format!(
+            "{CURSOR_HOME}{}frame {} {caption}  {status}{CLEAR_LINE}",
+            self.viewport.render(scene),
+            self.frame
+        );
+        self.emit(&frame);
+    }
+
+    /// Keeps the frame rate and handles the controls
+    fn wait(&mut self) {
+        if self.opts.paused && self.controls.is_none() {
+            // nobody could resume
+            self.opts.paused = false;
+        }
+        loop {
+            let cmd = match (&self.controls, self.opts.paused) {
+                (None, _) => None,
+                (Some(rx), true) => Some(rx.recv().unwrap_or(Command::Quit)),
+                (Some(rx), false) => match rx.try_recv() {
+                    Ok(cmd) => Some(cmd),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => Some(Command::Quit),
+                },
+            };
+            match cmd {
+                Some(Command::Step) if self.opts.paused => break,
+                Some(Command::Step) => self.opts.paused = true,
+                Some(Command::Continue) => self.opts.paused = false,
+                Some(Command::Quit) => {
+                    self.quit = true;
+                    self.controls = None;
+                    return;
+                }
+                Some(Command::Faster) => self.scale_fps(2.0),
+                Some(Command::Slower) => self.scale_fps(0.5),
+                None if self.opts.paused => continue,
+                None => break,
+            }
+        }
+        // no interval for 0, i.e., unlimited, nor for rates that skipped `Options::parse`
+        if let Ok(interval) = Duration::try_from_secs_f64(1.0 / self.opts.fps) {
+            if let Some(elapsed) = self.last_shown.map(|t| t.elapsed()) {
+                thread::sleep(interval.saturating_sub(elapsed));
+            }
+        }
+        self.last_shown = Some(Instant::now());
+    }
+
+    /// Keeps the frame rate within [`FPS_RANGE`], or unlimited
+    fn scale_fps(&mut self, factor: f64) {
+        if self.opts.fps > 0.0 {
+            let (min, max) = FPS_RANGE.into_inner();
+            self.opts.fps = (self.opts.fps * factor).clamp(min, max);
+        }
+    }
+
+    fn emit(&mut self, data: &str) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut res = self
+            .out
+            .write_all(data.as_bytes())
+            .and_then(|()| self.out.flush());
+        if let (Ok(()), Some(rec)) = (&res, &mut self.recording) {
+            res = rec.write(data);
+        }
+        if let Err(e) = res {
+            self.error = Some(e.into());
+            self.quit = true;
+        }
+    }
+
+    /// Restores the terminal
+    pub fn finish(mut self) -> anyhow::Result<()> {
+        self.emit(&format!("{RESET}{SHOW_CURSOR}\r\n"));
+        if let Some(mut rec) = self.recording.take() {
+            rec.out.flush().context("Error writing recording")?;
+        }
+        match self.error {
+            Some(e) => Err(e.context("Error animating")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A diagonal line of `#`, the focus on its last point
+    struct Diagonal(i32);
+
+    impl Scene for Diagonal {
+        fn bounds(&self) -> Bounds {
+            Bounds {
+                min: Point::ORIGIN,
+                max: Point::new(self.0 - 1, self.0 - 1),
+            }
+        }
+
+        fn focus(&self) -> Point {
+            Point::new(self.0 - 1, self.0 - 1)
+        }
+
+        fn glyph(&self, p: Point) -> Glyph {
+            match p.x == p.y {
+                true => Glyph::new('#', Color::Red),
+                false => Glyph::plain('.'),
+            }
+        }
+    }
+
+    #[test]
+    fn viewport_follows_focus_within_bounds() {
+        let bounds = Bounds {
+            min: Point::new(-10, 0),
+            max: Point::new(100, 5),
+        };
+        let mut vp = Viewport::new(20, 10);
+        vp.follow(Point::new(0, 3), bounds);
+        assert_eq!(vp.origin, Point::new(-5, 0));
+        vp.follow(Point::new(40, 3), bounds);
+        assert_eq!(vp.origin, Point::new(26, 0));
+        // small moves do not scroll
+        vp.follow(Point::new(35, 3), bounds);
+        assert_eq!(vp.origin, Point::new(26, 0));
+        vp.follow(Point::new(100, 3), bounds);
+        assert_eq!(vp.origin, Point::new(81, 0));
+        vp.follow(Point::new(-10, 3), bounds);
+        assert_eq!(vp.origin, Point::new(-10, 0));
+    }
+
+    #[test]
+    fn render_with_colours() {
+        let vp = Viewport::new(4, 2);
+        let strip = |s: String| s.replace(RESET, "").replace(CLEAR_LINE, "");
+        assert_eq!(
+            strip(vp.render(&Diagonal(3))),
+            "\x1b[31m#\x1b[39m.. \r\n.\x1b[31m#\x1b[39m. \r\n"
+        );
+    }
+
+    #[test]
+    fn parse_options() {
+        assert_eq!(Options::parse(["real"]).unwrap(), None);
+        let opts = Options::parse(["--viz-fps=5", "real", "--viz-size=40x12"])
+            .unwrap()
+            .unwrap();
+        assert_eq!((opts.fps, opts.width, opts.height), (5.0, 40, 12));
+        assert_eq!(Options::parse(["--viz"]).unwrap().unwrap().every, 1);
+        assert!(Options::parse(["--viz-every=0"]).is_err());
+        assert_eq!(Options::parse(["--viz-fps=0"]).unwrap().unwrap().fps, 0.0);
+        for fps in ["1e-320", "-1", "inf", "NaN", "1e9"] {
+            assert!(
+                Options::parse([format!("--viz-fps={fps}")]).is_err(),
+                "{fps}"
+            );
+        }
+        assert!(Options::parse(["--viz-fast"]).is_err());
+    }
+
+    #[test]
+    fn animate_and_record() {
+        let path =
+            env::temp_dir().join(format!("common-viz-{}.cast", std::process::id()));
+        let opts = Options {
+            fps: 0.0,
+            every: 2,
+            width: 3,
+            height: 3,
+            paused: true,
+            record: Some(path.clone()),
+        };
+        let mut out = vec![];
+        let mut animator = Animator::new(&mut out, opts).unwrap();
+        for n in 1..=4 {
+            animator.frame(&Diagonal(n), "");
+        }
+        animator.key_frame(&Diagonal(5), "done \"\\\u{1}\t\u{1f980}");
+        animator.finish().unwrap();
+        let shown = String::from_utf8(out).unwrap();
+        assert_eq!(shown.matches(CURSOR_HOME).count(), 3);
+        assert!(shown.contains("frame 5 done"));
+        let recording = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let lines: Vec<_> = recording.lines().collect();
+        assert_eq!(lines[0], r#"{"version": 2, "width": 3, "height": 4}"#);
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1].ends_with(r#", "o", "\u001b[2J\u001b[?25l"]"#));
+        let (_, kind, data): (f64, String, String) =
+            serde_json::from_str(lines[4]).unwrap();
+        assert_eq!(kind, "o");
+        assert!(data.contains("frame 5 done \"\\\u{1}\t\u{1f980}"));
+    }
+}